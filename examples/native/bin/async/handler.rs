@@ -43,6 +43,7 @@ impl CommandHandler<DefaultConfig> for AsyncHandler {
         &self,
         id: &str,
         args: &[&str],
+        cancel: &nut_shell::CancelToken,
     ) -> Result<Response<DefaultConfig>, CliError> {
         match id {
             "async_delay" => {
@@ -94,9 +95,10 @@ impl CommandHandler<DefaultConfig> for AsyncHandler {
                 let mut msg = heapless::String::<256>::new();
                 write!(msg, "Starting async computation...\r\n").ok();
 
-                // Simulate some async work with periodic delays
+                // Simulate some async work with periodic delays (cancellable between steps)
                 for i in 1..=3 {
                     sleep(Duration::from_millis(300)).await;
+                    cancel.check()?;
                     write!(msg, "Step {}/3 completed\r\n", i).ok();
                 }
 
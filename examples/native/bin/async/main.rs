@@ -19,7 +19,7 @@ mod tree;
 
 use handler::AsyncHandler;
 use native_examples::{ExampleAccessLevel, RawModeGuard, StdioCharIo};
-use nut_shell::{config::DefaultConfig, shell::Shell};
+use nut_shell::{CliState, config::DefaultConfig, shell::Shell};
 use std::io::{self as stdio, Read};
 use tree::ROOT;
 
@@ -51,7 +51,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("  fetch http://example.com - Simulated async HTTP fetch");
     println!("  compute       - Simulated async computation");
     println!();
-    println!("Type '?' for help, 'logout' to exit (with auth), or Ctrl+C to quit.\n");
+    println!("Type '?' for help, 'exit' or Ctrl+D to quit. Ctrl+C discards the current line.\n");
 
     // Enable raw terminal mode
     let _raw_mode_guard = RawModeGuard::new()?;
@@ -86,17 +86,20 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         match stdin_handle.read(&mut buf) {
             Ok(0) => break, // EOF (Ctrl+D on Unix)
             Ok(_) => {
-                // Handle Ctrl+C gracefully
-                if buf[0] == 0x03 {
+                // Ctrl+D (0x04) exits; Ctrl+C (0x03) is handled by the shell
+                if buf[0] == 0x04 {
                     println!("\r\n");
                     break;
                 }
 
-                let c = buf[0] as char;
-
-                // Process character asynchronously
+                // Process byte asynchronously (decodes UTF-8)
                 // This allows async commands to run without blocking the shell
-                shell.process_char_async(c).await.ok();
+                shell.process_byte_async(buf[0]).await.ok();
+
+                // `exit`/`quit` deactivates the shell - end the session
+                if shell.state() == CliState::Inactive {
+                    break;
+                }
             }
             Err(e) => {
                 // Restore terminal before printing error
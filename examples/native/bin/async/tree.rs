@@ -7,96 +7,87 @@ use nut_shell::tree::{CommandKind, CommandMeta, Directory, Node};
 // Async Commands
 // =============================================================================
 
-pub const CMD_DELAY: CommandMeta<ExampleAccessLevel> = CommandMeta {
-    id: "async_delay",
-    name: "delay",
-    description: "Async delay for N seconds (max 30)",
-    access_level: ExampleAccessLevel::Guest,
-    kind: CommandKind::Async,
-    min_args: 1,
-    max_args: 1,
-};
+pub const CMD_DELAY: CommandMeta<ExampleAccessLevel> = CommandMeta::new(
+    "async_delay",
+    "delay",
+    ExampleAccessLevel::Guest,
+    CommandKind::Async,
+)
+.with_description("Async delay for N seconds (max 30)")
+.with_args(1, 1);
 
-pub const CMD_FETCH: CommandMeta<ExampleAccessLevel> = CommandMeta {
-    id: "async_fetch",
-    name: "fetch",
-    description: "Simulate async HTTP fetch",
-    access_level: ExampleAccessLevel::User,
-    kind: CommandKind::Async,
-    min_args: 1,
-    max_args: 1,
-};
+pub const CMD_FETCH: CommandMeta<ExampleAccessLevel> = CommandMeta::new(
+    "async_fetch",
+    "fetch",
+    ExampleAccessLevel::User,
+    CommandKind::Async,
+)
+.with_description("Simulate async HTTP fetch")
+.with_args(1, 1);
 
-pub const CMD_COMPUTE: CommandMeta<ExampleAccessLevel> = CommandMeta {
-    id: "async_compute",
-    name: "compute",
-    description: "Simulate async computation",
-    access_level: ExampleAccessLevel::User,
-    kind: CommandKind::Async,
-    min_args: 0,
-    max_args: 0,
-};
+pub const CMD_COMPUTE: CommandMeta<ExampleAccessLevel> = CommandMeta::new(
+    "async_compute",
+    "compute",
+    ExampleAccessLevel::User,
+    CommandKind::Async,
+)
+.with_description("Simulate async computation");
 
-const ASYNC_DIR: Directory<ExampleAccessLevel> = Directory {
-    name: "async",
-    children: &[
+const ASYNC_DIR: Directory<ExampleAccessLevel> = Directory::new(
+    "async",
+    &[
         Node::Command(&CMD_DELAY),
         Node::Command(&CMD_FETCH),
         Node::Command(&CMD_COMPUTE),
     ],
-    access_level: ExampleAccessLevel::Guest,
-};
+    ExampleAccessLevel::Guest,
+);
 
 // =============================================================================
 // Sync Commands
 // =============================================================================
 
-pub const CMD_ECHO: CommandMeta<ExampleAccessLevel> = CommandMeta {
-    id: "sync_echo",
-    name: "echo",
-    description: "Echo arguments back",
-    access_level: ExampleAccessLevel::Guest,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 16,
-};
+pub const CMD_ECHO: CommandMeta<ExampleAccessLevel> = CommandMeta::new(
+    "sync_echo",
+    "echo",
+    ExampleAccessLevel::Guest,
+    CommandKind::Sync,
+)
+.with_description("Echo arguments back")
+.with_args(0, 16);
 
-pub const CMD_INFO: CommandMeta<ExampleAccessLevel> = CommandMeta {
-    id: "sync_info",
-    name: "info",
-    description: "Show system information",
-    access_level: ExampleAccessLevel::Guest,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
+pub const CMD_INFO: CommandMeta<ExampleAccessLevel> = CommandMeta::new(
+    "sync_info",
+    "info",
+    ExampleAccessLevel::Guest,
+    CommandKind::Sync,
+)
+.with_description("Show system information");
 
-pub const CMD_REBOOT: CommandMeta<ExampleAccessLevel> = CommandMeta {
-    id: "sync_reboot",
-    name: "reboot",
-    description: "Reboot the system (simulated)",
-    access_level: ExampleAccessLevel::Admin,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
+pub const CMD_REBOOT: CommandMeta<ExampleAccessLevel> = CommandMeta::new(
+    "sync_reboot",
+    "reboot",
+    ExampleAccessLevel::Admin,
+    CommandKind::Sync,
+)
+.with_description("Reboot the system (simulated)");
 
-const SYSTEM_DIR: Directory<ExampleAccessLevel> = Directory {
-    name: "system",
-    children: &[Node::Command(&CMD_REBOOT), Node::Command(&CMD_INFO)],
-    access_level: ExampleAccessLevel::Guest,
-};
+const SYSTEM_DIR: Directory<ExampleAccessLevel> = Directory::new(
+    "system",
+    &[Node::Command(&CMD_REBOOT), Node::Command(&CMD_INFO)],
+    ExampleAccessLevel::Guest,
+);
 
 // =============================================================================
 // Root Directory
 // =============================================================================
 
-pub const ROOT: Directory<ExampleAccessLevel> = Directory {
-    name: "/",
-    children: &[
+pub const ROOT: Directory<ExampleAccessLevel> = Directory::new(
+    "/",
+    &[
         Node::Directory(&SYSTEM_DIR),
         Node::Directory(&ASYNC_DIR),
         Node::Command(&CMD_ECHO),
     ],
-    access_level: ExampleAccessLevel::Guest,
-};
+    ExampleAccessLevel::Guest,
+);
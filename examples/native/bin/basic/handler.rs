@@ -2,7 +2,10 @@
 
 use core::fmt::Write;
 use nut_shell::{
-    CliError, config::DefaultConfig, response::Response, shell::handler::CommandHandler,
+    CliError,
+    config::DefaultConfig,
+    response::{Response, Table},
+    shell::handler::CommandHandler,
 };
 
 pub struct ExampleHandler;
@@ -12,11 +15,14 @@ impl CommandHandler<DefaultConfig> for ExampleHandler {
         match id {
             "system_reboot" => Ok(Response::success("System rebooting...\r\nGoodbye!").indented()),
             "system_status" => {
+                let mut table: Table<'_, 2, 3> = Table::new();
+                table.push_row(["CPU Usage:", "23%"])?;
+                table.push_row(["Memory:", "45% used"])?;
+                table.push_row(["Uptime:", "42 hours"])?;
+                let rows = table.render::<DefaultConfig, 192>()?;
+
                 let mut msg = heapless::String::<256>::new();
-                write!(msg, "System Status:\r\n").ok();
-                write!(msg, "  CPU Usage: 23%\r\n").ok();
-                write!(msg, "  Memory: 45% used\r\n").ok();
-                write!(msg, "  Uptime: 42 hours").ok();
+                write!(msg, "System Status:\r\n{}", rows).ok();
                 Ok(Response::success(&msg).indented())
             }
             "system_version" => Ok(Response::success(
@@ -60,6 +66,7 @@ impl CommandHandler<DefaultConfig> for ExampleHandler {
         &self,
         id: &str,
         _args: &[&str],
+        _cancel: &nut_shell::CancelToken,
     ) -> Result<Response<DefaultConfig>, CliError> {
         // This example doesn't use async commands
         let mut msg = heapless::String::<128>::new();
@@ -23,7 +23,7 @@ mod tree;
 
 use handler::ExampleHandler;
 use native_examples::{ExampleAccessLevel, RawModeGuard, StdioCharIo};
-use nut_shell::{config::DefaultConfig, shell::Shell};
+use nut_shell::{CliState, config::DefaultConfig, shell::Shell};
 use std::io::{self as stdio, Read};
 use tree::ROOT;
 
@@ -46,7 +46,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!();
     }
 
-    println!("Type '?' for help, 'logout' to exit (with auth), or Ctrl+C to quit.\n");
+    println!("Type '?' for help, 'exit' or Ctrl+D to quit. Ctrl+C discards the current line.\n");
 
     // Enable raw terminal mode to resemble embedded target behavior:
     // - No local echo (shell controls all echoing for password masking)
@@ -78,7 +78,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // This pattern resembles embedded target usage:
     // - Embedded: Poll UART RX buffer for characters
     // - Native: Poll stdin for characters
-    // - Both: Feed characters to shell.process_char() one at a time
+    // - Both: Feed bytes to shell.process_byte() one at a time (UTF-8 decoded by the shell)
     // - Shell controls all output (including echo and password masking)
     let stdin = stdio::stdin();
     let mut stdin_handle = stdin.lock();
@@ -89,20 +89,21 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         match stdin_handle.read(&mut buf) {
             Ok(0) => break, // EOF (Ctrl+D on Unix)
             Ok(_) => {
-                // In raw mode, Ctrl+C becomes character 0x03 instead of sending SIGINT.
-                // For this native example, we detect it and exit gracefully.
-                // On embedded targets, you might:
-                // - Ignore Ctrl+C entirely (no concept of "interrupt")
-                // - Use it as a special command (e.g., abort current operation)
-                // - Implement different exit mechanisms (reset button, watchdog, etc.)
-                if buf[0] == 0x03 {
+                // In raw mode, Ctrl+C (0x03) reaches the shell, which discards the line.
+                // Ctrl+D (0x04) no longer signals EOF, so we detect it and exit gracefully.
+                // On embedded targets, you might instead rely on `exit` or a reset button.
+                if buf[0] == 0x04 {
                     println!("\r\n"); // Move to new line before exit
                     break;
                 }
 
-                let c = buf[0] as char;
-                // Feed character to shell (shell controls echoing)
-                shell.process_char(c)?;
+                // Feed raw byte to shell (decodes UTF-8, shell controls echoing)
+                shell.process_byte(buf[0])?;
+
+                // `exit`/`quit` deactivates the shell - end the session
+                if shell.state() == CliState::Inactive {
+                    break;
+                }
             }
             Err(e) => {
                 // Restore terminal before printing error
@@ -7,114 +7,99 @@ use nut_shell::tree::{CommandKind, CommandMeta, Directory, Node};
 // System Commands
 // =============================================================================
 
-pub const CMD_REBOOT: CommandMeta<ExampleAccessLevel> = CommandMeta {
-    id: "system_reboot",
-    name: "reboot",
-    description: "Reboot the system (simulated)",
-    access_level: ExampleAccessLevel::Admin,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
+pub const CMD_REBOOT: CommandMeta<ExampleAccessLevel> = CommandMeta::new(
+    "system_reboot",
+    "reboot",
+    ExampleAccessLevel::Admin,
+    CommandKind::Sync,
+)
+.with_description("Reboot the system (simulated)");
 
-pub const CMD_STATUS: CommandMeta<ExampleAccessLevel> = CommandMeta {
-    id: "system_status",
-    name: "status",
-    description: "Show system status",
-    access_level: ExampleAccessLevel::User,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
+pub const CMD_STATUS: CommandMeta<ExampleAccessLevel> = CommandMeta::new(
+    "system_status",
+    "status",
+    ExampleAccessLevel::User,
+    CommandKind::Sync,
+)
+.with_description("Show system status");
 
-pub const CMD_VERSION: CommandMeta<ExampleAccessLevel> = CommandMeta {
-    id: "system_version",
-    name: "version",
-    description: "Show version information",
-    access_level: ExampleAccessLevel::Guest,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
+pub const CMD_VERSION: CommandMeta<ExampleAccessLevel> = CommandMeta::new(
+    "system_version",
+    "version",
+    ExampleAccessLevel::Guest,
+    CommandKind::Sync,
+)
+.with_description("Show version information");
 
-const SYSTEM_DIR: Directory<ExampleAccessLevel> = Directory {
-    name: "system",
-    children: &[
+const SYSTEM_DIR: Directory<ExampleAccessLevel> = Directory::new(
+    "system",
+    &[
         Node::Command(&CMD_REBOOT),
         Node::Command(&CMD_STATUS),
         Node::Command(&CMD_VERSION),
     ],
-    access_level: ExampleAccessLevel::Guest,
-};
+    ExampleAccessLevel::Guest,
+);
 
 // =============================================================================
 // Config Commands
 // =============================================================================
 
-pub const CMD_CONFIG_GET: CommandMeta<ExampleAccessLevel> = CommandMeta {
-    id: "config_get",
-    name: "get",
-    description: "Get configuration value",
-    access_level: ExampleAccessLevel::User,
-    kind: CommandKind::Sync,
-    min_args: 1,
-    max_args: 1,
-};
+pub const CMD_CONFIG_GET: CommandMeta<ExampleAccessLevel> = CommandMeta::new(
+    "config_get",
+    "get",
+    ExampleAccessLevel::User,
+    CommandKind::Sync,
+)
+.with_description("Get configuration value")
+.with_args(1, 1);
 
-pub const CMD_CONFIG_SET: CommandMeta<ExampleAccessLevel> = CommandMeta {
-    id: "config_set",
-    name: "set",
-    description: "Set configuration value",
-    access_level: ExampleAccessLevel::Admin,
-    kind: CommandKind::Sync,
-    min_args: 2,
-    max_args: 2,
-};
+pub const CMD_CONFIG_SET: CommandMeta<ExampleAccessLevel> = CommandMeta::new(
+    "config_set",
+    "set",
+    ExampleAccessLevel::Admin,
+    CommandKind::Sync,
+)
+.with_description("Set configuration value")
+.with_args(2, 2);
 
-const CONFIG_DIR: Directory<ExampleAccessLevel> = Directory {
-    name: "config",
-    children: &[
+const CONFIG_DIR: Directory<ExampleAccessLevel> = Directory::new(
+    "config",
+    &[
         Node::Command(&CMD_CONFIG_GET),
         Node::Command(&CMD_CONFIG_SET),
     ],
-    access_level: ExampleAccessLevel::User,
-};
+    ExampleAccessLevel::User,
+);
 
 // =============================================================================
 // Root-Level Commands
 // =============================================================================
 
-pub const CMD_ECHO: CommandMeta<ExampleAccessLevel> = CommandMeta {
-    id: "echo",
-    name: "echo",
-    description: "Echo arguments back",
-    access_level: ExampleAccessLevel::Guest,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 16,
-};
+pub const CMD_ECHO: CommandMeta<ExampleAccessLevel> =
+    CommandMeta::new("echo", "echo", ExampleAccessLevel::Guest, CommandKind::Sync)
+        .with_description("Echo arguments back")
+        .with_args(0, 16);
 
-pub const CMD_UPTIME: CommandMeta<ExampleAccessLevel> = CommandMeta {
-    id: "uptime",
-    name: "uptime",
-    description: "Show system uptime (simulated)",
-    access_level: ExampleAccessLevel::Guest,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
+pub const CMD_UPTIME: CommandMeta<ExampleAccessLevel> = CommandMeta::new(
+    "uptime",
+    "uptime",
+    ExampleAccessLevel::Guest,
+    CommandKind::Sync,
+)
+.with_description("Show system uptime (simulated)");
 
 // =============================================================================
 // Root Directory
 // =============================================================================
 
-pub const ROOT: Directory<ExampleAccessLevel> = Directory {
-    name: "/",
-    children: &[
+pub const ROOT: Directory<ExampleAccessLevel> = Directory::new(
+    "/",
+    &[
         Node::Directory(&SYSTEM_DIR),
         Node::Directory(&CONFIG_DIR),
         Node::Command(&CMD_ECHO),
         Node::Command(&CMD_UPTIME),
     ],
-    access_level: ExampleAccessLevel::Guest,
-};
+    ExampleAccessLevel::Guest,
+);
@@ -80,6 +80,7 @@ impl CommandHandler<DefaultConfig> for PicoHandler {
         &self,
         _id: &str,
         _args: &[&str],
+        _cancel: &nut_shell::CancelToken,
     ) -> Result<Response<DefaultConfig>, CliError> {
         // basic example is synchronous-only, no async commands
         Err(CliError::CommandNotFound)
@@ -7,19 +7,17 @@ use rp_pico_examples::{PicoAccessLevel, hw_commands, system_commands};
 // System Commands
 // =============================================================================
 
-pub const CMD_INFO: CommandMeta<PicoAccessLevel> = CommandMeta {
-    id: "system_info",
-    name: "info",
-    description: "Show device information",
-    access_level: PicoAccessLevel::User,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
+pub const CMD_INFO: CommandMeta<PicoAccessLevel> = CommandMeta::new(
+    "system_info",
+    "info",
+    PicoAccessLevel::User,
+    CommandKind::Sync,
+)
+.with_description("Show device information");
 
-const SYSTEM_DIR: Directory<PicoAccessLevel> = Directory {
-    name: "system",
-    children: &[
+const SYSTEM_DIR: Directory<PicoAccessLevel> = Directory::new(
+    "system",
+    &[
         Node::Command(&CMD_INFO),
         Node::Command(&system_commands::CMD_UPTIME),
         Node::Command(&system_commands::CMD_MEMINFO),
@@ -27,27 +25,21 @@ const SYSTEM_DIR: Directory<PicoAccessLevel> = Directory {
         Node::Command(&system_commands::CMD_FLASH),
         Node::Command(&system_commands::CMD_CRASH),
     ],
-    access_level: PicoAccessLevel::User,
-};
+    PicoAccessLevel::User,
+);
 
 // =============================================================================
 // Hardware Commands
 // =============================================================================
 
-pub const CMD_TEMP: CommandMeta<PicoAccessLevel> = CommandMeta {
-    id: "hw_temp",
-    name: "temp",
-    description: "Read internal temperature sensor",
-    access_level: PicoAccessLevel::User,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
+pub const CMD_TEMP: CommandMeta<PicoAccessLevel> =
+    CommandMeta::new("hw_temp", "temp", PicoAccessLevel::User, CommandKind::Sync)
+        .with_description("Read internal temperature sensor");
 
 // Hardware read commands
-const HARDWARE_GET_DIR: Directory<PicoAccessLevel> = Directory {
-    name: "get",
-    children: &[
+const HARDWARE_GET_DIR: Directory<PicoAccessLevel> = Directory::new(
+    "get",
+    &[
         Node::Command(&CMD_TEMP),
         Node::Command(&hw_commands::CMD_CHIPID),
         Node::Command(&hw_commands::CMD_CLOCKS),
@@ -55,41 +47,33 @@ const HARDWARE_GET_DIR: Directory<PicoAccessLevel> = Directory {
         Node::Command(&hw_commands::CMD_BOOTREASON),
         Node::Command(&hw_commands::CMD_GPIO),
     ],
-    access_level: PicoAccessLevel::User,
-};
+    PicoAccessLevel::User,
+);
 
 // Hardware write/control commands
-pub const CMD_LED: CommandMeta<PicoAccessLevel> = CommandMeta {
-    id: "hw_led",
-    name: "led",
-    description: "Control onboard LED (on/off)",
-    access_level: PicoAccessLevel::User,
-    kind: CommandKind::Sync,
-    min_args: 1,
-    max_args: 1,
-};
+pub const CMD_LED: CommandMeta<PicoAccessLevel> =
+    CommandMeta::new("hw_led", "led", PicoAccessLevel::User, CommandKind::Sync)
+        .with_description("Control onboard LED (on/off)")
+        .with_args(1, 1);
 
-const HARDWARE_SET_DIR: Directory<PicoAccessLevel> = Directory {
-    name: "set",
-    children: &[Node::Command(&CMD_LED)],
-    access_level: PicoAccessLevel::User,
-};
+const HARDWARE_SET_DIR: Directory<PicoAccessLevel> =
+    Directory::new("set", &[Node::Command(&CMD_LED)], PicoAccessLevel::User);
 
-const HARDWARE_DIR: Directory<PicoAccessLevel> = Directory {
-    name: "hardware",
-    children: &[
+const HARDWARE_DIR: Directory<PicoAccessLevel> = Directory::new(
+    "hardware",
+    &[
         Node::Directory(&HARDWARE_GET_DIR),
         Node::Directory(&HARDWARE_SET_DIR),
     ],
-    access_level: PicoAccessLevel::User,
-};
+    PicoAccessLevel::User,
+);
 
 // =============================================================================
 // Root Directory
 // =============================================================================
 
-pub const ROOT: Directory<PicoAccessLevel> = Directory {
-    name: "/",
-    children: &[Node::Directory(&SYSTEM_DIR), Node::Directory(&HARDWARE_DIR)],
-    access_level: PicoAccessLevel::User,
-};
+pub const ROOT: Directory<PicoAccessLevel> = Directory::new(
+    "/",
+    &[Node::Directory(&SYSTEM_DIR), Node::Directory(&HARDWARE_DIR)],
+    PicoAccessLevel::User,
+);
@@ -89,6 +89,7 @@ impl CommandHandler<DefaultConfig> for PicoHandler {
         &self,
         id: &str,
         args: &[&str],
+        _cancel: &nut_shell::CancelToken,
     ) -> Result<Response<DefaultConfig>, CliError> {
         match id {
             "system_delay" => {
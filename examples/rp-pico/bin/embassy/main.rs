@@ -10,7 +10,7 @@
 //!
 //! # Features
 //! - Embassy async runtime
-//! - Async command execution with `process_char_async()`
+//! - Async command execution with `process_byte_async()` (UTF-8 decoded by the shell)
 //! - Buffered USB I/O (deferred flush pattern)
 //! - Async delay command demonstration
 
@@ -20,11 +20,9 @@
 mod handler;
 mod hw_setup;
 mod hw_state;
-mod io;
 mod tasks;
 mod tree;
 
-use core::cell::RefCell;
 use embassy_executor::Spawner;
 use embassy_rp::{
     peripherals::USB,
@@ -35,16 +33,14 @@ use embassy_sync::channel::Channel;
 use embassy_time::{Duration, Timer};
 use embassy_usb::class::cdc_acm::{CdcAcmClass, State};
 use embassy_usb::{Builder, Config};
-use heapless;
 use panic_halt as _;
 use static_cell::StaticCell;
 
-use nut_shell::{config::DefaultConfig, shell::Shell};
+use nut_shell::{config::DefaultConfig, io::DeferredIo, shell::Shell};
 
 use rp_pico_examples::{PicoAccessLevel, init_boot_time, init_chip_id, init_reset_reason};
 
 use crate::handler::{LedCommand, PicoHandler};
-use crate::io::BufferedCharIo;
 use crate::tree::ROOT;
 
 // Bind USB interrupt handler
@@ -62,13 +58,8 @@ async fn shell_task(
     mut usb_class: CdcAcmClass<'static, Driver<'static, USB>>,
     led_channel: &'static Channel<ThreadModeRawMutex, LedCommand, 1>,
 ) {
-    // Create output buffer wrapped in RefCell for interior mutability
-    static OUTPUT_BUFFER: StaticCell<RefCell<heapless::Vec<u8, 512>>> = StaticCell::new();
-    let output_buffer = OUTPUT_BUFFER.init(RefCell::new(heapless::Vec::new()));
-
-    // Create buffered I/O (we'll create two references to the same buffer)
-    let io = BufferedCharIo::new(output_buffer);
-    let io_flush = BufferedCharIo::new(output_buffer); // Second reference for flushing
+    // Buffered I/O: output stays in memory until flushed to USB (deferred flush pattern)
+    let io = DeferredIo::<512>::new();
 
     // Create handler
     let handler = PicoHandler { led_channel };
@@ -79,11 +70,11 @@ async fn shell_task(
 
     // Create shell (with or without authentication based on feature flag)
     #[cfg(feature = "authentication")]
-    let mut shell: Shell<PicoAccessLevel, BufferedCharIo, PicoHandler, DefaultConfig> =
+    let mut shell: Shell<PicoAccessLevel, DeferredIo<512>, PicoHandler, DefaultConfig> =
         Shell::new(&ROOT, handler, &provider, io);
 
     #[cfg(not(feature = "authentication"))]
-    let mut shell: Shell<PicoAccessLevel, BufferedCharIo, PicoHandler, DefaultConfig> =
+    let mut shell: Shell<PicoAccessLevel, DeferredIo<512>, PicoHandler, DefaultConfig> =
         Shell::new(&ROOT, handler, io);
 
     // Wait for USB connection
@@ -93,19 +84,7 @@ async fn shell_task(
     shell.activate().ok();
 
     // Flush initial output (welcome message)
-    if io_flush.has_data() {
-        let data = io_flush.take_buffer();
-        // Write all data, splitting into 64-byte packets as needed
-        let mut offset = 0;
-        while offset < data.len() {
-            let chunk_size = (data.len() - offset).min(64);
-            let chunk = &data[offset..offset + chunk_size];
-            match usb_class.write_packet(chunk).await {
-                Ok(_) => offset += chunk_size,
-                Err(_) => break,
-            }
-        }
-    }
+    flush_output(&mut usb_class, shell.io_mut()).await;
 
     // Main async loop
     let mut usb_buf = [0u8; 64];
@@ -113,27 +92,12 @@ async fn shell_task(
         // Read from USB (async)
         match usb_class.read_packet(&mut usb_buf).await {
             Ok(n) if n > 0 => {
-                // Process each character
+                // Process each byte (multi-byte UTF-8 is decoded by the shell)
                 for &byte in &usb_buf[..n] {
-                    let c = byte as char;
-
-                    // Process character (async)
-                    shell.process_char_async(c).await.ok();
+                    shell.process_byte_async(byte).await.ok();
 
                     // Flush buffered output after each character (deferred flush pattern)
-                    if io_flush.has_data() {
-                        let data = io_flush.take_buffer();
-                        // Write all data, splitting into 64-byte packets as needed
-                        let mut offset = 0;
-                        while offset < data.len() {
-                            let chunk_size = (data.len() - offset).min(64);
-                            let chunk = &data[offset..offset + chunk_size];
-                            match usb_class.write_packet(chunk).await {
-                                Ok(_) => offset += chunk_size,
-                                Err(_) => break, // Stop on error
-                            }
-                        }
-                    }
+                    flush_output(&mut usb_class, shell.io_mut()).await;
                 }
             }
             _ => {
@@ -144,6 +108,19 @@ async fn shell_task(
     }
 }
 
+/// Write buffered output to USB in 64-byte packets, then clear it.
+async fn flush_output(
+    usb_class: &mut CdcAcmClass<'static, Driver<'static, USB>>,
+    io: &mut DeferredIo<512>,
+) {
+    for chunk in io.pending().chunks(64) {
+        if usb_class.write_packet(chunk).await.is_err() {
+            break; // Stop on error
+        }
+    }
+    io.clear();
+}
+
 // =============================================================================
 // Main Entry Point
 // =============================================================================
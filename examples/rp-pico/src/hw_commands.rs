@@ -37,55 +37,38 @@ static mut CACHED_CHIP_RESET: Option<u32> = None;
 // Command Metadata (for use in command trees)
 // =============================================================================
 
-pub const CMD_CHIPID: CommandMeta<PicoAccessLevel> = CommandMeta {
-    id: "hw_chipid",
-    name: "chipid",
-    description: "Display flash unique ID (64-bit)",
-    access_level: PicoAccessLevel::User,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
-
-pub const CMD_CLOCKS: CommandMeta<PicoAccessLevel> = CommandMeta {
-    id: "hw_clocks",
-    name: "clocks",
-    description: "Show clock frequencies",
-    access_level: PicoAccessLevel::User,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
-
-pub const CMD_CORE: CommandMeta<PicoAccessLevel> = CommandMeta {
-    id: "hw_core",
-    name: "core",
-    description: "Display CPU core ID",
-    access_level: PicoAccessLevel::User,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
-
-pub const CMD_BOOTREASON: CommandMeta<PicoAccessLevel> = CommandMeta {
-    id: "hw_bootreason",
-    name: "bootreason",
-    description: "Show last reset reason",
-    access_level: PicoAccessLevel::User,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
-
-pub const CMD_GPIO: CommandMeta<PicoAccessLevel> = CommandMeta {
-    id: "hw_gpio",
-    name: "gpio",
-    description: "Display GPIO pin status (usage: gpio <pin>)",
-    access_level: PicoAccessLevel::User,
-    kind: CommandKind::Sync,
-    min_args: 1,
-    max_args: 1,
-};
+pub const CMD_CHIPID: CommandMeta<PicoAccessLevel> = CommandMeta::new(
+    "hw_chipid",
+    "chipid",
+    PicoAccessLevel::User,
+    CommandKind::Sync,
+)
+.with_description("Display flash unique ID (64-bit)");
+
+pub const CMD_CLOCKS: CommandMeta<PicoAccessLevel> = CommandMeta::new(
+    "hw_clocks",
+    "clocks",
+    PicoAccessLevel::User,
+    CommandKind::Sync,
+)
+.with_description("Show clock frequencies");
+
+pub const CMD_CORE: CommandMeta<PicoAccessLevel> =
+    CommandMeta::new("hw_core", "core", PicoAccessLevel::User, CommandKind::Sync)
+        .with_description("Display CPU core ID");
+
+pub const CMD_BOOTREASON: CommandMeta<PicoAccessLevel> = CommandMeta::new(
+    "hw_bootreason",
+    "bootreason",
+    PicoAccessLevel::User,
+    CommandKind::Sync,
+)
+.with_description("Show last reset reason");
+
+pub const CMD_GPIO: CommandMeta<PicoAccessLevel> =
+    CommandMeta::new("hw_gpio", "gpio", PicoAccessLevel::User, CommandKind::Sync)
+        .with_description("Display GPIO pin status (usage: gpio <pin>)")
+        .with_args(1, 1);
 
 // =============================================================================
 // Hardware Access Registration Functions
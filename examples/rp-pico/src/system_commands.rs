@@ -65,55 +65,45 @@ pub fn init_boot_time() {
 // System Diagnostic Commands (Metadata)
 // =============================================================================
 
-pub const CMD_UPTIME: CommandMeta<PicoAccessLevel> = CommandMeta {
-    id: "system_uptime",
-    name: "uptime",
-    description: "Show system uptime",
-    access_level: PicoAccessLevel::User,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
-
-pub const CMD_MEMINFO: CommandMeta<PicoAccessLevel> = CommandMeta {
-    id: "system_meminfo",
-    name: "meminfo",
-    description: "Display memory usage statistics",
-    access_level: PicoAccessLevel::User,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
-
-pub const CMD_BENCHMARK: CommandMeta<PicoAccessLevel> = CommandMeta {
-    id: "system_benchmark",
-    name: "benchmark",
-    description: "Run CPU performance benchmark",
-    access_level: PicoAccessLevel::User,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
-
-pub const CMD_FLASH: CommandMeta<PicoAccessLevel> = CommandMeta {
-    id: "system_flash",
-    name: "flash",
-    description: "Show flash memory information",
-    access_level: PicoAccessLevel::User,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
-
-pub const CMD_CRASH: CommandMeta<PicoAccessLevel> = CommandMeta {
-    id: "system_crash",
-    name: "crash",
-    description: "Trigger controlled panic (Admin only!)",
-    access_level: PicoAccessLevel::Admin,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
+pub const CMD_UPTIME: CommandMeta<PicoAccessLevel> = CommandMeta::new(
+    "system_uptime",
+    "uptime",
+    PicoAccessLevel::User,
+    CommandKind::Sync,
+)
+.with_description("Show system uptime");
+
+pub const CMD_MEMINFO: CommandMeta<PicoAccessLevel> = CommandMeta::new(
+    "system_meminfo",
+    "meminfo",
+    PicoAccessLevel::User,
+    CommandKind::Sync,
+)
+.with_description("Display memory usage statistics");
+
+pub const CMD_BENCHMARK: CommandMeta<PicoAccessLevel> = CommandMeta::new(
+    "system_benchmark",
+    "benchmark",
+    PicoAccessLevel::User,
+    CommandKind::Sync,
+)
+.with_description("Run CPU performance benchmark");
+
+pub const CMD_FLASH: CommandMeta<PicoAccessLevel> = CommandMeta::new(
+    "system_flash",
+    "flash",
+    PicoAccessLevel::User,
+    CommandKind::Sync,
+)
+.with_description("Show flash memory information");
+
+pub const CMD_CRASH: CommandMeta<PicoAccessLevel> = CommandMeta::new(
+    "system_crash",
+    "crash",
+    PicoAccessLevel::Admin,
+    CommandKind::Sync,
+)
+.with_description("Trigger controlled panic (Admin only!)");
 
 // =============================================================================
 // System Diagnostic Commands (Implementations)
@@ -102,7 +102,12 @@ impl<C: ShellConfig> CommandHandler<C> for Stm32Handler {
     }
 
     #[cfg(feature = "async")]
-    async fn execute_async(&self, _id: &str, _args: &[&str]) -> Result<Response<C>, CliError> {
+    async fn execute_async(
+        &self,
+        _id: &str,
+        _args: &[&str],
+        _cancel: &nut_shell::CancelToken,
+    ) -> Result<Response<C>, CliError> {
         // Basic example is synchronous-only, no async commands
         Err(CliError::CommandNotFound)
     }
@@ -7,19 +7,17 @@ use stm32_examples::{Stm32AccessLevel, hw_commands, system_commands};
 // System Commands
 // =============================================================================
 
-pub const CMD_INFO: CommandMeta<Stm32AccessLevel> = CommandMeta {
-    id: "system_info",
-    name: "info",
-    description: "Show device information",
-    access_level: Stm32AccessLevel::User,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
+pub const CMD_INFO: CommandMeta<Stm32AccessLevel> = CommandMeta::new(
+    "system_info",
+    "info",
+    Stm32AccessLevel::User,
+    CommandKind::Sync,
+)
+.with_description("Show device information");
 
-const SYSTEM_DIR: Directory<Stm32AccessLevel> = Directory {
-    name: "system",
-    children: &[
+const SYSTEM_DIR: Directory<Stm32AccessLevel> = Directory::new(
+    "system",
+    &[
         Node::Command(&CMD_INFO),
         Node::Command(&system_commands::CMD_UPTIME),
         Node::Command(&system_commands::CMD_MEMINFO),
@@ -27,68 +25,54 @@ const SYSTEM_DIR: Directory<Stm32AccessLevel> = Directory {
         Node::Command(&system_commands::CMD_FLASH),
         Node::Command(&system_commands::CMD_CRASH),
     ],
-    access_level: Stm32AccessLevel::User,
-};
+    Stm32AccessLevel::User,
+);
 
 // =============================================================================
 // Hardware Commands
 // =============================================================================
 
-pub const CMD_TEMP: CommandMeta<Stm32AccessLevel> = CommandMeta {
-    id: "hw_temp",
-    name: "temp",
-    description: "Read internal temperature sensor",
-    access_level: Stm32AccessLevel::User,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
+pub const CMD_TEMP: CommandMeta<Stm32AccessLevel> =
+    CommandMeta::new("hw_temp", "temp", Stm32AccessLevel::User, CommandKind::Sync)
+        .with_description("Read internal temperature sensor");
 
 // Hardware read commands
-const HARDWARE_GET_DIR: Directory<Stm32AccessLevel> = Directory {
-    name: "get",
-    children: &[
+const HARDWARE_GET_DIR: Directory<Stm32AccessLevel> = Directory::new(
+    "get",
+    &[
         Node::Command(&CMD_TEMP),
         Node::Command(&hw_commands::CMD_CHIPID),
         Node::Command(&hw_commands::CMD_CLOCKS),
         Node::Command(&hw_commands::CMD_CORE),
         Node::Command(&hw_commands::CMD_BOOTREASON),
     ],
-    access_level: Stm32AccessLevel::User,
-};
+    Stm32AccessLevel::User,
+);
 
-pub const CMD_LED: CommandMeta<Stm32AccessLevel> = CommandMeta {
-    id: "hw_led",
-    name: "led",
-    description: "Control USER LED (on/off)",
-    access_level: Stm32AccessLevel::User,
-    kind: CommandKind::Sync,
-    min_args: 1,
-    max_args: 1,
-};
+pub const CMD_LED: CommandMeta<Stm32AccessLevel> =
+    CommandMeta::new("hw_led", "led", Stm32AccessLevel::User, CommandKind::Sync)
+        .with_description("Control USER LED (on/off)")
+        .with_args(1, 1);
 
 // Hardware write/control commands
-const HARDWARE_SET_DIR: Directory<Stm32AccessLevel> = Directory {
-    name: "set",
-    children: &[Node::Command(&CMD_LED)],
-    access_level: Stm32AccessLevel::User,
-};
+const HARDWARE_SET_DIR: Directory<Stm32AccessLevel> =
+    Directory::new("set", &[Node::Command(&CMD_LED)], Stm32AccessLevel::User);
 
-const HARDWARE_DIR: Directory<Stm32AccessLevel> = Directory {
-    name: "hardware",
-    children: &[
+const HARDWARE_DIR: Directory<Stm32AccessLevel> = Directory::new(
+    "hardware",
+    &[
         Node::Directory(&HARDWARE_GET_DIR),
         Node::Directory(&HARDWARE_SET_DIR),
     ],
-    access_level: Stm32AccessLevel::User,
-};
+    Stm32AccessLevel::User,
+);
 
 // =============================================================================
 // Root Directory
 // =============================================================================
 
-pub const ROOT: Directory<Stm32AccessLevel> = Directory {
-    name: "/",
-    children: &[Node::Directory(&SYSTEM_DIR), Node::Directory(&HARDWARE_DIR)],
-    access_level: Stm32AccessLevel::User,
-};
+pub const ROOT: Directory<Stm32AccessLevel> = Directory::new(
+    "/",
+    &[Node::Directory(&SYSTEM_DIR), Node::Directory(&HARDWARE_DIR)],
+    Stm32AccessLevel::User,
+);
@@ -22,45 +22,33 @@ use nut_shell::{
 // Command Metadata (for use in command trees)
 // =============================================================================
 
-pub const CMD_CHIPID: CommandMeta<Stm32AccessLevel> = CommandMeta {
-    id: "hw_chipid",
-    name: "chipid",
-    description: "Display unique device ID (96-bit)",
-    access_level: Stm32AccessLevel::User,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
-
-pub const CMD_CLOCKS: CommandMeta<Stm32AccessLevel> = CommandMeta {
-    id: "hw_clocks",
-    name: "clocks",
-    description: "Show clock frequencies",
-    access_level: Stm32AccessLevel::User,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
-
-pub const CMD_CORE: CommandMeta<Stm32AccessLevel> = CommandMeta {
-    id: "hw_core",
-    name: "core",
-    description: "Display CPU core information",
-    access_level: Stm32AccessLevel::User,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
-
-pub const CMD_BOOTREASON: CommandMeta<Stm32AccessLevel> = CommandMeta {
-    id: "hw_bootreason",
-    name: "bootreason",
-    description: "Show last reset reason",
-    access_level: Stm32AccessLevel::User,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
+pub const CMD_CHIPID: CommandMeta<Stm32AccessLevel> = CommandMeta::new(
+    "hw_chipid",
+    "chipid",
+    Stm32AccessLevel::User,
+    CommandKind::Sync,
+)
+.with_description("Display unique device ID (96-bit)");
+
+pub const CMD_CLOCKS: CommandMeta<Stm32AccessLevel> = CommandMeta::new(
+    "hw_clocks",
+    "clocks",
+    Stm32AccessLevel::User,
+    CommandKind::Sync,
+)
+.with_description("Show clock frequencies");
+
+pub const CMD_CORE: CommandMeta<Stm32AccessLevel> =
+    CommandMeta::new("hw_core", "core", Stm32AccessLevel::User, CommandKind::Sync)
+        .with_description("Display CPU core information");
+
+pub const CMD_BOOTREASON: CommandMeta<Stm32AccessLevel> = CommandMeta::new(
+    "hw_bootreason",
+    "bootreason",
+    Stm32AccessLevel::User,
+    CommandKind::Sync,
+)
+.with_description("Show last reset reason");
 
 // =============================================================================
 // Unique Device ID Command
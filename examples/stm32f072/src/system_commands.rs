@@ -38,55 +38,45 @@ pub fn init_boot_time(boot_ms: u32) {
 // System Diagnostic Commands (Metadata)
 // =============================================================================
 
-pub const CMD_UPTIME: CommandMeta<Stm32AccessLevel> = CommandMeta {
-    id: "system_uptime",
-    name: "uptime",
-    description: "Show system uptime",
-    access_level: Stm32AccessLevel::User,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
-
-pub const CMD_MEMINFO: CommandMeta<Stm32AccessLevel> = CommandMeta {
-    id: "system_meminfo",
-    name: "meminfo",
-    description: "Display memory usage statistics",
-    access_level: Stm32AccessLevel::User,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
-
-pub const CMD_BENCHMARK: CommandMeta<Stm32AccessLevel> = CommandMeta {
-    id: "system_benchmark",
-    name: "benchmark",
-    description: "Run CPU performance benchmark",
-    access_level: Stm32AccessLevel::User,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
-
-pub const CMD_FLASH: CommandMeta<Stm32AccessLevel> = CommandMeta {
-    id: "system_flash",
-    name: "flash",
-    description: "Show flash memory information",
-    access_level: Stm32AccessLevel::User,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
-
-pub const CMD_CRASH: CommandMeta<Stm32AccessLevel> = CommandMeta {
-    id: "system_crash",
-    name: "crash",
-    description: "Trigger controlled panic (Admin only!)",
-    access_level: Stm32AccessLevel::Admin,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
+pub const CMD_UPTIME: CommandMeta<Stm32AccessLevel> = CommandMeta::new(
+    "system_uptime",
+    "uptime",
+    Stm32AccessLevel::User,
+    CommandKind::Sync,
+)
+.with_description("Show system uptime");
+
+pub const CMD_MEMINFO: CommandMeta<Stm32AccessLevel> = CommandMeta::new(
+    "system_meminfo",
+    "meminfo",
+    Stm32AccessLevel::User,
+    CommandKind::Sync,
+)
+.with_description("Display memory usage statistics");
+
+pub const CMD_BENCHMARK: CommandMeta<Stm32AccessLevel> = CommandMeta::new(
+    "system_benchmark",
+    "benchmark",
+    Stm32AccessLevel::User,
+    CommandKind::Sync,
+)
+.with_description("Run CPU performance benchmark");
+
+pub const CMD_FLASH: CommandMeta<Stm32AccessLevel> = CommandMeta::new(
+    "system_flash",
+    "flash",
+    Stm32AccessLevel::User,
+    CommandKind::Sync,
+)
+.with_description("Show flash memory information");
+
+pub const CMD_CRASH: CommandMeta<Stm32AccessLevel> = CommandMeta::new(
+    "system_crash",
+    "crash",
+    Stm32AccessLevel::Admin,
+    CommandKind::Sync,
+)
+.with_description("Trigger controlled panic (Admin only!)");
 
 // =============================================================================
 // System Diagnostic Commands (Implementations)
@@ -112,7 +112,12 @@ impl<C: ShellConfig> CommandHandler<C> for H753Handler {
     }
 
     #[cfg(feature = "async")]
-    async fn execute_async(&self, _id: &str, _args: &[&str]) -> Result<Response<C>, CliError> {
+    async fn execute_async(
+        &self,
+        _id: &str,
+        _args: &[&str],
+        _cancel: &nut_shell::CancelToken,
+    ) -> Result<Response<C>, CliError> {
         Err(CliError::CommandNotFound)
     }
 }
@@ -6,12 +6,12 @@
 //! # Task structure
 //!
 //! - `usb_task`: drives the USB device state machine (never returns)
-//! - `shell_task`: owns the CDC ACM class, reads USB packets, feeds each byte
-//!   to the shell via `process_char_async()`, and flushes buffered output back
+//! - `shell_task`: owns the CDC ACM class, reads USB packets, feeds each packet
+//!   to the shell via `process_bytes_async()`, and flushes buffered output back
 //!   to USB after each packet
 //!
-//! Output is written through `UsbCharIo` into a static TX buffer; the shell
-//! task flushes that buffer to USB in 64-byte chunks after each packet.
+//! Output is buffered by the shell's `DeferredIo`; the shell task flushes it
+//! to USB in 64-byte chunks after each packet.
 //!
 //! # Hardware Setup
 //! - Power the board via CN1 (ST-LINK USB) BEFORE connecting CN13
@@ -34,10 +34,8 @@
 mod handler;
 mod hw_setup;
 mod hw_state;
-mod io;
 mod tree;
 
-use core::cell::RefCell;
 use embassy_executor::Spawner;
 use embassy_stm32::gpio::{Level, Output, Speed};
 use embassy_stm32::peripherals::USB_OTG_FS;
@@ -49,11 +47,10 @@ use embassy_usb::class::cdc_acm::CdcAcmClass;
 use panic_halt as _;
 use static_cell::StaticCell;
 
-use nut_shell::{config::DefaultConfig, shell::Shell};
+use nut_shell::{config::DefaultConfig, io::DeferredIo, shell::Shell};
 use stm32h753zi_embassy_examples::H753AccessLevel;
 
 use crate::handler::H753Handler;
-use crate::io::{TxBuffer, UsbCharIo};
 use crate::tree::ROOT;
 
 // =============================================================================
@@ -76,20 +73,19 @@ async fn usb_task(mut device: embassy_usb::UsbDevice<'static, Driver<'static, US
 #[embassy_executor::task]
 async fn shell_task(
     mut usb_class: CdcAcmClass<'static, Driver<'static, USB_OTG_FS>>,
-    tx_buf: &'static RefCell<TxBuffer>,
 ) {
-    let io = UsbCharIo::new(tx_buf);
+    let io = DeferredIo::<512>::new();
     let handler = H753Handler;
 
     #[cfg(feature = "authentication")]
     let provider = stm32h753zi_embassy_examples::create_h753_provider();
 
     #[cfg(feature = "authentication")]
-    let mut shell: Shell<H753AccessLevel, UsbCharIo, H753Handler, DefaultConfig> =
+    let mut shell: Shell<H753AccessLevel, DeferredIo<512>, H753Handler, DefaultConfig> =
         Shell::new(&ROOT, handler, &provider, io);
 
     #[cfg(not(feature = "authentication"))]
-    let mut shell: Shell<H753AccessLevel, UsbCharIo, H753Handler, DefaultConfig> =
+    let mut shell: Shell<H753AccessLevel, DeferredIo<512>, H753Handler, DefaultConfig> =
         Shell::new(&ROOT, handler, io);
 
     let mut usb_buf = [0u8; 64];
@@ -99,15 +95,13 @@ async fn shell_task(
         shell.activate().ok();
 
         // Flush activation output (welcome message / prompt)
-        flush_tx(&mut usb_class, tx_buf).await;
+        flush_tx(&mut usb_class, shell.io_mut()).await;
 
         loop {
             match usb_class.read_packet(&mut usb_buf).await {
                 Ok(n) => {
-                    for &byte in &usb_buf[..n] {
-                        shell.process_char_async(byte as char).await.ok();
-                    }
-                    flush_tx(&mut usb_class, tx_buf).await;
+                    shell.process_bytes_async(&usb_buf[..n]).await.ok();
+                    flush_tx(&mut usb_class, shell.io_mut()).await;
                 }
                 Err(_) => break, // Disconnected
             }
@@ -118,17 +112,14 @@ async fn shell_task(
 /// Write all buffered TX bytes to USB in 64-byte chunks.
 async fn flush_tx(
     usb_class: &mut CdcAcmClass<'static, Driver<'static, USB_OTG_FS>>,
-    tx_buf: &'static RefCell<TxBuffer>,
+    io: &mut DeferredIo<512>,
 ) {
-    let data = tx_buf.borrow_mut().take();
-    let mut offset = 0;
-    while offset < data.len() {
-        let end = (offset + 64).min(data.len());
-        if usb_class.write_packet(&data[offset..end]).await.is_err() {
+    for chunk in io.pending().chunks(64) {
+        if usb_class.write_packet(chunk).await.is_err() {
             break;
         }
-        offset = end;
     }
+    io.clear();
 }
 
 // =============================================================================
@@ -210,18 +201,12 @@ async fn main(spawner: Spawner) {
     let usb_device = builder.build();
 
     // ------------------------------------------------------------------
-    // 5. Static TX buffer shared between UsbCharIo and shell_task
-    // ------------------------------------------------------------------
-    static TX_BUF: StaticCell<RefCell<TxBuffer>> = StaticCell::new();
-    let tx_buf = TX_BUF.init(RefCell::new(TxBuffer::new()));
-
-    // ------------------------------------------------------------------
-    // 6. Spawn tasks
+    // 5. Spawn tasks
     // ------------------------------------------------------------------
     spawner.spawn(usb_task(usb_device).unwrap());
 
     // Small delay to let USB enumeration begin before accepting serial data
     Timer::after_millis(100).await;
 
-    spawner.spawn(shell_task(cdc_class, tx_buf).unwrap());
+    spawner.spawn(shell_task(cdc_class).unwrap());
 }
@@ -16,55 +16,45 @@ use nut_shell::{
 // Command metadata
 // =============================================================================
 
-pub const CMD_UPTIME: CommandMeta<H753AccessLevel> = CommandMeta {
-    id: "system_uptime",
-    name: "uptime",
-    description: "Show system uptime",
-    access_level: H753AccessLevel::User,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
-
-pub const CMD_MEMINFO: CommandMeta<H753AccessLevel> = CommandMeta {
-    id: "system_meminfo",
-    name: "meminfo",
-    description: "Display memory usage statistics",
-    access_level: H753AccessLevel::User,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
-
-pub const CMD_BENCHMARK: CommandMeta<H753AccessLevel> = CommandMeta {
-    id: "system_benchmark",
-    name: "benchmark",
-    description: "Run CPU performance benchmark",
-    access_level: H753AccessLevel::User,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
-
-pub const CMD_FLASH: CommandMeta<H753AccessLevel> = CommandMeta {
-    id: "system_flash",
-    name: "flash",
-    description: "Show flash memory information",
-    access_level: H753AccessLevel::User,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
-
-pub const CMD_CRASH: CommandMeta<H753AccessLevel> = CommandMeta {
-    id: "system_crash",
-    name: "crash",
-    description: "Trigger controlled panic (Admin only)",
-    access_level: H753AccessLevel::Admin,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
+pub const CMD_UPTIME: CommandMeta<H753AccessLevel> = CommandMeta::new(
+    "system_uptime",
+    "uptime",
+    H753AccessLevel::User,
+    CommandKind::Sync,
+)
+.with_description("Show system uptime");
+
+pub const CMD_MEMINFO: CommandMeta<H753AccessLevel> = CommandMeta::new(
+    "system_meminfo",
+    "meminfo",
+    H753AccessLevel::User,
+    CommandKind::Sync,
+)
+.with_description("Display memory usage statistics");
+
+pub const CMD_BENCHMARK: CommandMeta<H753AccessLevel> = CommandMeta::new(
+    "system_benchmark",
+    "benchmark",
+    H753AccessLevel::User,
+    CommandKind::Sync,
+)
+.with_description("Run CPU performance benchmark");
+
+pub const CMD_FLASH: CommandMeta<H753AccessLevel> = CommandMeta::new(
+    "system_flash",
+    "flash",
+    H753AccessLevel::User,
+    CommandKind::Sync,
+)
+.with_description("Show flash memory information");
+
+pub const CMD_CRASH: CommandMeta<H753AccessLevel> = CommandMeta::new(
+    "system_crash",
+    "crash",
+    H753AccessLevel::Admin,
+    CommandKind::Sync,
+)
+.with_description("Trigger controlled panic (Admin only)");
 
 // =============================================================================
 // Command implementations
@@ -22,45 +22,33 @@ use nut_shell::{
 // Command metadata
 // =============================================================================
 
-pub const CMD_CHIPID: CommandMeta<H753AccessLevel> = CommandMeta {
-    id: "hw_chipid",
-    name: "chipid",
-    description: "Display unique device ID (96-bit)",
-    access_level: H753AccessLevel::User,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
-
-pub const CMD_CLOCKS: CommandMeta<H753AccessLevel> = CommandMeta {
-    id: "hw_clocks",
-    name: "clocks",
-    description: "Show clock frequencies",
-    access_level: H753AccessLevel::User,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
-
-pub const CMD_CORE: CommandMeta<H753AccessLevel> = CommandMeta {
-    id: "hw_core",
-    name: "core",
-    description: "Display CPU core information",
-    access_level: H753AccessLevel::User,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
-
-pub const CMD_BOOTREASON: CommandMeta<H753AccessLevel> = CommandMeta {
-    id: "hw_bootreason",
-    name: "bootreason",
-    description: "Show last reset reason",
-    access_level: H753AccessLevel::User,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
+pub const CMD_CHIPID: CommandMeta<H753AccessLevel> = CommandMeta::new(
+    "hw_chipid",
+    "chipid",
+    H753AccessLevel::User,
+    CommandKind::Sync,
+)
+.with_description("Display unique device ID (96-bit)");
+
+pub const CMD_CLOCKS: CommandMeta<H753AccessLevel> = CommandMeta::new(
+    "hw_clocks",
+    "clocks",
+    H753AccessLevel::User,
+    CommandKind::Sync,
+)
+.with_description("Show clock frequencies");
+
+pub const CMD_CORE: CommandMeta<H753AccessLevel> =
+    CommandMeta::new("hw_core", "core", H753AccessLevel::User, CommandKind::Sync)
+        .with_description("Display CPU core information");
+
+pub const CMD_BOOTREASON: CommandMeta<H753AccessLevel> = CommandMeta::new(
+    "hw_bootreason",
+    "bootreason",
+    H753AccessLevel::User,
+    CommandKind::Sync,
+)
+.with_description("Show last reset reason");
 
 // =============================================================================
 // Unique Device ID
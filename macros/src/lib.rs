@@ -4,8 +4,9 @@ use syn::{Data, DeriveInput, Fields, parse_macro_input};
 
 /// Derives the `AccessLevel` trait for enums.
 ///
-/// This macro automatically implements `from_str` and `as_str` methods
-/// for your access level enum, using the variant names as string representations.
+/// This macro automatically implements `from_str`, `from_str_ignore_case`, and `as_str`
+/// methods for your access level enum, using the variant names as string representations,
+/// and `rank` returning the variant's discriminant.
 ///
 /// # Example
 ///
@@ -33,6 +34,14 @@ use syn::{Data, DeriveInput, Fields, parse_macro_input};
 ///         }
 ///     }
 ///
+///     fn from_str_ignore_case(s: &str) -> Option<Self> {
+///         if s.eq_ignore_ascii_case("Guest") {
+///             return Some(Self::Guest);
+///         }
+///         // ... same for "User" and "Admin"
+///         None
+///     }
+///
 ///     fn as_str(&self) -> &'static str {
 ///         match self {
 ///             Self::Guest => "Guest",
@@ -40,6 +49,10 @@ use syn::{Data, DeriveInput, Fields, parse_macro_input};
 ///             Self::Admin => "Admin",
 ///         }
 ///     }
+///
+///     fn rank(&self) -> u8 {
+///         *self as u8
+///     }
 /// }
 /// ```
 ///
@@ -48,6 +61,7 @@ use syn::{Data, DeriveInput, Fields, parse_macro_input};
 /// - The type must be an enum
 /// - All variants must be unit variants (no fields)
 /// - Variant names will be used as the string representation
+/// - Discriminants must fit in a `u8` (checked at compile time)
 #[proc_macro_derive(AccessLevel)]
 pub fn derive_access_level(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
@@ -85,6 +99,17 @@ pub fn derive_access_level(input: TokenStream) -> TokenStream {
         }
     });
 
+    // Generate comparisons for from_str_ignore_case
+    let ignore_case_checks = variants.iter().map(|variant| {
+        let variant_name = &variant.ident;
+        let variant_str = variant_name.to_string();
+        quote! {
+            if s.eq_ignore_ascii_case(#variant_str) {
+                return Some(Self::#variant_name);
+            }
+        }
+    });
+
     // Generate match arms for as_str
     let as_str_arms = variants.iter().map(|variant| {
         let variant_name = &variant.ident;
@@ -94,6 +119,17 @@ pub fn derive_access_level(input: TokenStream) -> TokenStream {
         }
     });
 
+    // Discriminants must fit the u8 returned by rank()
+    let rank_checks = variants.iter().map(|variant| {
+        let variant_name = &variant.ident;
+        quote! {
+            assert!(
+                (#name::#variant_name as i128) >= 0 && (#name::#variant_name as i128) <= 255,
+                "AccessLevel discriminants must fit in a u8"
+            );
+        }
+    });
+
     // Generate the impl block
     let expanded = quote! {
         #[automatically_derived]
@@ -105,12 +141,25 @@ pub fn derive_access_level(input: TokenStream) -> TokenStream {
                 }
             }
 
+            fn from_str_ignore_case(s: &str) -> Option<Self> {
+                #(#ignore_case_checks)*
+                None
+            }
+
             fn as_str(&self) -> &'static str {
                 match self {
                     #(#as_str_arms,)*
                 }
             }
+
+            fn rank(&self) -> u8 {
+                *self as u8
+            }
         }
+
+        const _: () = {
+            #(#rank_checks)*
+        };
     };
 
     TokenStream::from(expanded)
@@ -1,10 +1,9 @@
 #![no_std]
 #![no_main]
 
-use core::fmt::Write;
 use nut_shell::config::MinimalConfig;
 use nut_shell::tree::{CommandKind, CommandMeta, Directory, Node};
-use nut_shell::{CharIo, CliError, CommandHandler, Response, Shell};
+use nut_shell::{CliError, CommandHandler, NullIo, Response, Shell};
 use panic_halt as _;
 
 // Minimal access level for testing
@@ -13,27 +12,6 @@ pub enum Level {
     User = 0,
 }
 
-// Minimal CharIo implementation - measures only struct size
-pub struct MinimalIo;
-
-impl CharIo for MinimalIo {
-    type Error = ();
-
-    fn get_char(&mut self) -> Result<Option<char>, Self::Error> {
-        Ok(None)
-    }
-
-    fn put_char(&mut self, _c: char) -> Result<(), Self::Error> {
-        Ok(())
-    }
-}
-
-impl Write for MinimalIo {
-    fn write_str(&mut self, _s: &str) -> core::fmt::Result {
-        Ok(())
-    }
-}
-
 // Command implementations
 fn status_cmd<C: nut_shell::ShellConfig>(_args: &[&str]) -> Result<Response<C>, CliError> {
     Ok(Response::success("OK"))
@@ -45,41 +23,24 @@ async fn info_cmd<C: nut_shell::ShellConfig>(_args: &[&str]) -> Result<Response<
 }
 
 // Command metadata
-const STATUS: CommandMeta<Level> = CommandMeta {
-    id: "status",
-    name: "status",
-    access_level: Level::User,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-    description: "Show status",
-};
+const STATUS: CommandMeta<Level> =
+    CommandMeta::new("status", "status", Level::User, CommandKind::Sync)
+        .with_description("Show status");
 
 #[cfg(feature = "async")]
-const INFO: CommandMeta<Level> = CommandMeta {
-    id: "info",
-    name: "info",
-    access_level: Level::User,
-    kind: CommandKind::Async,
-    min_args: 0,
-    max_args: 0,
-    description: "Show info",
-};
+const INFO: CommandMeta<Level> =
+    CommandMeta::new("info", "info", Level::User, CommandKind::Async).with_description("Show info");
 
 // Directory tree with commands
 #[cfg(feature = "async")]
-const ROOT: Directory<Level> = Directory {
-    name: "",
-    children: &[Node::Command(&STATUS), Node::Command(&INFO)],
-    access_level: Level::User,
-};
+const ROOT: Directory<Level> = Directory::new(
+    "",
+    &[Node::Command(&STATUS), Node::Command(&INFO)],
+    Level::User,
+);
 
 #[cfg(not(feature = "async"))]
-const ROOT: Directory<Level> = Directory {
-    name: "",
-    children: &[Node::Command(&STATUS)],
-    access_level: Level::User,
-};
+const ROOT: Directory<Level> = Directory::new("", &[Node::Command(&STATUS)], Level::User);
 
 // Minimal command handler
 struct MinHandler;
@@ -97,6 +58,7 @@ impl CommandHandler<MinimalConfig> for MinHandler {
         &self,
         id: &str,
         args: &[&str],
+        _cancel: &nut_shell::CancelToken,
     ) -> Result<Response<MinimalConfig>, CliError> {
         match id {
             "info" => info_cmd::<MinimalConfig>(args).await,
@@ -128,7 +90,7 @@ impl nut_shell::auth::CredentialProvider<Level> for MinCredentials {
 // Entry point
 #[cortex_m_rt::entry]
 fn main() -> ! {
-    let io = MinimalIo;
+    let io = NullIo;
     let handler = MinHandler;
 
     #[cfg(feature = "authentication")]
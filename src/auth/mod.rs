@@ -7,6 +7,8 @@
 
 #![cfg_attr(not(feature = "authentication"), allow(unused_imports))]
 
+use crate::error::CliError;
+
 // Sub-modules
 #[cfg(feature = "authentication")]
 pub mod password;
@@ -14,12 +16,24 @@ pub mod password;
 #[cfg(feature = "authentication")]
 pub mod providers;
 
+#[cfg(feature = "roles")]
+pub mod roles;
+
 // Re-exports
 #[cfg(feature = "authentication")]
 pub use password::Sha256Hasher;
 
+#[cfg(feature = "pbkdf2")]
+pub use password::Pbkdf2Hasher;
+
+#[cfg(feature = "argon2")]
+pub use password::Argon2Hasher;
+
 #[cfg(feature = "authentication")]
-pub use providers::ConstCredentialProvider;
+pub use providers::{ConstCredentialProvider, RamCredentialProvider};
+
+#[cfg(feature = "roles")]
+pub use roles::RoleSet;
 
 /// Access level trait for hierarchical permissions.
 /// Implement this trait to define your application's access hierarchy.
@@ -29,18 +43,42 @@ pub trait AccessLevel: Copy + Clone + PartialOrd + Ord + 'static {
     where
         Self: Sized;
 
+    /// Parse access level from string, ignoring ASCII case (e.g. `admin` for `Admin`).
+    ///
+    /// Default falls back to the exact `from_str()`; `#[derive(AccessLevel)]` generates a
+    /// case-insensitive match.
+    fn from_str_ignore_case(s: &str) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        Self::from_str(s)
+    }
+
     /// Convert access level to string representation.
     fn as_str(&self) -> &'static str;
+
+    /// Numeric rank of the level, ordered like the levels themselves (enum discriminant when
+    /// derived), e.g. for serialization or comparing against a threshold from config.
+    ///
+    /// MUST be consistent with `Ord`: `a < b` implies `a.rank() < b.rank()`.
+    fn rank(&self) -> u8;
 }
 
+/// Maximum username length in bytes.
+pub const MAX_USERNAME_LEN: usize = 32;
+
 /// User information.
 ///
 /// Contains username, access level, and (when authentication enabled) password hash and salt.
 /// This type is always available, even when authentication feature is disabled.
+///
+/// Create users with `User::new()` or `User::builder()`, which reject overlong usernames
+/// with `CliError::BufferFull`. Building the struct from its fields is discouraged: filling
+/// `username` via `push_str(..).unwrap()` panics on overflow.
 #[derive(Debug, Clone)]
 pub struct User<L: AccessLevel> {
     /// Username (always present)
-    pub username: heapless::String<32>,
+    pub username: heapless::String<MAX_USERNAME_LEN>,
 
     /// User's access level (always present)
     pub access_level: L,
@@ -52,45 +90,177 @@ pub struct User<L: AccessLevel> {
     /// Salt for password hashing
     #[cfg(feature = "authentication")]
     pub salt: [u8; 16],
+
+    /// Directory entered on login (absolute path; empty or unresolvable = root)
+    #[cfg(feature = "authentication")]
+    pub home_path: &'static str,
+
+    /// Roles held in addition to the access level (checked against `CommandMeta::required_role`)
+    #[cfg(feature = "roles")]
+    pub roles: RoleSet,
 }
 
 impl<L: AccessLevel> User<L> {
     /// Create a new user without authentication (auth feature disabled).
+    ///
+    /// Returns `BufferFull` if `username` exceeds `MAX_USERNAME_LEN` bytes.
     #[cfg(not(feature = "authentication"))]
-    pub fn new(username: &str, access_level: L) -> Result<Self, crate::error::CliError> {
-        let mut user_str = heapless::String::new();
-        user_str
-            .push_str(username)
-            .map_err(|_| crate::error::CliError::BufferFull)?;
-
-        Ok(Self {
-            username: user_str,
-            access_level,
-        })
+    pub fn new(username: &str, access_level: L) -> Result<Self, CliError> {
+        Self::builder(username, access_level).build()
     }
 
     /// Create a new user with authentication (auth feature enabled).
+    ///
+    /// Returns `BufferFull` if `username` exceeds `MAX_USERNAME_LEN` bytes.
     #[cfg(feature = "authentication")]
     pub fn new(
         username: &str,
         access_level: L,
         password_hash: [u8; 32],
         salt: [u8; 16],
-    ) -> Result<Self, crate::error::CliError> {
-        let mut user_str = heapless::String::new();
-        user_str
-            .push_str(username)
-            .map_err(|_| crate::error::CliError::BufferFull)?;
-
-        Ok(Self {
-            username: user_str,
+    ) -> Result<Self, CliError> {
+        Self::builder(username, access_level)
+            .password(password_hash, salt)
+            .build()
+    }
+
+    /// Start building a user (same signature with and without authentication).
+    ///
+    /// Length is checked in `UserBuilder::build()`.
+    pub fn builder(username: &str, access_level: L) -> UserBuilder<'_, L> {
+        UserBuilder {
+            username,
             access_level,
-            password_hash,
-            salt,
+            #[cfg(feature = "authentication")]
+            password_hash: [0u8; 32],
+            #[cfg(feature = "authentication")]
+            salt: [0u8; 16],
+            #[cfg(feature = "authentication")]
+            home_path: "",
+            #[cfg(feature = "roles")]
+            roles: RoleSet::empty(),
+        }
+    }
+
+    /// Set the directory entered on login (e.g. `"/diagnostics"`).
+    #[cfg(feature = "authentication")]
+    pub fn with_home_path(mut self, path: &'static str) -> Self {
+        self.home_path = path;
+        self
+    }
+
+    /// Set the roles this user holds.
+    #[cfg(feature = "roles")]
+    pub fn with_roles(mut self, roles: RoleSet) -> Self {
+        self.roles = roles;
+        self
+    }
+
+    /// Whether this user meets `level` and holds `required_role` (if any).
+    ///
+    /// Without the `roles` feature no user holds a role, so any `required_role` denies.
+    pub fn can_access(&self, level: L, required_role: Option<u8>) -> bool {
+        #[cfg(feature = "roles")]
+        if let Some(role) = required_role
+            && !self.roles.contains(role)
+        {
+            return false;
+        }
+        #[cfg(not(feature = "roles"))]
+        if required_role.is_some() {
+            return false;
+        }
+
+        self.access_level >= level
+    }
+}
+
+/// Builder for `User` (see `User::builder()`).
+///
+/// Optional fields default to no home path and no roles. Without `password()` the hash is
+/// all zeros, which no password verifies against.
+#[derive(Debug, Clone)]
+pub struct UserBuilder<'a, L: AccessLevel> {
+    username: &'a str,
+    access_level: L,
+    #[cfg(feature = "authentication")]
+    password_hash: [u8; 32],
+    #[cfg(feature = "authentication")]
+    salt: [u8; 16],
+    #[cfg(feature = "authentication")]
+    home_path: &'static str,
+    #[cfg(feature = "roles")]
+    roles: RoleSet,
+}
+
+impl<L: AccessLevel> UserBuilder<'_, L> {
+    /// Set password hash and the salt it was computed with.
+    #[cfg(feature = "authentication")]
+    pub fn password(mut self, password_hash: [u8; 32], salt: [u8; 16]) -> Self {
+        self.password_hash = password_hash;
+        self.salt = salt;
+        self
+    }
+
+    /// Set the directory entered on login (see `User::with_home_path()`).
+    #[cfg(feature = "authentication")]
+    pub fn home_path(mut self, path: &'static str) -> Self {
+        self.home_path = path;
+        self
+    }
+
+    /// Set the roles this user holds (see `User::with_roles()`).
+    #[cfg(feature = "roles")]
+    pub fn roles(mut self, roles: RoleSet) -> Self {
+        self.roles = roles;
+        self
+    }
+
+    /// Build the user.
+    ///
+    /// Returns `BufferFull` if the username exceeds `MAX_USERNAME_LEN` bytes.
+    pub fn build(self) -> Result<User<L>, CliError> {
+        let username =
+            heapless::String::try_from(self.username).map_err(|_| CliError::BufferFull)?;
+
+        Ok(User {
+            username,
+            access_level: self.access_level,
+            #[cfg(feature = "authentication")]
+            password_hash: self.password_hash,
+            #[cfg(feature = "authentication")]
+            salt: self.salt,
+            #[cfg(feature = "authentication")]
+            home_path: self.home_path,
+            #[cfg(feature = "roles")]
+            roles: self.roles,
         })
     }
 }
 
+/// Outcome of `CredentialProvider::authenticate()`.
+///
+/// The failure reason is for logging and lockout accounting only; operators always see the
+/// same generic login failure message.
+#[cfg(feature = "authentication")]
+#[derive(Debug, Clone)]
+pub enum AuthResult<L: AccessLevel> {
+    /// Credentials accepted
+    Success(User<L>),
+    /// No such user (or the provider failed to look it up)
+    UserNotFound,
+    /// User exists but the password is wrong
+    BadPassword,
+}
+
+#[cfg(feature = "authentication")]
+impl<L: AccessLevel> AuthResult<L> {
+    /// Whether the credentials were accepted.
+    pub fn is_success(&self) -> bool {
+        matches!(self, Self::Success(_))
+    }
+}
+
 /// Credential provider trait.
 /// Implementations provide user lookup and password verification.
 #[cfg(feature = "authentication")]
@@ -101,12 +271,69 @@ pub trait CredentialProvider<L: AccessLevel> {
     /// Find user by username.
     ///
     /// Returns `Ok(Some(user))` if found, `Ok(None)` if not found.
+    /// Should scan all entries rather than return early, so timing doesn't reveal
+    /// whether the username exists.
     fn find_user(&self, username: &str) -> Result<Option<User<L>>, Self::Error>;
 
     /// Verify password for user.
     ///
     /// MUST use constant-time comparison to prevent timing attacks.
     fn verify_password(&self, user: &User<L>, password: &str) -> bool;
+
+    /// Look up `username` and verify `password`, reporting why a login failed.
+    ///
+    /// Default combines `find_user()` and `verify_password()`; lookup errors count as
+    /// `UserNotFound`.
+    fn authenticate(&self, username: &str, password: &str) -> AuthResult<L> {
+        match self.find_user(username) {
+            Ok(Some(user)) if self.verify_password(&user, password) => AuthResult::Success(user),
+            Ok(Some(_)) => AuthResult::BadPassword,
+            _ => AuthResult::UserNotFound,
+        }
+    }
+
+    /// List usernames known to the provider (account audits, debugging).
+    ///
+    /// Default returns an empty list (provider cannot enumerate accounts).
+    fn list_users(&self) -> Result<heapless::Vec<&str, 32>, Self::Error> {
+        Ok(heapless::Vec::new())
+    }
+
+    /// List usernames with their access levels.
+    ///
+    /// Default combines `list_users()` with `find_user()`; names that no longer resolve are skipped.
+    fn list_users_detailed(&self) -> Result<heapless::Vec<(&str, L), 32>, Self::Error> {
+        let mut detailed = heapless::Vec::new();
+        for name in self.list_users()? {
+            if let Some(user) = self.find_user(name)? {
+                // Same capacity as list_users() - cannot overflow
+                let _ = detailed.push((name, user.access_level));
+            }
+        }
+        Ok(detailed)
+    }
+
+    /// Hash password with the provider's configured hasher.
+    ///
+    /// Default returns `CliError::Unsupported` (provider cannot produce new hashes).
+    fn hash_password(&self, password: &str, salt: &[u8; 16]) -> Result<[u8; 32], CliError> {
+        let _ = (password, salt);
+        Err(CliError::Unsupported)
+    }
+
+    /// Store new password hash and salt for user.
+    ///
+    /// Default returns `CliError::Unsupported` (read-only providers, e.g. const/ROM storage).
+    /// The shell has no RNG, so the new salt is always supplied by the caller.
+    fn set_password(
+        &self,
+        username: &str,
+        new_hash: [u8; 32],
+        new_salt: [u8; 16],
+    ) -> Result<(), CliError> {
+        let _ = (username, new_hash, new_salt);
+        Err(CliError::Unsupported)
+    }
 }
 
 /// Password hasher trait.
@@ -120,12 +347,46 @@ pub trait PasswordHasher {
     /// Returns 32-byte hash.
     fn hash(&self, password: &str, salt: &[u8]) -> [u8; 32];
 
+    /// Hash password with salt, or `None` if no hash can be produced (rejected inputs, busy
+    /// working memory). Providers storing new hashes MUST use this instead of `hash()`.
+    ///
+    /// Default wraps `hash()` for hashers that cannot fail.
+    fn try_hash(&self, password: &str, salt: &[u8]) -> Option<[u8; 32]> {
+        Some(self.hash(password, salt))
+    }
+
     /// Verify password against hash using constant-time comparison.
     ///
     /// MUST use constant-time comparison to prevent timing attacks.
     fn verify(&self, password: &str, salt: &[u8], hash: &[u8; 32]) -> bool;
 }
 
+/// Shared hasher, e.g. a `static Argon2Hasher` whose working memory must stay off the stack.
+#[cfg(feature = "authentication")]
+impl<H: PasswordHasher + ?Sized> PasswordHasher for &H {
+    fn hash(&self, password: &str, salt: &[u8]) -> [u8; 32] {
+        (**self).hash(password, salt)
+    }
+
+    fn try_hash(&self, password: &str, salt: &[u8]) -> Option<[u8; 32]> {
+        (**self).try_hash(password, salt)
+    }
+
+    fn verify(&self, password: &str, salt: &[u8], hash: &[u8; 32]) -> bool {
+        (**self).verify(password, salt, hash)
+    }
+}
+
+/// Entropy source for salt and secret generation.
+///
+/// The crate has no built-in RNG; platforms supply one (hardware RNG, ring oscillator, etc.).
+/// Implementations MUST be cryptographically secure when used for salts or secrets.
+#[cfg(feature = "authentication")]
+pub trait EntropySource {
+    /// Fill `buf` with random bytes.
+    fn fill(&self, buf: &mut [u8]);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -155,6 +416,10 @@ mod tests {
                 Self::Admin => "Admin",
             }
         }
+
+        fn rank(&self) -> u8 {
+            *self as u8
+        }
     }
 
     #[test]
@@ -177,4 +442,57 @@ mod tests {
             assert_eq!(user.salt, salt);
         }
     }
+
+    #[test]
+    fn test_user_builder_checks_username_length() {
+        let longest = core::str::from_utf8(&[b'a'; MAX_USERNAME_LEN]).unwrap();
+        let user = User::builder(longest, TestAccessLevel::Admin)
+            .build()
+            .unwrap();
+        assert_eq!(user.username.as_str(), longest);
+        assert_eq!(user.access_level, TestAccessLevel::Admin);
+
+        let too_long = core::str::from_utf8(&[b'a'; MAX_USERNAME_LEN + 1]).unwrap();
+        assert!(matches!(
+            User::builder(too_long, TestAccessLevel::Guest).build(),
+            Err(CliError::BufferFull)
+        ));
+
+        #[cfg(feature = "authentication")]
+        {
+            assert!(matches!(
+                User::new(too_long, TestAccessLevel::Guest, [0u8; 32], [0u8; 16]),
+                Err(CliError::BufferFull)
+            ));
+
+            let user = User::builder("bob", TestAccessLevel::User)
+                .password([1u8; 32], [2u8; 16])
+                .home_path("/system")
+                .build()
+                .unwrap();
+            assert_eq!(user.password_hash, [1u8; 32]);
+            assert_eq!(user.salt, [2u8; 16]);
+            assert_eq!(user.home_path, "/system");
+        }
+    }
+
+    #[test]
+    fn test_can_access_checks_level_and_role() {
+        let user = User::builder("alice", TestAccessLevel::User)
+            .build()
+            .unwrap();
+        assert!(user.can_access(TestAccessLevel::Guest, None));
+        assert!(user.can_access(TestAccessLevel::User, None));
+        assert!(!user.can_access(TestAccessLevel::Admin, None));
+
+        // A role nobody holds (and, without the `roles` feature, any role) denies
+        assert!(!user.can_access(TestAccessLevel::Guest, Some(0)));
+
+        #[cfg(feature = "roles")]
+        {
+            let user = user.with_roles(RoleSet::empty().with(0));
+            assert!(user.can_access(TestAccessLevel::Guest, Some(0)));
+            assert!(!user.can_access(TestAccessLevel::Guest, Some(1)));
+        }
+    }
 }
@@ -1,6 +1,8 @@
 //! Password hashing implementations.
 //!
-//! Provides SHA-256 based password hashing with constant-time verification.
+//! Provides SHA-256 based password hashing with constant-time verification,
+//! PBKDF2-HMAC-SHA256 (`pbkdf2` feature) for stronger brute-force resistance,
+//! and memory-hard Argon2id (`argon2` feature).
 
 use super::PasswordHasher;
 use sha2::{Digest, Sha256};
@@ -43,6 +45,169 @@ impl PasswordHasher for Sha256Hasher {
     }
 }
 
+/// PBKDF2-HMAC-SHA256 password hasher with constant-time verification (requires `pbkdf2` feature).
+/// Security: iterated key stretching slows offline brute force; cost scales linearly with `iterations`.
+#[cfg(feature = "pbkdf2")]
+#[derive(Debug, Copy, Clone)]
+pub struct Pbkdf2Hasher {
+    iterations: u32,
+}
+
+#[cfg(feature = "pbkdf2")]
+impl Pbkdf2Hasher {
+    /// Default iteration count (balances login latency on Cortex-M against brute-force cost).
+    pub const DEFAULT_ITERATIONS: u32 = 10_000;
+
+    /// Create a PBKDF2 hasher with the given iteration count (values below 1 are treated as 1).
+    pub const fn new(iterations: u32) -> Self {
+        Self {
+            iterations: if iterations == 0 { 1 } else { iterations },
+        }
+    }
+
+    /// Get configured iteration count.
+    pub const fn iterations(&self) -> u32 {
+        self.iterations
+    }
+}
+
+#[cfg(feature = "pbkdf2")]
+impl Default for Pbkdf2Hasher {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_ITERATIONS)
+    }
+}
+
+#[cfg(feature = "pbkdf2")]
+impl PasswordHasher for Pbkdf2Hasher {
+    /// Derive 32-byte key from password and salt using PBKDF2-HMAC-SHA256.
+    fn hash(&self, password: &str, salt: &[u8]) -> [u8; 32] {
+        let mut hash = [0u8; 32];
+        pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), salt, self.iterations, &mut hash);
+        hash
+    }
+
+    /// Verify password against hash using constant-time comparison.
+    fn verify(&self, password: &str, salt: &[u8], hash: &[u8; 32]) -> bool {
+        let computed_hash = self.hash(password, salt);
+
+        // Use constant-time comparison to prevent timing attacks
+        computed_hash.ct_eq(hash).into()
+    }
+}
+
+/// Argon2id password hasher with constant-time verification (requires `argon2` feature).
+///
+/// Security: memory-hard key derivation; `M_KIB` KiB of working memory are owned by the
+/// hasher (no heap), so keep it in a `static` (it is `Sync`) and hand the provider a
+/// `&'static` reference rather than moving it through the stack. Single lane (`p = 1`);
+/// time cost scales with `iterations`. Working memory is zeroed after each hash.
+///
+/// The memory is claimed in a short `critical_section::with()`, so the platform must
+/// provide a critical-section implementation. A hash attempted while another is running
+/// fails (`try_hash()` returns `None`, verification rejected) instead of blocking.
+#[cfg(feature = "argon2")]
+pub struct Argon2Hasher<const M_KIB: usize> {
+    iterations: u32,
+
+    /// Set while a hash owns `memory`
+    busy: critical_section::Mutex<core::cell::Cell<bool>>,
+
+    memory: core::cell::UnsafeCell<[argon2::Block; M_KIB]>,
+}
+
+// SAFETY: `memory` is only accessed by the holder of `busy`, which is claimed and released
+// inside critical sections, so no two threads or interrupts ever reference it at once.
+#[cfg(feature = "argon2")]
+unsafe impl<const M_KIB: usize> Sync for Argon2Hasher<M_KIB> {}
+
+#[cfg(feature = "argon2")]
+impl<const M_KIB: usize> Argon2Hasher<M_KIB> {
+    /// Default iteration count (`t` cost).
+    pub const DEFAULT_ITERATIONS: u32 = 2;
+
+    /// Create an Argon2id hasher (`M_KIB` must be at least 8; iterations below 1 are treated as 1).
+    pub const fn new(iterations: u32) -> Self {
+        assert!(M_KIB >= 8, "Argon2 requires at least 8 KiB of memory");
+        Self {
+            iterations: if iterations == 0 { 1 } else { iterations },
+            busy: critical_section::Mutex::new(core::cell::Cell::new(false)),
+            memory: core::cell::UnsafeCell::new([argon2::Block::new(); M_KIB]),
+        }
+    }
+
+    /// Get configured iteration count.
+    pub const fn iterations(&self) -> u32 {
+        self.iterations
+    }
+
+    /// Get working memory size in KiB (`m` cost).
+    pub const fn memory_kib(&self) -> usize {
+        M_KIB
+    }
+}
+
+#[cfg(feature = "argon2")]
+impl<const M_KIB: usize> Default for Argon2Hasher<M_KIB> {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_ITERATIONS)
+    }
+}
+
+#[cfg(feature = "argon2")]
+impl<const M_KIB: usize> core::fmt::Debug for Argon2Hasher<M_KIB> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Argon2Hasher")
+            .field("memory_kib", &M_KIB)
+            .field("iterations", &self.iterations)
+            .finish()
+    }
+}
+
+#[cfg(feature = "argon2")]
+impl<const M_KIB: usize> PasswordHasher for Argon2Hasher<M_KIB> {
+    /// Derive 32-byte key from password and salt using Argon2id (all zeros if `try_hash()` fails).
+    fn hash(&self, password: &str, salt: &[u8]) -> [u8; 32] {
+        self.try_hash(password, salt).unwrap_or([0u8; 32])
+    }
+
+    /// Derive 32-byte key, or `None` if Argon2 rejects the inputs (e.g. salt under 8 bytes)
+    /// or another hash is using the working memory.
+    fn try_hash(&self, password: &str, salt: &[u8]) -> Option<[u8; 32]> {
+        let params = argon2::Params::new(M_KIB as u32, self.iterations, 1, Some(32)).ok()?;
+        let argon =
+            argon2::Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params);
+
+        if critical_section::with(|cs| self.busy.borrow(cs).replace(true)) {
+            return None;
+        }
+        // SAFETY: `busy` was clear and is now set by us, so this is the only reference
+        let memory = unsafe { &mut *self.memory.get() };
+
+        let mut hash = [0u8; 32];
+        let result = argon.hash_password_into_with_memory(
+            password.as_bytes(),
+            salt,
+            &mut hash,
+            &mut *memory,
+        );
+
+        // Don't leave password-derived state in RAM
+        memory.fill(argon2::Block::new());
+        critical_section::with(|cs| self.busy.borrow(cs).set(false));
+        result.ok().map(|_| hash)
+    }
+
+    /// Verify password against hash using constant-time comparison.
+    fn verify(&self, password: &str, salt: &[u8], hash: &[u8; 32]) -> bool {
+        match self.try_hash(password, salt) {
+            // Use constant-time comparison to prevent timing attacks
+            Some(computed_hash) => computed_hash.ct_eq(hash).into(),
+            None => false,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -132,4 +297,202 @@ mod tests {
         assert!(hasher.verify(password, &salt, &hash));
         assert!(!hasher.verify("different", &salt, &hash));
     }
+
+    // ========================================
+    // PBKDF2 Tests
+    // ========================================
+
+    /// Decode hex test vector into 32-byte array.
+    #[cfg(feature = "pbkdf2")]
+    fn hex32(s: &str) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        for (i, byte) in out.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&s[i * 2..i * 2 + 2], 16).unwrap();
+        }
+        out
+    }
+
+    #[test]
+    #[cfg(feature = "pbkdf2")]
+    fn test_pbkdf2_reference_vectors() {
+        // PBKDF2-HMAC-SHA256 reference vectors (RFC 6070 inputs, SHA-256 PRF, dkLen = 32)
+        let vectors = [
+            (
+                1,
+                "120fb6cffcf8b32c43e7225256c4f837a86548c92ccc35480805987cb70be17b",
+            ),
+            (
+                2,
+                "ae4d0c95af6b46d32d0adff928f06dd02a303f8ef3c251dfd6e2d85a95474c43",
+            ),
+            (
+                4096,
+                "c5e478d59288c841aa530db6845c4c8d962893a001ce4e11a4963873aa98134a",
+            ),
+        ];
+
+        for (iterations, expected) in vectors {
+            let hasher = Pbkdf2Hasher::new(iterations);
+            assert_eq!(hasher.hash("password", b"salt"), hex32(expected));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "pbkdf2")]
+    fn test_pbkdf2_rfc7914_vector() {
+        // RFC 7914 section 11: P="passwd", S="salt", c=1 (first 32 bytes of the 64-byte output)
+        let hasher = Pbkdf2Hasher::new(1);
+        assert_eq!(
+            hasher.hash("passwd", b"salt"),
+            hex32("55ac046e56e3089fec1691c22544b605f94185216dde0465e68b9d57c20dacbc")
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "pbkdf2")]
+    fn test_pbkdf2_verify() {
+        let hasher = Pbkdf2Hasher::new(100);
+        let salt = [1u8; 16];
+        let hash = hasher.hash("password123", &salt);
+
+        assert!(hasher.verify("password123", &salt, &hash));
+        assert!(!hasher.verify("wrongpassword", &salt, &hash));
+        assert!(!hasher.verify("password123", &[2u8; 16], &hash));
+    }
+
+    #[test]
+    #[cfg(feature = "pbkdf2")]
+    fn test_pbkdf2_iterations_change_hash() {
+        let salt = [1u8; 16];
+        let hash1 = Pbkdf2Hasher::new(1).hash("password123", &salt);
+        let hash2 = Pbkdf2Hasher::new(2).hash("password123", &salt);
+
+        assert_ne!(hash1, hash2);
+        assert_ne!(hash1, Sha256Hasher::new().hash("password123", &salt));
+    }
+
+    #[test]
+    #[cfg(feature = "pbkdf2")]
+    fn test_pbkdf2_zero_iterations_clamped() {
+        assert_eq!(Pbkdf2Hasher::new(0).iterations(), 1);
+        assert_eq!(
+            Pbkdf2Hasher::default().iterations(),
+            Pbkdf2Hasher::DEFAULT_ITERATIONS
+        );
+    }
+
+    // ========================================
+    // Argon2 Tests
+    // ========================================
+
+    #[test]
+    #[cfg(feature = "argon2")]
+    fn test_argon2_matches_reference_implementation() {
+        let hasher = Argon2Hasher::<8>::new(1);
+        let salt = [1u8; 16];
+
+        let params = argon2::Params::new(8, 1, 1, Some(32)).unwrap();
+        let mut memory = [argon2::Block::new(); 8];
+        let mut expected = [0u8; 32];
+        argon2::Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params)
+            .hash_password_into_with_memory(b"password123", &salt, &mut expected, &mut memory)
+            .unwrap();
+
+        assert_eq!(hasher.hash("password123", &salt), expected);
+    }
+
+    #[test]
+    #[cfg(feature = "argon2")]
+    fn test_argon2_verify() {
+        let hasher = Argon2Hasher::<8>::new(1);
+        let salt = [1u8; 16];
+        let hash = hasher.hash("password123", &salt);
+
+        assert!(hasher.verify("password123", &salt, &hash));
+        assert!(!hasher.verify("wrongpassword", &salt, &hash));
+        assert!(!hasher.verify("password123", &[2u8; 16], &hash));
+    }
+
+    #[test]
+    #[cfg(feature = "argon2")]
+    fn test_argon2_static_hasher_shared_by_reference() {
+        static HASHER: Argon2Hasher<8> = Argon2Hasher::new(1);
+
+        fn hash_with(hasher: impl PasswordHasher) -> [u8; 32] {
+            hasher.hash("password123", &[1u8; 16])
+        }
+        assert_eq!(hash_with(&HASHER), hash_with(Argon2Hasher::<8>::new(1)));
+    }
+
+    #[test]
+    #[cfg(feature = "argon2")]
+    fn test_argon2_parameters_change_hash() {
+        let salt = [1u8; 16];
+        let hash = Argon2Hasher::<8>::new(1).hash("password123", &salt);
+
+        assert_ne!(hash, Argon2Hasher::<8>::new(2).hash("password123", &salt));
+        assert_ne!(hash, Argon2Hasher::<16>::new(1).hash("password123", &salt));
+    }
+
+    #[test]
+    #[cfg(feature = "argon2")]
+    fn test_argon2_rejects_short_salt() {
+        let hasher = Argon2Hasher::<8>::default();
+        assert_eq!(hasher.iterations(), Argon2Hasher::<8>::DEFAULT_ITERATIONS);
+        assert_eq!(hasher.memory_kib(), 8);
+
+        // Argon2 requires at least 8 bytes of salt
+        assert_eq!(hasher.try_hash("password123", b"salt"), None);
+        let hash = hasher.hash("password123", b"salt");
+        assert_eq!(hash, [0u8; 32]);
+        assert!(!hasher.verify("password123", b"salt", &hash));
+    }
+
+    #[test]
+    #[cfg(feature = "argon2")]
+    fn test_argon2_busy_fails_provider_password_change() {
+        use crate::auth::{AccessLevel, CredentialProvider, RamCredentialProvider, User};
+        use crate::error::CliError;
+
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+        struct Level;
+
+        impl AccessLevel for Level {
+            fn from_str(_: &str) -> Option<Self> {
+                Some(Self)
+            }
+
+            fn as_str(&self) -> &'static str {
+                "User"
+            }
+
+            fn rank(&self) -> u8 {
+                0
+            }
+        }
+
+        let hasher = Argon2Hasher::<8>::new(1);
+        let salt = [1u8; 16];
+        let user = User::new("alice", Level, hasher.hash("old", &salt), salt).unwrap();
+        let mut provider = RamCredentialProvider::<_, _, 1>::new(&hasher);
+        provider.add_user(user).unwrap();
+
+        // Another hash holds the working memory
+        critical_section::with(|cs| hasher.busy.borrow(cs).set(true));
+        assert!(matches!(
+            provider.hash_password("new", &[2u8; 16]),
+            Err(CliError::Other(_))
+        ));
+        assert!(matches!(
+            provider.set_password("alice", "new", [2u8; 16]),
+            Err(CliError::Other(_))
+        ));
+        critical_section::with(|cs| hasher.busy.borrow(cs).set(false));
+
+        // No all-zero hash was stored: the old password still works
+        let user = provider.find_user("alice").unwrap().unwrap();
+        assert_eq!(user.salt, salt);
+        assert!(provider.verify_password(&user, "old"));
+        assert!(provider.hash_password("new", &[2u8; 16]).is_ok());
+    }
 }
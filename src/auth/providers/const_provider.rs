@@ -3,6 +3,8 @@
 //! **WARNING**: Credentials visible in binary. Never use in production.
 
 use crate::auth::{AccessLevel, CredentialProvider, PasswordHasher, User};
+use crate::error::CliError;
+use subtle::ConstantTimeEq;
 
 /// Constant credential provider with hardcoded users.
 /// **WARNING**: For testing/examples only, not production.
@@ -25,13 +27,28 @@ impl<L: AccessLevel, H: PasswordHasher, const N: usize> CredentialProvider<L>
     type Error = ();
 
     /// Find user by username (case-sensitive).
+    ///
+    /// Scans every entry with constant-time comparison so lookup time doesn't reveal
+    /// where (or whether) the name is stored.
     fn find_user(&self, username: &str) -> Result<Option<User<L>>, Self::Error> {
+        let mut found = None;
         for user in &self.users {
-            if user.username.as_str() == username {
-                return Ok(Some(user.clone()));
+            let matches = bool::from(user.username.as_bytes().ct_eq(username.as_bytes()));
+            if matches && found.is_none() {
+                found = Some(user);
             }
         }
-        Ok(None)
+        Ok(found.cloned())
+    }
+
+    /// List usernames in declaration order (first 32).
+    fn list_users(&self) -> Result<heapless::Vec<&str, 32>, Self::Error> {
+        Ok(self
+            .users
+            .iter()
+            .take(32)
+            .map(|u| u.username.as_str())
+            .collect())
     }
 
     /// Verify password against user's stored hash and salt.
@@ -39,6 +56,17 @@ impl<L: AccessLevel, H: PasswordHasher, const N: usize> CredentialProvider<L>
         self.hasher
             .verify(password, &user.salt, &user.password_hash)
     }
+
+    /// Hash password with the provider's hasher; `Other` if the hasher fails (e.g. busy).
+    fn hash_password(&self, password: &str, salt: &[u8; 16]) -> Result<[u8; 32], CliError> {
+        self.hasher.try_hash(password, salt).ok_or_else(|| {
+            CliError::Other(
+                heapless::String::try_from("password hashing failed").unwrap_or_default(),
+            )
+        })
+    }
+
+    // `set_password` uses the default (`Unsupported`): credentials live in ROM.
 }
 
 #[cfg(test)]
@@ -71,6 +99,10 @@ mod tests {
                 Self::Admin => "Admin",
             }
         }
+
+        fn rank(&self) -> u8 {
+            *self as u8
+        }
     }
 
     /// Create test user with pre-hashed credentials using fixed salt.
@@ -154,4 +186,42 @@ mod tests {
         assert!(provider.find_user("alice").unwrap().is_none());
         assert!(provider.find_user("ALICE").unwrap().is_none());
     }
+
+    #[test]
+    fn test_set_password_unsupported() {
+        let users = [create_test_user("alice", "pass123", TestAccessLevel::Admin)];
+
+        let provider = ConstCredentialProvider::new(users, Sha256Hasher::new());
+
+        let hash = provider.hash_password("newpass", &[3u8; 16]).unwrap();
+        assert_eq!(hash, Sha256Hasher::new().hash("newpass", &[3u8; 16]));
+
+        assert_eq!(
+            provider.set_password("alice", hash, [3u8; 16]),
+            Err(CliError::Unsupported)
+        );
+
+        // Stored credentials unchanged
+        let user = provider.find_user("alice").unwrap().unwrap();
+        assert!(provider.verify_password(&user, "pass123"));
+    }
+
+    #[test]
+    fn test_list_users_detailed() {
+        let users = [
+            create_test_user("alice", "pass123", TestAccessLevel::Admin),
+            create_test_user("bob", "pass456", TestAccessLevel::Guest),
+        ];
+
+        let provider = ConstCredentialProvider::new(users, Sha256Hasher::new());
+
+        assert_eq!(provider.list_users().unwrap().as_slice(), &["alice", "bob"]);
+        assert_eq!(
+            provider.list_users_detailed().unwrap().as_slice(),
+            &[
+                ("alice", TestAccessLevel::Admin),
+                ("bob", TestAccessLevel::Guest)
+            ]
+        );
+    }
 }
@@ -1,8 +1,13 @@
 //! Credential provider implementations for authentication.
 //!
 //! `ConstCredentialProvider` for testing (hardcoded credentials).
+//! `RamCredentialProvider` for users managed at runtime.
 
 pub mod const_provider;
+pub mod ram_provider;
 
 /// Testing/demo provider with hardcoded credentials (not for production).
 pub use const_provider::ConstCredentialProvider;
+
+/// Runtime-managed provider with users stored in RAM.
+pub use ram_provider::RamCredentialProvider;
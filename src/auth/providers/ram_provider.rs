@@ -0,0 +1,261 @@
+//! RAM-backed credential provider with users managed at runtime.
+//!
+//! Contents are lost on reset; persist them yourself if accounts must survive a reboot.
+
+use crate::auth::{AccessLevel, CredentialProvider, PasswordHasher, User};
+use crate::error::CliError;
+use core::cell::Cell;
+use subtle::ConstantTimeEq;
+
+/// Stored user plus its current hash and salt.
+///
+/// Credentials sit in a `Cell` so `CredentialProvider::set_password()` can replace them
+/// through the shared reference the shell holds; `user`'s own hash/salt are not used.
+#[derive(Debug)]
+struct Entry<L: AccessLevel> {
+    user: User<L>,
+    credentials: Cell<([u8; 32], [u8; 16])>,
+}
+
+/// Credential provider holding up to `N` users in RAM.
+///
+/// Users are added and removed with `&mut self` (before handing the provider to the shell);
+/// passwords can also be changed while the shell borrows it (`passwd`, `change_password()`).
+#[derive(Debug)]
+pub struct RamCredentialProvider<L: AccessLevel, H: PasswordHasher, const N: usize> {
+    users: heapless::Vec<Entry<L>, N>,
+    hasher: H,
+}
+
+impl<L: AccessLevel, H: PasswordHasher, const N: usize> RamCredentialProvider<L, H, N> {
+    /// Create an empty provider.
+    pub const fn new(hasher: H) -> Self {
+        Self {
+            users: heapless::Vec::new(),
+            hasher,
+        }
+    }
+
+    /// Number of stored users.
+    pub fn len(&self) -> usize {
+        self.users.len()
+    }
+
+    /// Whether no users are stored.
+    pub fn is_empty(&self) -> bool {
+        self.users.is_empty()
+    }
+
+    /// Add a user with pre-hashed credentials.
+    ///
+    /// Returns `BufferFull` when `N` users are stored, or `Other` if the name is taken.
+    pub fn add_user(&mut self, user: User<L>) -> Result<(), CliError> {
+        if self.position(&user.username).is_some() {
+            return Err(CliError::Other(
+                heapless::String::try_from("user already exists").unwrap_or_default(),
+            ));
+        }
+        let credentials = Cell::new((user.password_hash, user.salt));
+        self.users
+            .push(Entry { user, credentials })
+            .map_err(|_| CliError::BufferFull)
+    }
+
+    /// Remove a user, returning it (with current credentials) if it existed.
+    pub fn remove_user(&mut self, username: &str) -> Option<User<L>> {
+        let index = self.position(username)?;
+        Some(Self::current(&self.users.remove(index)))
+    }
+
+    /// Hash `password` with the provider's hasher and store it for `username`.
+    ///
+    /// `salt` must be fresh per change (never reused). Returns `Other` for unknown users or
+    /// when the hasher fails (the stored password is then left unchanged).
+    pub fn set_password(
+        &self,
+        username: &str,
+        password: &str,
+        salt: [u8; 16],
+    ) -> Result<(), CliError> {
+        let hash = CredentialProvider::hash_password(self, password, &salt)?;
+        CredentialProvider::set_password(self, username, hash, salt)
+    }
+
+    /// Index of `username`, scanning every entry in constant time.
+    fn position(&self, username: &str) -> Option<usize> {
+        let mut found = None;
+        for (i, entry) in self.users.iter().enumerate() {
+            let matches = bool::from(entry.user.username.as_bytes().ct_eq(username.as_bytes()));
+            if matches && found.is_none() {
+                found = Some(i);
+            }
+        }
+        found
+    }
+
+    /// Copy of the entry's user carrying its current credentials.
+    fn current(entry: &Entry<L>) -> User<L> {
+        let mut user = entry.user.clone();
+        (user.password_hash, user.salt) = entry.credentials.get();
+        user
+    }
+}
+
+impl<L: AccessLevel, H: PasswordHasher, const N: usize> CredentialProvider<L>
+    for RamCredentialProvider<L, H, N>
+{
+    type Error = ();
+
+    /// Find user by username (case-sensitive, constant-time scan).
+    fn find_user(&self, username: &str) -> Result<Option<User<L>>, Self::Error> {
+        Ok(self
+            .position(username)
+            .map(|i| Self::current(&self.users[i])))
+    }
+
+    /// List usernames in insertion order (first 32).
+    fn list_users(&self) -> Result<heapless::Vec<&str, 32>, Self::Error> {
+        Ok(self
+            .users
+            .iter()
+            .take(32)
+            .map(|e| e.user.username.as_str())
+            .collect())
+    }
+
+    /// Verify password against user's stored hash and salt.
+    fn verify_password(&self, user: &User<L>, password: &str) -> bool {
+        self.hasher
+            .verify(password, &user.salt, &user.password_hash)
+    }
+
+    /// Hash password with the provider's hasher; `Other` if the hasher fails (e.g. busy).
+    fn hash_password(&self, password: &str, salt: &[u8; 16]) -> Result<[u8; 32], CliError> {
+        self.hasher.try_hash(password, salt).ok_or_else(|| {
+            CliError::Other(
+                heapless::String::try_from("password hashing failed").unwrap_or_default(),
+            )
+        })
+    }
+
+    /// Replace the user's hash and salt; `Other` for unknown users.
+    fn set_password(
+        &self,
+        username: &str,
+        new_hash: [u8; 32],
+        new_salt: [u8; 16],
+    ) -> Result<(), CliError> {
+        let index = self.position(username).ok_or_else(|| {
+            CliError::Other(heapless::String::try_from("unknown user").unwrap_or_default())
+        })?;
+        self.users[index].credentials.set((new_hash, new_salt));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::password::Sha256Hasher;
+
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+    enum TestAccessLevel {
+        Guest = 0,
+        Admin = 1,
+    }
+
+    impl AccessLevel for TestAccessLevel {
+        fn from_str(s: &str) -> Option<Self> {
+            match s {
+                "Guest" => Some(Self::Guest),
+                "Admin" => Some(Self::Admin),
+                _ => None,
+            }
+        }
+
+        fn as_str(&self) -> &'static str {
+            match self {
+                Self::Guest => "Guest",
+                Self::Admin => "Admin",
+            }
+        }
+
+        fn rank(&self) -> u8 {
+            *self as u8
+        }
+    }
+
+    type Provider = RamCredentialProvider<TestAccessLevel, Sha256Hasher, 2>;
+
+    fn create_test_user(username: &str, password: &str) -> User<TestAccessLevel> {
+        let salt = [1u8; 16];
+        let hash = Sha256Hasher::new().hash(password, &salt);
+        User::new(username, TestAccessLevel::Admin, hash, salt).unwrap()
+    }
+
+    #[test]
+    fn test_add_find_remove() {
+        let mut provider = Provider::new(Sha256Hasher::new());
+        assert!(provider.is_empty());
+
+        provider
+            .add_user(create_test_user("alice", "pass123"))
+            .unwrap();
+        provider
+            .add_user(create_test_user("bob", "pass456"))
+            .unwrap();
+        assert_eq!(provider.list_users().unwrap().as_slice(), &["alice", "bob"]);
+
+        let user = provider.find_user("alice").unwrap().unwrap();
+        assert!(provider.verify_password(&user, "pass123"));
+        assert!(provider.find_user("Alice").unwrap().is_none());
+
+        let removed = provider.remove_user("alice").unwrap();
+        assert_eq!(removed.username.as_str(), "alice");
+        assert!(provider.find_user("alice").unwrap().is_none());
+        assert!(provider.remove_user("alice").is_none());
+        assert_eq!(provider.len(), 1);
+    }
+
+    #[test]
+    fn test_add_user_full_or_duplicate() {
+        let mut provider = Provider::new(Sha256Hasher::new());
+        provider.add_user(create_test_user("alice", "a")).unwrap();
+
+        assert!(matches!(
+            provider.add_user(create_test_user("alice", "b")),
+            Err(CliError::Other(_))
+        ));
+
+        provider.add_user(create_test_user("bob", "b")).unwrap();
+        assert_eq!(
+            provider.add_user(create_test_user("carol", "c")),
+            Err(CliError::BufferFull)
+        );
+    }
+
+    #[test]
+    fn test_set_password_through_shared_ref() {
+        let mut provider = Provider::new(Sha256Hasher::new());
+        provider.add_user(create_test_user("alice", "old")).unwrap();
+
+        let shared = &provider;
+        shared.set_password("alice", "new", [7u8; 16]).unwrap();
+
+        let user = shared.find_user("alice").unwrap().unwrap();
+        assert_eq!(user.salt, [7u8; 16]);
+        assert!(shared.verify_password(&user, "new"));
+        assert!(!shared.verify_password(&user, "old"));
+
+        // Trait path (used by the shell) with a caller-computed hash
+        let hash = shared.hash_password("newer", &[8u8; 16]).unwrap();
+        CredentialProvider::set_password(shared, "alice", hash, [8u8; 16]).unwrap();
+        let user = shared.find_user("alice").unwrap().unwrap();
+        assert!(shared.verify_password(&user, "newer"));
+
+        assert!(matches!(
+            shared.set_password("nobody", "x", [0u8; 16]),
+            Err(CliError::Other(_))
+        ));
+    }
+}
@@ -0,0 +1,102 @@
+//! Role sets for capabilities outside the access-level hierarchy.
+//!
+//! A command with `CommandMeta::required_role` is accessible only to users whose
+//! `RoleSet` contains that role, in addition to meeting its access level.
+
+/// Set of up to 32 roles (bit `n` = role `n`), `Copy` and const-constructible.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RoleSet(u32);
+
+impl RoleSet {
+    /// Number of distinct roles; valid role ids are `0..MAX_ROLES`.
+    pub const MAX_ROLES: u8 = 32;
+
+    /// Set containing no roles.
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Set from a raw bitmask (bit `n` = role `n`).
+    pub const fn from_bits(bits: u32) -> Self {
+        Self(bits)
+    }
+
+    /// Raw bitmask.
+    pub const fn bits(self) -> u32 {
+        self.0
+    }
+
+    /// Copy of this set with `role` added (out-of-range ids are ignored).
+    pub const fn with(self, role: u8) -> Self {
+        Self(self.0 | Self::bit(role))
+    }
+
+    /// Add `role` (out-of-range ids are ignored).
+    pub fn insert(&mut self, role: u8) {
+        self.0 |= Self::bit(role);
+    }
+
+    /// Remove `role`.
+    pub fn remove(&mut self, role: u8) {
+        self.0 &= !Self::bit(role);
+    }
+
+    /// Whether `role` is in the set (always `false` for out-of-range ids).
+    pub const fn contains(self, role: u8) -> bool {
+        self.0 & Self::bit(role) != 0
+    }
+
+    /// Whether the set is empty.
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Bit for `role`, or 0 if out of range.
+    const fn bit(role: u8) -> u32 {
+        match 1u32.checked_shl(role as u32) {
+            Some(bit) => bit,
+            None => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NET: u8 = 0;
+    const HW: u8 = 5;
+
+    #[test]
+    fn test_insert_remove_contains() {
+        let mut roles = RoleSet::empty();
+        assert!(roles.is_empty());
+
+        roles.insert(NET);
+        roles.insert(HW);
+        assert!(roles.contains(NET));
+        assert!(roles.contains(HW));
+        assert!(!roles.contains(1));
+        assert_eq!(roles.bits(), 0b10_0001);
+
+        roles.remove(NET);
+        assert!(!roles.contains(NET));
+        assert!(roles.contains(HW));
+    }
+
+    #[test]
+    fn test_const_construction() {
+        const ROLES: RoleSet = RoleSet::empty().with(NET).with(31);
+        assert!(ROLES.contains(NET));
+        assert!(ROLES.contains(31));
+        assert_eq!(ROLES, RoleSet::from_bits(0x8000_0001));
+    }
+
+    #[test]
+    fn test_out_of_range_roles_ignored() {
+        let mut roles = RoleSet::empty().with(RoleSet::MAX_ROLES);
+        roles.insert(200);
+        assert!(roles.is_empty());
+        assert!(!RoleSet::from_bits(u32::MAX).contains(32));
+    }
+}
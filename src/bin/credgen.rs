@@ -1,15 +1,44 @@
 //! Build-time credential generator for nut-shell
 //!
-//! Reads plaintext credentials from TOML and generates Rust code with
-//! pre-hashed passwords for compile-time inclusion.
+//! Reads plaintext credentials from TOML, JSON, or YAML and generates Rust code
+//! with pre-hashed passwords for compile-time inclusion.
 
+use nut_shell::auth::MAX_USERNAME_LEN;
 use serde::Deserialize;
 use std::env;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process;
 
-/// TOML configuration structure
+/// Credentials file format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Toml,
+    Json,
+    Yaml,
+}
+
+impl Format {
+    /// Parse `--format` value.
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "toml" => Some(Self::Toml),
+            "json" => Some(Self::Json),
+            "yaml" | "yml" => Some(Self::Yaml),
+            _ => None,
+        }
+    }
+
+    /// Detect format from file extension (TOML if unknown).
+    fn from_path(path: &Path) -> Self {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(Self::from_name)
+            .unwrap_or(Self::Toml)
+    }
+}
+
+/// Credentials configuration structure
 #[derive(Debug, Deserialize)]
 struct Config {
     access_level_type: String,
@@ -17,11 +46,12 @@ struct Config {
     users: Vec<UserConfig>,
 }
 
-/// User configuration from TOML
+/// User configuration entry
 #[derive(Debug, Deserialize)]
 struct UserConfig {
     username: String,
     password: String,
+    #[serde(alias = "access_level")]
     level: String,
 }
 
@@ -33,14 +63,172 @@ struct GeneratedUser {
     level: String,
 }
 
+/// Password hasher baked into the generated provider
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Hasher {
+    Sha256,
+    Argon2id { memory_kib: u32, iterations: u32 },
+}
+
+impl Hasher {
+    /// Default Argon2id memory cost in KiB (RAM needed on the device to verify)
+    const DEFAULT_ARGON2_MEMORY_KIB: u32 = 16;
+
+    /// Default Argon2id iteration count (matches `Argon2Hasher::DEFAULT_ITERATIONS`)
+    const DEFAULT_ARGON2_ITERATIONS: u32 = 2;
+
+    /// Hash password with salt using this hasher.
+    fn hash(&self, password: &str, salt: &[u8; 16]) -> Result<[u8; 32], String> {
+        match *self {
+            Self::Sha256 => hash_password(password, salt),
+            Self::Argon2id {
+                memory_kib,
+                iterations,
+            } => hash_password_argon2id(password, salt, memory_kib, iterations),
+        }
+    }
+
+    /// Hasher type as it appears in the generated provider type.
+    ///
+    /// Argon2id is referenced from the generated `static HASHER` so its working memory
+    /// is never moved through the stack.
+    fn type_name(&self) -> String {
+        match self {
+            Self::Sha256 => "Sha256Hasher".into(),
+            Self::Argon2id { memory_kib, .. } => format!("&'static Argon2Hasher<{}>", memory_kib),
+        }
+    }
+
+    /// Hasher expression passed to the provider in the generated code.
+    fn constructor(&self) -> String {
+        match self {
+            Self::Sha256 => "Sha256Hasher".into(),
+            Self::Argon2id { .. } => "&HASHER".into(),
+        }
+    }
+
+    /// Generated `static HASHER` item, for hashers too large to pass by value.
+    fn static_item(&self) -> Option<String> {
+        match self {
+            Self::Sha256 => None,
+            Self::Argon2id {
+                memory_kib,
+                iterations,
+            } => Some(format!(
+                "/// Argon2id hasher; its {} KiB of working memory live here, not on the stack.\n\
+                 static HASHER: Argon2Hasher<{}> = Argon2Hasher::new({});\n\n",
+                memory_kib, memory_kib, iterations
+            )),
+        }
+    }
+
+    /// Import name for the generated `use` line.
+    fn import(&self) -> &'static str {
+        match self {
+            Self::Sha256 => "Sha256Hasher",
+            Self::Argon2id { .. } => "Argon2Hasher",
+        }
+    }
+}
+
+/// Command line options
+#[derive(Debug)]
+struct Options {
+    format: Format,
+    hasher: Hasher,
+    input_path: PathBuf,
+}
+
+/// Parse command line arguments (excluding program name)
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut format = None;
+    let mut hasher_name = "sha256".to_string();
+    let mut memory_kib = Hasher::DEFAULT_ARGON2_MEMORY_KIB;
+    let mut iterations = Hasher::DEFAULT_ARGON2_ITERATIONS;
+    let mut input_path = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = || {
+            iter.next()
+                .ok_or_else(|| format!("missing value for {}", arg))
+        };
+        match arg.as_str() {
+            "--format" => {
+                let name = value()?;
+                format = Some(Format::from_name(name).ok_or_else(|| {
+                    format!("unknown format: {} (expected toml, json, or yaml)", name)
+                })?);
+            }
+            "--hasher" => hasher_name = value()?.clone(),
+            "--memory-kib" => {
+                memory_kib = value()?
+                    .parse()
+                    .map_err(|_| "--memory-kib expects a number".to_string())?;
+            }
+            "--iterations" => {
+                iterations = value()?
+                    .parse()
+                    .map_err(|_| "--iterations expects a number".to_string())?;
+            }
+            _ if input_path.is_none() && !arg.starts_with("--") => {
+                input_path = Some(PathBuf::from(arg));
+            }
+            _ => return Err(format!("unexpected argument: {}", arg)),
+        }
+    }
+
+    let input_path = input_path.ok_or("missing credentials file")?;
+    let hasher = match hasher_name.as_str() {
+        "sha256" => Hasher::Sha256,
+        "argon2id" => {
+            if memory_kib < 8 {
+                return Err("--memory-kib must be at least 8".into());
+            }
+            if iterations < 1 {
+                return Err("--iterations must be at least 1".into());
+            }
+            Hasher::Argon2id {
+                memory_kib,
+                iterations,
+            }
+        }
+        other => {
+            return Err(format!(
+                "unknown hasher: {} (expected sha256 or argon2id)",
+                other
+            ));
+        }
+    };
+
+    Ok(Options {
+        format: format.unwrap_or_else(|| Format::from_path(&input_path)),
+        hasher,
+        input_path,
+    })
+}
+
 fn main() {
     // Parse command line arguments
     let args: Vec<String> = env::args().collect();
 
-    if args.len() != 2 {
-        eprintln!("Usage: {} <credentials.toml>", args[0]);
+    let options = parse_args(&args[1..]).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        eprintln!();
+        eprintln!(
+            "Usage: {} [--format toml|json|yaml] [--hasher sha256|argon2id] \\",
+            args[0]
+        );
+        eprintln!("           [--memory-kib N] [--iterations N] <credentials>");
         eprintln!();
-        eprintln!("Generates Rust code with pre-hashed credentials from TOML configuration.");
+        eprintln!("Generates Rust code with pre-hashed credentials from a configuration file.");
+        eprintln!("The format is detected from the file extension unless --format is given.");
+        eprintln!(
+            "--memory-kib/--iterations set Argon2id costs (defaults {} KiB, {}); the device needs",
+            Hasher::DEFAULT_ARGON2_MEMORY_KIB,
+            Hasher::DEFAULT_ARGON2_ITERATIONS
+        );
+        eprintln!("--memory-kib KiB of RAM to verify logins and nut-shell's `argon2` feature.");
         eprintln!();
         eprintln!("Example credentials.toml:");
         eprintln!("  access_level_type = \"my_crate::AccessLevel\"");
@@ -50,18 +238,21 @@ fn main() {
         eprintln!("  password = \"secret123\"");
         eprintln!("  level = \"Admin\"");
         process::exit(1);
-    }
-
-    let input_path = PathBuf::from(&args[1]);
-
-    // Read and parse TOML
-    let toml_content = fs::read_to_string(&input_path).unwrap_or_else(|e| {
+    });
+    let Options {
+        format,
+        hasher,
+        input_path,
+    } = options;
+
+    // Read and parse configuration
+    let content = fs::read_to_string(&input_path).unwrap_or_else(|e| {
         eprintln!("Error reading {}: {}", input_path.display(), e);
         process::exit(1);
     });
 
-    let config: Config = toml::from_str(&toml_content).unwrap_or_else(|e| {
-        eprintln!("Error parsing TOML: {}", e);
+    let config = parse_config(&content, format).unwrap_or_else(|e| {
+        eprintln!("{}", e);
         process::exit(1);
     });
 
@@ -72,18 +263,31 @@ fn main() {
     }
 
     // Generate hashed credentials
-    let users = generate_users(&config.users).unwrap_or_else(|e| {
+    let users = generate_users(&config.users, hasher).unwrap_or_else(|e| {
         eprintln!("Error generating credentials: {}", e);
         process::exit(1);
     });
 
     // Generate Rust code
-    let code = generate_code(&config.access_level_type, &users);
+    let code = generate_code(&config.access_level_type, hasher, &users);
 
     // Write to stdout
     println!("{}", code);
 }
 
+/// Parse configuration in the given format
+fn parse_config(content: &str, format: Format) -> Result<Config, String> {
+    match format {
+        Format::Toml => toml::from_str(content).map_err(|e| format!("Error parsing TOML: {}", e)),
+        Format::Json => {
+            serde_json::from_str(content).map_err(|e| format!("Error parsing JSON: {}", e))
+        }
+        Format::Yaml => {
+            serde_yaml_ng::from_str(content).map_err(|e| format!("Error parsing YAML: {}", e))
+        }
+    }
+}
+
 /// Validate the configuration
 fn validate_config(config: &Config) -> Result<(), String> {
     if config.access_level_type.is_empty() {
@@ -94,11 +298,17 @@ fn validate_config(config: &Config) -> Result<(), String> {
         return Err("at least one user must be defined".into());
     }
 
-    // Check for empty usernames or passwords
+    // Check for empty (or overlong) usernames and empty passwords
     for user in &config.users {
         if user.username.is_empty() {
             return Err("username cannot be empty".into());
         }
+        if user.username.len() > MAX_USERNAME_LEN {
+            return Err(format!(
+                "username exceeds {} bytes: {}",
+                MAX_USERNAME_LEN, user.username
+            ));
+        }
         if user.password.is_empty() {
             return Err("password cannot be empty".into());
         }
@@ -119,7 +329,7 @@ fn validate_config(config: &Config) -> Result<(), String> {
 }
 
 /// Generate users with hashed credentials
-fn generate_users(configs: &[UserConfig]) -> Result<Vec<GeneratedUser>, String> {
+fn generate_users(configs: &[UserConfig], hasher: Hasher) -> Result<Vec<GeneratedUser>, String> {
     configs
         .iter()
         .map(|cfg| {
@@ -127,7 +337,7 @@ fn generate_users(configs: &[UserConfig]) -> Result<Vec<GeneratedUser>, String>
             let salt = generate_salt();
 
             // Hash password with salt
-            let hash = hash_password(&cfg.password, &salt)?;
+            let hash = hasher.hash(&cfg.password, &salt)?;
 
             Ok(GeneratedUser {
                 username: cfg.username.clone(),
@@ -161,8 +371,28 @@ fn hash_password(password: &str, salt: &[u8; 16]) -> Result<[u8; 32], String> {
     Ok(hash)
 }
 
+/// Hash password using Argon2id (matching nut-shell's Argon2Hasher: single lane, 32-byte output)
+fn hash_password_argon2id(
+    password: &str,
+    salt: &[u8; 16],
+    memory_kib: u32,
+    iterations: u32,
+) -> Result<[u8; 32], String> {
+    use argon2::{Algorithm, Argon2, Block, Params, Version};
+
+    let params = Params::new(memory_kib, iterations, 1, Some(32))
+        .map_err(|e| format!("invalid Argon2 parameters: {}", e))?;
+    let mut memory = vec![Block::new(); memory_kib as usize];
+    let mut hash = [0u8; 32];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into_with_memory(password.as_bytes(), salt, &mut hash, &mut memory)
+        .map_err(|e| format!("Argon2 hashing failed: {}", e))?;
+
+    Ok(hash)
+}
+
 /// Generate Rust source code
-fn generate_code(access_level_type: &str, users: &[GeneratedUser]) -> String {
+fn generate_code(access_level_type: &str, hasher: Hasher, users: &[GeneratedUser]) -> String {
     // Extract simple type name from full path
     let type_name = access_level_type
         .split("::")
@@ -177,15 +407,30 @@ fn generate_code(access_level_type: &str, users: &[GeneratedUser]) -> String {
     output.push_str("//\n");
     output.push_str("// WARNING: Credentials are visible in the binary.\n");
     output.push_str("// Only use in production if you understand the security implications.\n");
+    if let Hasher::Argon2id { memory_kib, .. } = hasher {
+        output.push_str("//\n");
+        output.push_str(&format!(
+            "// Requires nut-shell's `argon2` feature; verifying a login uses {} KiB of RAM.\n",
+            memory_kib
+        ));
+    }
     output.push('\n');
-    output.push_str("use nut_shell::auth::{ConstCredentialProvider, Sha256Hasher, User};\n");
+    output.push_str(&format!(
+        "use nut_shell::auth::{{AccessLevel, ConstCredentialProvider, {}, User}};\n",
+        hasher.import()
+    ));
     output.push_str(&format!("use {};\n\n", access_level_type));
 
+    if let Some(item) = hasher.static_item() {
+        output.push_str(&item);
+    }
+
     // Provider type alias
     output.push_str("/// Build-time credential provider with pre-hashed credentials.\n");
     output.push_str(&format!(
-        "pub type BuildTimeProvider = ConstCredentialProvider<{}, Sha256Hasher, {}>;\n\n",
+        "pub type BuildTimeProvider = ConstCredentialProvider<{}, {}, {}>;\n\n",
         type_name,
+        hasher.type_name(),
         users.len()
     ));
 
@@ -198,7 +443,12 @@ fn generate_code(access_level_type: &str, users: &[GeneratedUser]) -> String {
     for user in users {
         output.push_str("        User::new(\n");
         output.push_str(&format!("            \"{}\",\n", user.username));
-        output.push_str(&format!("            {}::{},\n", type_name, user.level));
+        // Level names match variants ignoring case (`admin` selects `Admin`)
+        let missing = format!("unknown access level '{}'", user.level);
+        output.push_str(&format!(
+            "            {}::from_str_ignore_case({:?}).expect({:?}),\n",
+            type_name, user.level, missing
+        ));
         output.push_str(&format!(
             "            {},\n",
             format_byte_array(&user.password_hash)
@@ -207,7 +457,10 @@ fn generate_code(access_level_type: &str, users: &[GeneratedUser]) -> String {
         output.push_str("        ).unwrap(),\n");
     }
     output.push_str("    ];\n\n");
-    output.push_str("    ConstCredentialProvider::new(users, Sha256Hasher)\n");
+    output.push_str(&format!(
+        "    ConstCredentialProvider::new(users, {})\n",
+        hasher.constructor()
+    ));
     output.push_str("}\n");
 
     output
@@ -271,6 +524,19 @@ mod tests {
         assert!(validate_config(&config).is_err());
     }
 
+    #[test]
+    fn test_validate_config_long_username() {
+        let config = Config {
+            access_level_type: "Foo::Bar".into(),
+            users: vec![UserConfig {
+                username: "a".repeat(MAX_USERNAME_LEN + 1),
+                password: "pass".into(),
+                level: "Admin".into(),
+            }],
+        };
+        assert!(validate_config(&config).is_err());
+    }
+
     #[test]
     fn test_validate_config_duplicate_username() {
         let config = Config {
@@ -291,6 +557,29 @@ mod tests {
         assert!(validate_config(&config).is_err());
     }
 
+    #[test]
+    fn test_generate_code_parses_level_ignoring_case() {
+        let users = [GeneratedUser {
+            username: "ops".into(),
+            password_hash: [0; 32],
+            salt: [0; 16],
+            level: "admin".into(),
+        }];
+        let code = generate_code("my_crate::Level", Hasher::Sha256, &users);
+        assert!(
+            code.contains("use nut_shell::auth::{AccessLevel, "),
+            "{}",
+            code
+        );
+        assert!(
+            code.contains(
+                "Level::from_str_ignore_case(\"admin\").expect(\"unknown access level 'admin'\")"
+            ),
+            "{}",
+            code
+        );
+    }
+
     #[test]
     fn test_format_byte_array() {
         let bytes = [0x00, 0xff, 0x42];
@@ -318,4 +607,125 @@ mod tests {
         let hash3 = hash_password("different", &salt).unwrap();
         assert_ne!(hash, hash3);
     }
+
+    #[test]
+    fn test_format_detection() {
+        assert_eq!(Format::from_path(Path::new("creds.json")), Format::Json);
+        assert_eq!(Format::from_path(Path::new("creds.yml")), Format::Yaml);
+        assert_eq!(Format::from_path(Path::new("creds.yaml")), Format::Yaml);
+        assert_eq!(Format::from_path(Path::new("creds.toml")), Format::Toml);
+        assert_eq!(Format::from_path(Path::new("creds")), Format::Toml);
+        assert_eq!(Format::from_name("xml"), None);
+    }
+
+    #[test]
+    fn test_parse_config_formats() {
+        let toml = r#"
+            access_level_type = "my_crate::Level"
+
+            [[users]]
+            username = "admin"
+            password = "pass"
+            level = "Admin"
+        "#;
+        let json = r#"{
+            "access_level_type": "my_crate::Level",
+            "users": [{ "username": "admin", "password": "pass", "access_level": "Admin" }]
+        }"#;
+        let yaml = "
+access_level_type: my_crate::Level
+users:
+  - username: admin
+    password: pass
+    level: Admin
+";
+
+        for (content, format) in [
+            (toml, Format::Toml),
+            (json, Format::Json),
+            (yaml, Format::Yaml),
+        ] {
+            let config = parse_config(content, format).unwrap();
+            assert_eq!(config.access_level_type, "my_crate::Level");
+            assert_eq!(config.users.len(), 1);
+            assert_eq!(config.users[0].username, "admin");
+            assert_eq!(config.users[0].password, "pass");
+            assert_eq!(config.users[0].level, "Admin");
+        }
+
+        assert!(parse_config("{", Format::Json).is_err());
+    }
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let options = parse_args(&args(&["creds.json"])).unwrap();
+        assert_eq!(options.format, Format::Json);
+        assert_eq!(options.hasher, Hasher::Sha256);
+
+        let options = parse_args(&args(&[
+            "--hasher",
+            "argon2id",
+            "--memory-kib",
+            "32",
+            "--format",
+            "yaml",
+            "creds.txt",
+        ]))
+        .unwrap();
+        assert_eq!(options.format, Format::Yaml);
+        assert_eq!(
+            options.hasher,
+            Hasher::Argon2id {
+                memory_kib: 32,
+                iterations: Hasher::DEFAULT_ARGON2_ITERATIONS
+            }
+        );
+
+        assert!(parse_args(&args(&[])).is_err());
+        assert!(parse_args(&args(&["--hasher", "md5", "creds.toml"])).is_err());
+        assert!(
+            parse_args(&args(&[
+                "--hasher",
+                "argon2id",
+                "--memory-kib",
+                "4",
+                "c.toml"
+            ]))
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_argon2id_matches_runtime_hasher() {
+        use nut_shell::auth::{Argon2Hasher, PasswordHasher};
+
+        let salt = [1u8; 16];
+        let hash = hash_password_argon2id("test123", &salt, 8, 1).unwrap();
+        assert_eq!(hash, Argon2Hasher::<8>::new(1).hash("test123", &salt));
+    }
+
+    #[test]
+    fn test_generate_code_argon2id() {
+        let hasher = Hasher::Argon2id {
+            memory_kib: 16,
+            iterations: 3,
+        };
+        let users = [GeneratedUser {
+            username: "admin".into(),
+            password_hash: [0u8; 32],
+            salt: [0u8; 16],
+            level: "Admin".into(),
+        }];
+        let code = generate_code("my_crate::Level", hasher, &users);
+        assert!(code.contains(
+            "use nut_shell::auth::{AccessLevel, ConstCredentialProvider, Argon2Hasher, User};"
+        ));
+        assert!(code.contains("static HASHER: Argon2Hasher<16> = Argon2Hasher::new(3);"));
+        assert!(code.contains("ConstCredentialProvider<Level, &'static Argon2Hasher<16>, 1>"));
+        assert!(code.contains("ConstCredentialProvider::new(users, &HASHER)"));
+    }
 }
@@ -3,33 +3,131 @@
 //! The `ShellConfig` trait allows compile-time configuration of buffer sizes
 //! and capacity limits without runtime overhead.
 
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+
 /// Shell configuration trait defining buffer sizes and capacity limits.
 ///
-/// All values are const (zero runtime cost). Due to Rust's const generics limitations
-/// (const trait bounds not yet stable), internal buffers use hardcoded sizes from
-/// `DefaultConfig` rather than `C::MAX_INPUT`, etc. The trait establishes the API
-/// contract for when const generics stabilize.
+/// All values are const (zero runtime cost). Buffer storage is sized by `Buffers`, since
+/// stable Rust cannot size a `heapless` buffer from `C::MAX_INPUT` inside generic code.
+/// The `MAX_*` limits default to those sizes and may be set lower (checked at compile time).
 ///
-/// **Currently customizable:** `MSG_*` strings only.
-/// **Not yet customizable:** Buffer size constants (hardcoded to `DefaultConfig` values).
+/// Per-command scratch for tokens and path names is sized at the [`capacity`] ceilings.
 pub trait ShellConfig {
-    /// Maximum input buffer size (default: 128)
-    const MAX_INPUT: usize;
+    /// Buffer sizes, e.g. `Buffers<128, 8, 16, 64, 256, 10>` (see [`Buffers`])
+    type Buffers: BufferSizes;
+
+    /// Maximum input buffer size (default: `Buffers` input size)
+    const MAX_INPUT: usize = <Self::Buffers as BufferSizes>::INPUT;
+
+    /// Maximum path depth (default: `Buffers` path depth, at most `capacity::PATH_DEPTH`)
+    const MAX_PATH_DEPTH: usize = <Self::Buffers as BufferSizes>::PATH_DEPTH;
+
+    /// Maximum number of command arguments (default: `Buffers` argument count, at most `capacity::ARGS`)
+    const MAX_ARGS: usize = <Self::Buffers as BufferSizes>::ARGS;
+
+    /// Maximum prompt length (default: `Buffers` prompt size)
+    const MAX_PROMPT: usize = <Self::Buffers as BufferSizes>::PROMPT;
+
+    /// Maximum response message length (default: `Buffers` response size)
+    const MAX_RESPONSE: usize = <Self::Buffers as BufferSizes>::RESPONSE;
+
+    /// Command history size (default: `Buffers` history size)
+    const HISTORY_SIZE: usize = <Self::Buffers as BufferSizes>::HISTORY;
+
+    /// Idle time before automatic logout in milliseconds, driven by `Shell::tick()` (0 = disabled)
+    const SESSION_TIMEOUT_MS: u32 = 0;
+
+    /// Echo for masked password input (`None` = echo nothing, hides password length)
+    const MASK_CHAR: Option<char> = Some('*');
+
+    /// Show each password character in clear until the next keystroke, then mask it
+    ///
+    /// For touch keyboards. The last character is masked again on Enter, so nothing stays
+    /// revealed after submission. No effect when `MASK_CHAR` is `None`.
+    const MASK_REVEAL_LAST: bool = false;
+
+    /// Line ending for all shell output (default: `"\r\n"`; `"\n"` for raw pipes)
+    const LINE_ENDING: &'static str = "\r\n";
+
+    /// Report `PermissionDenied` instead of `InvalidPath` for inaccessible nodes
+    ///
+    /// Default `false` hides node existence; enable only for trusted internal tools.
+    const REVEAL_ACCESS_DENIED: bool = false;
+
+    /// Upper bound on async command duration in milliseconds (0 = unlimited)
+    ///
+    /// Requires a `Timer` set via `Shell::set_timer()`; without one, async commands run unbounded.
+    const ASYNC_TIMEOUT_MS: u32 = 0;
 
-    /// Maximum path depth (default: 8)
-    const MAX_PATH_DEPTH: usize;
+    /// Prefix the prompt with the last non-zero exit code (e.g. `[1] @/> `)
+    const SHOW_EXIT_CODE: bool = false;
 
-    /// Maximum number of command arguments (default: 16)
-    const MAX_ARGS: usize;
+    /// Feedback for rejected input (full buffer, no completion)
+    const BELL: BellMode = BellMode::Audible;
 
-    /// Maximum prompt length (default: 64)
-    const MAX_PROMPT: usize;
+    /// Longest prompt path in characters before the middle is elided (0 = never truncate)
+    ///
+    /// The final segment is always kept, e.g. `admin@/sys/.../leds> `, and the middle is only
+    /// elided when that shortens the path.
+    const MAX_PROMPT_PATH: usize = 0;
 
-    /// Maximum response message length (default: 256)
-    const MAX_RESPONSE: usize;
+    /// Indentation for indented responses, listings, help, and status messages
+    ///
+    /// Applied to every line of a multi-line indented response (e.g. `"    "` or `"\t"`).
+    const INDENT: &'static str = "  ";
 
-    /// Command history size (default: 10)
-    const HISTORY_SIZE: usize;
+    /// Lines of a command response or `ls` listing shown before pausing with `MSG_MORE`
+    /// (0 = never page; non-zero requires the `paging` feature)
+    ///
+    /// Space shows the next page, `q` (or Ctrl+C) drops the rest. The next page of a streamed
+    /// response re-runs the stream and skips the lines already shown, so streams should emit
+    /// the same output on every run. Logging out (or a session timeout) drops the rest.
+    const PAGE_SIZE: usize = 0;
+
+    /// Interpret `\n`, `\t`, `\r`, and `\xNN` (ASCII) escapes inside double-quoted arguments
+    ///
+    /// Off by default: commands receive the escaped character literally (`"\n"` → `n`).
+    const QUOTE_ESCAPES: bool = false;
+
+    /// Extra argument separator, e.g. `','` to accept `led,on` from a fixed protocol
+    ///
+    /// Whitespace always separates; quoting and escapes still apply (`"a,b"` is one argument).
+    const ARG_DELIMITER: char = ' ';
+
+    /// Run several commands from one line, e.g. `Some(';')` for `led on; status; led off`
+    ///
+    /// Segments (split outside quotes) run as tree commands in order; the first failure stops
+    /// the rest unless `CHAIN_CONTINUE_ON_ERROR`. History records the whole line.
+    const CHAIN_SEPARATOR: Option<char> = None;
+
+    /// Keep running chained commands after one fails (see `CHAIN_SEPARATOR`)
+    const CHAIN_CONTINUE_ON_ERROR: bool = false;
+
+    /// Prefix error messages with `CliError::code()`: `Error[0x0001]: Command not found`
+    const SHOW_ERROR_CODES: bool = false;
+
+    /// Arrow keys cycle through history instead of stopping at its ends
+    ///
+    /// The empty edit line sits between newest and oldest: Up from the oldest entry clears
+    /// the line, Down from an empty line recalls the oldest.
+    const HISTORY_WRAP: bool = false;
+
+    /// List `ls` entries alphabetically instead of in declaration order
+    const SORT_LISTINGS: bool = false;
+
+    /// Prefix each response with `[<ms>] ` from the clock set via `Shell::set_clock()`
+    const TIMESTAMP_OUTPUT: bool = false;
+
+    /// Echo typed input back (default `true`; initial state of `Shell::set_echo()`)
+    ///
+    /// Disable for front-ends that paint the line themselves from `Shell::input_line()`.
+    const ECHO_INPUT: bool = true;
+
+    /// Write the command and login prompts to I/O (default `true`)
+    ///
+    /// Disable for front-ends that render the prompt themselves from `Shell::current_prompt()`.
+    const EMIT_PROMPT: bool = true;
 
     // Message constants for user-visible strings
     // All stored in ROM, zero runtime cost
@@ -51,6 +149,288 @@ pub trait ShellConfig {
 
     /// Invalid login format message
     const MSG_INVALID_LOGIN_FORMAT: &'static str;
+
+    /// Password prompt for commands flagged `requires_reauth`
+    const MSG_REAUTH_PROMPT: &'static str = "Password: ";
+
+    /// Message shown when session idle timeout logs the user out
+    const MSG_TIMEOUT: &'static str = "Session timed out.";
+
+    /// Message shown when `exit`/`quit` deactivates the shell
+    const MSG_GOODBYE: &'static str = "Goodbye.";
+
+    /// Message shown (with the bell) when a command line reaches `MAX_INPUT` (empty = bell only)
+    const MSG_INPUT_TOO_LONG: &'static str = "Input too long.";
+
+    /// Message shown when the current directory no longer resolves and the shell returns to root
+    const MSG_PATH_RESET: &'static str = "Current directory is gone, returned to /.";
+
+    /// Pager prompt shown after each `PAGE_SIZE` lines of a long response
+    const MSG_MORE: &'static str = "-- More --";
+}
+
+/// How the shell signals rejected input.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BellMode {
+    /// ASCII BEL (`\x07`)
+    Audible,
+
+    /// Briefly toggle reverse video (`ESC [ ? 5 h` / `ESC [ ? 5 l`)
+    Visual,
+
+    /// No feedback
+    None,
+}
+
+/// Buffer sizes for a `ShellConfig`, as const generic parameters.
+///
+/// - `INPUT`: input line, arguments, and history entries in bytes
+/// - `PATH_DEPTH`: directory nesting
+/// - `ARGS`: command arguments
+/// - `PROMPT`: prompt in bytes
+/// - `RESPONSE`: `Response` message in bytes
+/// - `HISTORY`: history entries (0 with the `history` feature off)
+///
+/// ```
+/// use nut_shell::config::{Buffers, ShellConfig};
+///
+/// struct TinyConfig;
+///
+/// impl ShellConfig for TinyConfig {
+///     type Buffers = Buffers<32, 2, 4, 16, 64, 0>;
+///
+///     const MSG_WELCOME: &'static str = "hi";
+///     const MSG_LOGIN_PROMPT: &'static str = "Login> ";
+///     const MSG_LOGIN_SUCCESS: &'static str = "ok";
+///     const MSG_LOGIN_FAILED: &'static str = "failed";
+///     const MSG_LOGOUT: &'static str = "bye";
+///     const MSG_INVALID_LOGIN_FORMAT: &'static str = "name:password";
+/// }
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Buffers<
+    const INPUT: usize,
+    const PATH_DEPTH: usize,
+    const ARGS: usize,
+    const PROMPT: usize,
+    const RESPONSE: usize,
+    const HISTORY: usize,
+>;
+
+/// Sizes and storage types of a [`Buffers`] set.
+///
+/// Implemented only by `Buffers`; the associated types are the `heapless` buffers the shell
+/// allocates for a config.
+pub trait BufferSizes {
+    /// Input buffer size in bytes
+    const INPUT: usize;
+    /// Path stack depth
+    const PATH_DEPTH: usize;
+    /// Argument count
+    const ARGS: usize;
+    /// Prompt size in bytes
+    const PROMPT: usize;
+    /// Response message size in bytes
+    const RESPONSE: usize;
+    /// History entries
+    const HISTORY: usize;
+
+    /// Input line (`heapless::String<INPUT>`)
+    type Input: StringBuffer;
+    /// Navigation stack of child indices (`heapless::Vec<usize, PATH_DEPTH>`)
+    type Path: VecBuffer<usize>;
+    /// Command arguments (`heapless::Vec<Self::Input, ARGS>`)
+    type Args: VecBuffer<Self::Input>;
+    /// Prompt (`heapless::String<PROMPT>`)
+    type Prompt: StringBuffer;
+    /// Response message (`heapless::String<RESPONSE>`)
+    type Response: StringBuffer;
+    /// History entries with their outcome (`heapless::Vec<(Self::Input, bool), HISTORY>`)
+    type History: VecBuffer<(Self::Input, bool)>;
+}
+
+impl<
+    const INPUT: usize,
+    const PATH_DEPTH: usize,
+    const ARGS: usize,
+    const PROMPT: usize,
+    const RESPONSE: usize,
+    const HISTORY: usize,
+> BufferSizes for Buffers<INPUT, PATH_DEPTH, ARGS, PROMPT, RESPONSE, HISTORY>
+{
+    const INPUT: usize = INPUT;
+    const PATH_DEPTH: usize = PATH_DEPTH;
+    const ARGS: usize = ARGS;
+    const PROMPT: usize = PROMPT;
+    const RESPONSE: usize = RESPONSE;
+    const HISTORY: usize = HISTORY;
+
+    type Input = heapless::String<INPUT>;
+    type Path = heapless::Vec<usize, PATH_DEPTH>;
+    type Args = heapless::Vec<heapless::String<INPUT>, ARGS>;
+    type Prompt = heapless::String<PROMPT>;
+    type Response = heapless::String<RESPONSE>;
+    type History = heapless::Vec<(heapless::String<INPUT>, bool), HISTORY>;
+}
+
+/// Input buffer of config `C`.
+pub type InputBuffer<C> = <<C as ShellConfig>::Buffers as BufferSizes>::Input;
+
+/// Argument list of config `C`.
+pub type ArgsBuffer<C> = <<C as ShellConfig>::Buffers as BufferSizes>::Args;
+
+/// Prompt buffer of config `C`.
+pub type PromptBuffer<C> = <<C as ShellConfig>::Buffers as BufferSizes>::Prompt;
+
+/// Response message buffer of config `C`.
+pub type ResponseBuffer<C> = <<C as ShellConfig>::Buffers as BufferSizes>::Response;
+
+/// History storage of config `C`.
+pub type HistoryBuffer<C> = <<C as ShellConfig>::Buffers as BufferSizes>::History;
+
+/// Fixed-capacity string used for config-sized buffers (`heapless::String<N>`).
+///
+/// Lets generic code use a buffer whose size comes from `C::Buffers`.
+pub trait StringBuffer:
+    Default
+    + Clone
+    + PartialEq
+    + fmt::Debug
+    + fmt::Display
+    + fmt::Write
+    + Deref<Target = str>
+    + for<'a> TryFrom<&'a str>
+{
+    /// Contents as `&str`
+    fn as_str(&self) -> &str;
+    /// Append a char (`Err` when full)
+    fn push(&mut self, c: char) -> Result<(), heapless::CapacityError>;
+    /// Append a string (`Err`, unchanged, when it doesn't fit)
+    fn push_str(&mut self, s: &str) -> Result<(), heapless::CapacityError>;
+    /// Remove the last char
+    fn pop(&mut self) -> Option<char>;
+    /// Shorten to `len` bytes (must be a char boundary)
+    fn truncate(&mut self, len: usize);
+    /// Remove all contents
+    fn clear(&mut self);
+    /// Capacity in bytes
+    fn capacity(&self) -> usize;
+}
+
+impl<const N: usize> StringBuffer for heapless::String<N> {
+    fn as_str(&self) -> &str {
+        self.as_str()
+    }
+
+    fn push(&mut self, c: char) -> Result<(), heapless::CapacityError> {
+        self.push(c)
+    }
+
+    fn push_str(&mut self, s: &str) -> Result<(), heapless::CapacityError> {
+        self.push_str(s)
+    }
+
+    fn pop(&mut self) -> Option<char> {
+        self.pop()
+    }
+
+    fn truncate(&mut self, len: usize) {
+        self.truncate(len)
+    }
+
+    fn clear(&mut self) {
+        self.clear()
+    }
+
+    fn capacity(&self) -> usize {
+        self.capacity()
+    }
+}
+
+/// Fixed-capacity vector used for config-sized buffers (`heapless::Vec<T, N>`).
+pub trait VecBuffer<T>:
+    Default + Clone + PartialEq + fmt::Debug + Deref<Target = [T]> + DerefMut
+{
+    /// Copy `items` into a new buffer (`Err` when they don't fit)
+    fn from_slice(items: &[T]) -> Result<Self, heapless::CapacityError>
+    where
+        T: Clone;
+    /// Append an item (`Err(item)` when full)
+    fn push(&mut self, item: T) -> Result<(), T>;
+    /// Remove the last item
+    fn pop(&mut self) -> Option<T>;
+    /// Insert at `index`, shifting later items (`Err(item)` when full)
+    fn insert(&mut self, index: usize, item: T) -> Result<(), T>;
+    /// Remove the item at `index`, shifting later items
+    fn remove(&mut self, index: usize) -> T;
+    /// Shorten to `len` items
+    fn truncate(&mut self, len: usize);
+    /// Remove all items
+    fn clear(&mut self);
+    /// Capacity in items
+    fn capacity(&self) -> usize;
+    /// No room for another item
+    fn is_full(&self) -> bool {
+        self.len() == self.capacity()
+    }
+}
+
+impl<T: Clone + PartialEq + fmt::Debug, const N: usize> VecBuffer<T> for heapless::Vec<T, N> {
+    fn from_slice(items: &[T]) -> Result<Self, heapless::CapacityError>
+    where
+        T: Clone,
+    {
+        heapless::Vec::from_slice(items)
+    }
+
+    fn push(&mut self, item: T) -> Result<(), T> {
+        self.push(item)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        self.pop()
+    }
+
+    fn insert(&mut self, index: usize, item: T) -> Result<(), T> {
+        self.insert(index, item)
+    }
+
+    fn remove(&mut self, index: usize) -> T {
+        self.remove(index)
+    }
+
+    fn truncate(&mut self, len: usize) {
+        self.truncate(len)
+    }
+
+    fn clear(&mut self) {
+        self.clear()
+    }
+
+    fn capacity(&self) -> usize {
+        self.capacity()
+    }
+}
+
+/// Ceilings for per-command scratch and runtime-set buffers.
+///
+/// Token and path-name lists are built on the stack for each command. They hold one more
+/// entry than `MAX_ARGS`/`MAX_PATH_DEPTH` (the path), which stable Rust cannot express from
+/// the config, so they are sized here. `Shell::new()` rejects (at compile time) any config
+/// whose limit exceeds its ceiling.
+pub mod capacity {
+    /// Directory nesting ceiling (`MAX_PATH_DEPTH`)
+    pub const PATH_DEPTH: usize = 8;
+
+    /// Command argument ceiling (`MAX_ARGS`)
+    pub const ARGS: usize = 16;
+
+    /// Runtime message override ceiling in bytes (`Shell::set_welcome()`, `set_login_prompt()`)
+    pub const MESSAGE: usize = 128;
+
+    /// Runtime-registered command slots (`Shell::register_command()`)
+    #[cfg(feature = "dynamic")]
+    pub const DYNAMIC_COMMANDS: usize = 8;
 }
 
 /// Default configuration for typical embedded systems.
@@ -58,17 +438,11 @@ pub trait ShellConfig {
 pub struct DefaultConfig;
 
 impl ShellConfig for DefaultConfig {
-    const MAX_INPUT: usize = 128;
-    const MAX_PATH_DEPTH: usize = 8;
-    const MAX_ARGS: usize = 16;
-    const MAX_PROMPT: usize = 64;
-    const MAX_RESPONSE: usize = 256;
-
     #[cfg(feature = "history")]
-    const HISTORY_SIZE: usize = 10;
+    type Buffers = Buffers<128, 8, 16, 64, 256, 10>;
 
     #[cfg(not(feature = "history"))]
-    const HISTORY_SIZE: usize = 0;
+    type Buffers = Buffers<128, 8, 16, 64, 256, 0>;
 
     #[cfg(feature = "authentication")]
     const MSG_WELCOME: &'static str = "Welcome to nut-shell! Please login.";
@@ -87,17 +461,11 @@ impl ShellConfig for DefaultConfig {
 pub struct MinimalConfig;
 
 impl ShellConfig for MinimalConfig {
-    const MAX_INPUT: usize = 64;
-    const MAX_PATH_DEPTH: usize = 4;
-    const MAX_ARGS: usize = 8;
-    const MAX_PROMPT: usize = 32;
-    const MAX_RESPONSE: usize = 128;
-
     #[cfg(feature = "history")]
-    const HISTORY_SIZE: usize = 4;
+    type Buffers = Buffers<64, 4, 8, 32, 128, 4>;
 
     #[cfg(not(feature = "history"))]
-    const HISTORY_SIZE: usize = 0;
+    type Buffers = Buffers<64, 4, 8, 32, 128, 0>;
 
     const MSG_WELCOME: &'static str = "Welcome";
 
@@ -121,5 +489,11 @@ mod tests {
         const _FAILED: &str = DefaultConfig::MSG_LOGIN_FAILED;
         const _LOGOUT: &str = DefaultConfig::MSG_LOGOUT;
         const _FORMAT: &str = DefaultConfig::MSG_INVALID_LOGIN_FORMAT;
+        const _REAUTH: &str = DefaultConfig::MSG_REAUTH_PROMPT;
+        const _TIMEOUT: &str = DefaultConfig::MSG_TIMEOUT;
+        const _GOODBYE: &str = DefaultConfig::MSG_GOODBYE;
+        const _TOO_LONG: &str = DefaultConfig::MSG_INPUT_TOO_LONG;
+        const _PATH_RESET: &str = DefaultConfig::MSG_PATH_RESET;
+        const _MORE: &str = DefaultConfig::MSG_MORE;
     }
 }
@@ -20,6 +20,14 @@ pub enum CliError {
     /// SECURITY: Never reveal whether path exists vs. access denied
     InvalidPath,
 
+    /// User lacks access to an existing node
+    ///
+    /// Only returned when `ShellConfig::REVEAL_ACCESS_DENIED` is true (otherwise `InvalidPath`).
+    PermissionDenied {
+        /// Required access level (`AccessLevel::as_str()`)
+        required: &'static str,
+    },
+
     /// Wrong number of arguments
     InvalidArgumentCount {
         /// Minimum expected arguments
@@ -38,12 +46,30 @@ pub enum CliError {
         expected: heapless::String<32>,
     },
 
+    /// Argument doesn't fit where it is copied (never silently truncated)
+    ArgumentTooLong {
+        /// Which argument (0-indexed)
+        index: usize,
+    },
+
+    /// Command line has more tokens than `capacity::ARGS` arguments plus the path
+    TooManyArgs,
+
+    /// Input has an opening `"` without a matching close
+    UnterminatedQuote,
+
     /// Buffer capacity exceeded
     BufferFull,
 
     /// Path exceeds MAX_PATH_DEPTH
     PathTooDeep,
 
+    /// Path names a command where a directory is required (`cd`, `ls <path>`, or mid-path)
+    NotADirectory {
+        /// Name of the command found in place of a directory
+        name: &'static str,
+    },
+
     /// Authentication failed - wrong credentials
     #[cfg(feature = "authentication")]
     AuthenticationFailed,
@@ -52,9 +78,19 @@ pub enum CliError {
     #[cfg(feature = "authentication")]
     NotAuthenticated,
 
+    /// Command flagged `requires_auth` run without a logged-in user (always, without `authentication`)
+    AuthRequired,
+
+    /// Re-authentication for a privileged command failed (wrong password)
+    #[cfg(feature = "authentication")]
+    ReauthFailed,
+
     /// I/O error occurred
     IoError,
 
+    /// Operation not supported (e.g., password change on a read-only credential provider)
+    Unsupported,
+
     /// Async command called from sync context
     #[cfg(feature = "async")]
     AsyncInSyncContext,
@@ -62,6 +98,15 @@ pub enum CliError {
     /// Operation timed out
     Timeout,
 
+    /// Command was cancelled before completion (e.g. via `CancelToken`)
+    Cancelled,
+
+    /// Command ran less than `CommandMeta::min_interval_ms` ago
+    RateLimited {
+        /// Milliseconds until the command may run again
+        retry_in_ms: u32,
+    },
+
     /// Command executed but reported failure
     CommandFailed(heapless::String<128>),
 
@@ -69,11 +114,66 @@ pub enum CliError {
     Other(heapless::String<128>),
 }
 
+impl CliError {
+    /// Stable numeric code for this error's variant (for tooling; never reused or renumbered).
+    ///
+    /// | Code | Variant | Code | Variant |
+    /// |------|---------|------|---------|
+    /// | `0x0001` | `CommandNotFound` | `0x000D` | `NotAuthenticated` |
+    /// | `0x0002` | `InvalidPath` | `0x000E` | `AuthRequired` |
+    /// | `0x0003` | `PermissionDenied` | `0x000F` | `ReauthFailed` |
+    /// | `0x0004` | `InvalidArgumentCount` | `0x0010` | `IoError` |
+    /// | `0x0005` | `InvalidArgumentFormat` | `0x0011` | `Unsupported` |
+    /// | `0x0006` | `ArgumentTooLong` | `0x0012` | `AsyncInSyncContext` |
+    /// | `0x0007` | `TooManyArgs` | `0x0013` | `Timeout` |
+    /// | `0x0008` | `UnterminatedQuote` | `0x0014` | `Cancelled` |
+    /// | `0x0009` | `BufferFull` | `0x0015` | `RateLimited` |
+    /// | `0x000A` | `PathTooDeep` | `0x0016` | `CommandFailed` |
+    /// | `0x000B` | `NotADirectory` | `0x0017` | `Other` |
+    /// | `0x000C` | `AuthenticationFailed` | | |
+    ///
+    /// Shown as `Error[0x0001]: ...` with `ShellConfig::SHOW_ERROR_CODES`.
+    pub const fn code(&self) -> u16 {
+        match self {
+            CliError::CommandNotFound => 0x0001,
+            CliError::InvalidPath => 0x0002,
+            CliError::PermissionDenied { .. } => 0x0003,
+            CliError::InvalidArgumentCount { .. } => 0x0004,
+            CliError::InvalidArgumentFormat { .. } => 0x0005,
+            CliError::ArgumentTooLong { .. } => 0x0006,
+            CliError::TooManyArgs => 0x0007,
+            CliError::UnterminatedQuote => 0x0008,
+            CliError::BufferFull => 0x0009,
+            CliError::PathTooDeep => 0x000A,
+            CliError::NotADirectory { .. } => 0x000B,
+            #[cfg(feature = "authentication")]
+            CliError::AuthenticationFailed => 0x000C,
+            #[cfg(feature = "authentication")]
+            CliError::NotAuthenticated => 0x000D,
+            CliError::AuthRequired => 0x000E,
+            #[cfg(feature = "authentication")]
+            CliError::ReauthFailed => 0x000F,
+            CliError::IoError => 0x0010,
+            CliError::Unsupported => 0x0011,
+            #[cfg(feature = "async")]
+            CliError::AsyncInSyncContext => 0x0012,
+            CliError::Timeout => 0x0013,
+            CliError::Cancelled => 0x0014,
+            CliError::RateLimited { .. } => 0x0015,
+            CliError::CommandFailed(_) => 0x0016,
+            CliError::Other(_) => 0x0017,
+        }
+    }
+}
+
 impl fmt::Display for CliError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             CliError::CommandNotFound => write!(f, "Command not found"),
             CliError::InvalidPath => write!(f, "Invalid path"),
+            CliError::PermissionDenied { required } => {
+                write!(f, "Permission denied (requires {})", required)
+            }
             CliError::InvalidArgumentCount {
                 expected_min,
                 expected_max,
@@ -95,16 +195,30 @@ impl fmt::Display for CliError {
             } => {
                 write!(f, "Argument {}: expected {}", arg_index + 1, expected)
             }
+            CliError::ArgumentTooLong { index } => {
+                write!(f, "Argument {} too long", index + 1)
+            }
+            CliError::TooManyArgs => write!(f, "Too many arguments"),
+            CliError::UnterminatedQuote => write!(f, "Unterminated quote"),
             CliError::BufferFull => write!(f, "Buffer full"),
             CliError::PathTooDeep => write!(f, "Path too deep"),
+            CliError::NotADirectory { name } => write!(f, "{} is not a directory", name),
             #[cfg(feature = "authentication")]
             CliError::AuthenticationFailed => write!(f, "Authentication failed"),
             #[cfg(feature = "authentication")]
             CliError::NotAuthenticated => write!(f, "Not authenticated"),
+            CliError::AuthRequired => write!(f, "Authentication required"),
+            #[cfg(feature = "authentication")]
+            CliError::ReauthFailed => write!(f, "Re-authentication failed"),
             CliError::IoError => write!(f, "I/O error"),
+            CliError::Unsupported => write!(f, "Operation not supported"),
             #[cfg(feature = "async")]
             CliError::AsyncInSyncContext => write!(f, "Async command requires async context"),
             CliError::Timeout => write!(f, "Timeout"),
+            CliError::Cancelled => write!(f, "Cancelled"),
+            CliError::RateLimited { retry_in_ms } => {
+                write!(f, "Rate limited (retry in {} ms)", retry_in_ms)
+            }
             CliError::CommandFailed(msg) => write!(f, "{}", msg),
             CliError::Other(msg) => write!(f, "{}", msg),
         }
@@ -117,6 +231,19 @@ mod tests {
     extern crate std;
     use std::format;
 
+    #[test]
+    fn test_error_codes_are_stable() {
+        assert_eq!(CliError::CommandNotFound.code(), 0x0001);
+        assert_eq!(
+            CliError::PermissionDenied { required: "Admin" }.code(),
+            0x0003
+        );
+        assert_eq!(CliError::ArgumentTooLong { index: 2 }.code(), 0x0006);
+        assert_eq!(CliError::AuthRequired.code(), 0x000E);
+        assert_eq!(CliError::RateLimited { retry_in_ms: 5 }.code(), 0x0015);
+        assert_eq!(CliError::Other(heapless::String::new()).code(), 0x0017);
+    }
+
     #[test]
     fn test_error_display() {
         assert_eq!(
@@ -124,6 +251,40 @@ mod tests {
             "Command not found"
         );
         assert_eq!(format!("{}", CliError::InvalidPath), "Invalid path");
+        assert_eq!(
+            format!("{}", CliError::PermissionDenied { required: "Admin" }),
+            "Permission denied (requires Admin)"
+        );
+        assert_eq!(
+            format!("{}", CliError::UnterminatedQuote),
+            "Unterminated quote"
+        );
+        assert_eq!(
+            format!("{}", CliError::Unsupported),
+            "Operation not supported"
+        );
+        assert_eq!(format!("{}", CliError::Cancelled), "Cancelled");
+        assert_eq!(
+            format!("{}", CliError::ArgumentTooLong { index: 0 }),
+            "Argument 1 too long"
+        );
+        assert_eq!(
+            format!("{}", CliError::AuthRequired),
+            "Authentication required"
+        );
+        assert_eq!(
+            format!("{}", CliError::RateLimited { retry_in_ms: 250 }),
+            "Rate limited (retry in 250 ms)"
+        );
+        assert_eq!(
+            format!("{}", CliError::NotADirectory { name: "reboot" }),
+            "reboot is not a directory"
+        );
+        #[cfg(feature = "authentication")]
+        assert_eq!(
+            format!("{}", CliError::ReauthFailed),
+            "Re-authentication failed"
+        );
 
         let err = CliError::InvalidArgumentCount {
             expected_min: 2,
@@ -1,6 +1,8 @@
 //! Character I/O abstraction for platform-agnostic input/output.
 //!
 //! The `CharIo` trait provides non-blocking character-level I/O operations.
+//! Byte-oriented transports (UART, USB-CDC) can implement `ByteIo` instead and use
+//! the `Utf8Io` adapter, or feed raw bytes via `Shell::process_byte()`.
 
 /// Platform-agnostic character I/O trait.
 /// Implementations provide non-blocking character I/O with platform-specific buffering.
@@ -13,16 +15,642 @@ pub trait CharIo {
     /// Returns `Ok(Some(char))` if available, `Ok(None)` otherwise.
     fn get_char(&mut self) -> Result<Option<char>, Self::Error>;
 
+    /// Read character, or report that the transport closed (non-blocking, used by `Shell::poll()`).
+    ///
+    /// Default wraps `get_char()` and never reports `Disconnected`. Override for transports
+    /// that can detect a closed connection (TCP, USB-CDC DTR drop) so the session is ended.
+    fn read(&mut self) -> Result<ReadState, Self::Error> {
+        self.get_char().map(ReadState::from)
+    }
+
     /// Write character to output buffer (must not block indefinitely).
     fn put_char(&mut self, c: char) -> Result<(), Self::Error>;
 
     /// Write string to output buffer.
     ///
     /// Default calls `put_char()` per character. Override for efficiency if needed.
+    /// Buffered implementations should return an error when the buffer cannot take `s`
+    /// rather than silently dropping bytes; the shell stops and surfaces it to the caller.
     fn write_str(&mut self, s: &str) -> Result<(), Self::Error> {
         for c in s.chars() {
             self.put_char(c)?;
         }
         Ok(())
     }
+
+    /// Drain buffered output (called by the shell after each complete response).
+    ///
+    /// Default does nothing. Buffered implementations can block or hand off to a
+    /// transmitter here so large responses don't overflow their buffer.
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Result of a non-blocking `CharIo::read()`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ReadState {
+    /// No data available
+    None,
+
+    /// Character received
+    Char(char),
+
+    /// Transport closed (peer hung up)
+    Disconnected,
+}
+
+impl From<Option<char>> for ReadState {
+    fn from(c: Option<char>) -> Self {
+        match c {
+            Some(c) => ReadState::Char(c),
+            None => ReadState::None,
+        }
+    }
+}
+
+/// Platform-agnostic byte I/O trait (binary-safe transports).
+///
+/// Wrap in `Utf8Io` to use with `Shell`. Casting bytes straight to `char` is wrong for
+/// any byte ≥ 0x80 - multibyte UTF-8 must be decoded first.
+pub trait ByteIo {
+    /// Platform-specific error type
+    type Error;
+
+    /// Read byte if available (non-blocking).
+    ///
+    /// Returns `Ok(Some(u8))` if available, `Ok(None)` otherwise.
+    fn get_byte(&mut self) -> Result<Option<u8>, Self::Error>;
+
+    /// Write byte to output buffer (must not block indefinitely).
+    fn put_byte(&mut self, b: u8) -> Result<(), Self::Error>;
+
+    /// Write bytes to output buffer.
+    ///
+    /// Default calls `put_byte()` per byte. Override for efficiency if needed.
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+        for &b in bytes {
+            self.put_byte(b)?;
+        }
+        Ok(())
+    }
+
+    /// Drain buffered output (see `CharIo::flush()`). Default does nothing.
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Incremental UTF-8 decoder (bytes in, `char`s out).
+///
+/// Accumulates multibyte sequences across calls. Invalid bytes yield
+/// `char::REPLACEMENT_CHARACTER`; a truncated sequence is dropped when the next lead byte arrives.
+#[derive(Debug, Clone, Default)]
+pub struct Utf8Decoder {
+    buf: [u8; 4],
+    len: u8,
+    expected: u8,
+}
+
+impl Utf8Decoder {
+    /// Create decoder with no pending bytes.
+    pub const fn new() -> Self {
+        Self {
+            buf: [0; 4],
+            len: 0,
+            expected: 0,
+        }
+    }
+
+    /// Feed one byte. Returns `Some(char)` when a sequence completes, `None` while accumulating.
+    pub fn push(&mut self, byte: u8) -> Option<char> {
+        if self.expected == 0 {
+            // Lead byte: determine sequence length
+            let expected = match byte {
+                0x00..=0x7F => return Some(byte as char),
+                0xC2..=0xDF => 2,
+                0xE0..=0xEF => 3,
+                0xF0..=0xF4 => 4,
+                _ => return Some(char::REPLACEMENT_CHARACTER), // Stray continuation/invalid lead
+            };
+            self.buf[0] = byte;
+            self.len = 1;
+            self.expected = expected;
+            return None;
+        }
+
+        if byte & 0xC0 != 0x80 {
+            // Sequence interrupted - drop it and restart with this byte
+            self.reset();
+            return self.push(byte);
+        }
+
+        self.buf[self.len as usize] = byte;
+        self.len += 1;
+        if self.len < self.expected {
+            return None;
+        }
+
+        let decoded = core::str::from_utf8(&self.buf[..self.len as usize])
+            .ok()
+            .and_then(|s| s.chars().next())
+            .unwrap_or(char::REPLACEMENT_CHARACTER); // Overlong/surrogate
+        self.reset();
+        Some(decoded)
+    }
+
+    /// Check if a multibyte sequence is partially accumulated.
+    pub fn is_pending(&self) -> bool {
+        self.expected != 0
+    }
+
+    /// Discard any partially accumulated sequence.
+    pub fn reset(&mut self) {
+        self.len = 0;
+        self.expected = 0;
+    }
+}
+
+/// Adapter implementing `CharIo` over a `ByteIo` transport with UTF-8 encoding/decoding.
+#[derive(Debug)]
+pub struct Utf8Io<B: ByteIo> {
+    inner: B,
+    decoder: Utf8Decoder,
+}
+
+impl<B: ByteIo> Utf8Io<B> {
+    /// Wrap byte transport.
+    pub const fn new(inner: B) -> Self {
+        Self {
+            inner,
+            decoder: Utf8Decoder::new(),
+        }
+    }
+
+    /// Get reference to wrapped transport.
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+
+    /// Get mutable reference to wrapped transport.
+    pub fn inner_mut(&mut self) -> &mut B {
+        &mut self.inner
+    }
+
+    /// Unwrap and return the byte transport.
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+}
+
+impl<B: ByteIo> CharIo for Utf8Io<B> {
+    type Error = B::Error;
+
+    fn get_char(&mut self) -> Result<Option<char>, Self::Error> {
+        // Drain available bytes until a full char decodes (or transport runs dry)
+        while let Some(byte) = self.inner.get_byte()? {
+            if let Some(c) = self.decoder.push(byte) {
+                return Ok(Some(c));
+            }
+        }
+        Ok(None)
+    }
+
+    fn put_char(&mut self, c: char) -> Result<(), Self::Error> {
+        let mut buf = [0u8; 4];
+        self.inner.write_bytes(c.encode_utf8(&mut buf).as_bytes())
+    }
+
+    fn write_str(&mut self, s: &str) -> Result<(), Self::Error> {
+        self.inner.write_bytes(s.as_bytes())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush()
+    }
+}
+
+/// `CharIo` that buffers output for a transport written outside the shell (deferred flush).
+///
+/// Suits async tasks that feed input via `Shell::process_bytes_async()` and await their own
+/// writes: after processing, send `shell.io().pending()` and `clear()` it, or `drain()` it
+/// synchronously. `get_char()` always returns `None`. Output that doesn't fit in `N` bytes is
+/// rejected with `Err(())`, so flush at least once per processed packet.
+#[derive(Debug, Clone, Default)]
+pub struct DeferredIo<const N: usize> {
+    buffer: heapless::Vec<u8, N>,
+}
+
+impl<const N: usize> DeferredIo<N> {
+    /// Create with an empty buffer.
+    pub const fn new() -> Self {
+        Self {
+            buffer: heapless::Vec::new(),
+        }
+    }
+
+    /// Check if output is waiting to be sent.
+    pub fn has_pending(&self) -> bool {
+        !self.buffer.is_empty()
+    }
+
+    /// Buffered output (UTF-8 bytes) not yet sent.
+    pub fn pending(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    /// Discard buffered output (after sending `pending()`).
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+    }
+
+    /// Pass buffered output to `f` (only if any is pending), then clear it.
+    pub fn drain(&mut self, mut f: impl FnMut(&[u8])) {
+        if self.has_pending() {
+            f(&self.buffer);
+            self.buffer.clear();
+        }
+    }
+}
+
+impl<const N: usize> CharIo for DeferredIo<N> {
+    type Error = ();
+
+    fn get_char(&mut self) -> Result<Option<char>, Self::Error> {
+        // Input is fed to the shell directly, not polled
+        Ok(None)
+    }
+
+    fn put_char(&mut self, c: char) -> Result<(), Self::Error> {
+        let mut buf = [0u8; 4];
+        self.write_str(c.encode_utf8(&mut buf))
+    }
+
+    fn write_str(&mut self, s: &str) -> Result<(), Self::Error> {
+        // All or nothing - never leave half a string (or char) in the buffer
+        self.buffer.extend_from_slice(s.as_bytes()).map_err(|_| ())
+    }
+}
+
+/// `CharIo` that never has input and discards all output.
+///
+/// For headless shells driven through `Shell::execute_line()`, size measurements, and as
+/// the smallest complete `CharIo` implementation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NullIo;
+
+impl CharIo for NullIo {
+    type Error = core::convert::Infallible;
+
+    fn get_char(&mut self) -> Result<Option<char>, Self::Error> {
+        Ok(None)
+    }
+
+    fn put_char(&mut self, _c: char) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn write_str(&mut self, _s: &str) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// `CharIo` wrapper expanding lone `\n` to `\r\n` on output (avoids staircasing).
+///
+/// Handlers can then emit plain `\n`; `\r\n` already written by the shell passes through
+/// unchanged, also when split across writes. Input, `read()`, and `flush()` are forwarded.
+#[derive(Debug)]
+pub struct CrlfTranslate<IO: CharIo> {
+    inner: IO,
+    last_was_cr: bool,
+}
+
+impl<IO: CharIo> CrlfTranslate<IO> {
+    /// Wrap I/O.
+    pub const fn new(inner: IO) -> Self {
+        Self {
+            inner,
+            last_was_cr: false,
+        }
+    }
+
+    /// Get reference to wrapped I/O.
+    pub fn inner(&self) -> &IO {
+        &self.inner
+    }
+
+    /// Get mutable reference to wrapped I/O.
+    pub fn inner_mut(&mut self) -> &mut IO {
+        &mut self.inner
+    }
+
+    /// Unwrap and return the wrapped I/O.
+    pub fn into_inner(self) -> IO {
+        self.inner
+    }
+}
+
+impl<IO: CharIo> CharIo for CrlfTranslate<IO> {
+    type Error = IO::Error;
+
+    fn get_char(&mut self) -> Result<Option<char>, Self::Error> {
+        self.inner.get_char()
+    }
+
+    fn read(&mut self) -> Result<ReadState, Self::Error> {
+        self.inner.read()
+    }
+
+    fn put_char(&mut self, c: char) -> Result<(), Self::Error> {
+        if c == '\n' && !self.last_was_cr {
+            self.inner.write_str("\r\n")?;
+        } else {
+            self.inner.put_char(c)?;
+        }
+        self.last_was_cr = c == '\r';
+        Ok(())
+    }
+
+    fn write_str(&mut self, s: &str) -> Result<(), Self::Error> {
+        let mut segments = s.split('\n').peekable();
+
+        while let Some(segment) = segments.next() {
+            if !segment.is_empty() {
+                self.inner.write_str(segment)?;
+                self.last_was_cr = segment.ends_with('\r');
+            }
+            if segments.peek().is_none() {
+                break;
+            }
+
+            // Segment followed by '\n' - add '\r' unless one precedes it
+            let ending = if self.last_was_cr { "\n" } else { "\r\n" };
+            self.inner.write_str(ending)?;
+            self.last_was_cr = false;
+        }
+
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush()
+    }
+}
+
+/// `core::fmt::Write` adapter over a `CharIo` (stream formatted output without a buffer).
+///
+/// Lone `\n` and `\r\n` are both written as the configured line ending (default `"\r\n"`,
+/// use `ShellConfig::LINE_ENDING` to match the shell). I/O errors surface as `fmt::Error`.
+#[derive(Debug)]
+pub struct FmtWriter<'a, IO: CharIo> {
+    io: &'a mut IO,
+    line_ending: &'static str,
+    last_was_cr: bool,
+}
+
+impl<'a, IO: CharIo> FmtWriter<'a, IO> {
+    /// Wrap I/O with `"\r\n"` line endings.
+    pub fn new(io: &'a mut IO) -> Self {
+        Self::with_line_ending(io, "\r\n")
+    }
+
+    /// Wrap I/O with a specific line ending.
+    pub fn with_line_ending(io: &'a mut IO, line_ending: &'static str) -> Self {
+        Self {
+            io,
+            line_ending,
+            last_was_cr: false,
+        }
+    }
+}
+
+impl<IO: CharIo> core::fmt::Write for FmtWriter<'_, IO> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let mut segments = s.split('\n').peekable();
+
+        while let Some(segment) = segments.next() {
+            if segments.peek().is_none() {
+                // Trailing text (not followed by '\n')
+                if !segment.is_empty() {
+                    self.io.write_str(segment).map_err(|_| core::fmt::Error)?;
+                    self.last_was_cr = segment.ends_with('\r');
+                }
+                break;
+            }
+
+            // Segment followed by '\n' - drop '\r' of a "\r\n" pair, emit configured ending
+            let ending = match segment.strip_suffix('\r') {
+                Some(text) => {
+                    self.io.write_str(text).map_err(|_| core::fmt::Error)?;
+                    self.line_ending
+                }
+                None if segment.is_empty() && self.last_was_cr => {
+                    // '\r' already written by previous call
+                    self.line_ending
+                        .strip_prefix('\r')
+                        .unwrap_or(self.line_ending)
+                }
+                None => {
+                    self.io.write_str(segment).map_err(|_| core::fmt::Error)?;
+                    self.line_ending
+                }
+            };
+            self.io.write_str(ending).map_err(|_| core::fmt::Error)?;
+            self.last_was_cr = false;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode_all(bytes: &[u8]) -> heapless::String<32> {
+        let mut decoder = Utf8Decoder::new();
+        let mut out = heapless::String::new();
+        for &b in bytes {
+            if let Some(c) = decoder.push(b) {
+                out.push(c).unwrap();
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn test_decoder_ascii_passthrough() {
+        assert_eq!(decode_all(b"abc"), "abc");
+    }
+
+    #[test]
+    fn test_decoder_multibyte() {
+        assert_eq!(decode_all("é€😀".as_bytes()), "é€😀");
+
+        let mut decoder = Utf8Decoder::new();
+        assert_eq!(decoder.push(0xC3), None);
+        assert!(decoder.is_pending());
+        assert_eq!(decoder.push(0xA9), Some('é'));
+        assert!(!decoder.is_pending());
+    }
+
+    #[test]
+    fn test_decoder_invalid_sequences() {
+        // Stray continuation byte
+        assert_eq!(decode_all(&[0x80, b'a']), "\u{FFFD}a");
+        // Truncated sequence is dropped, following byte survives
+        assert_eq!(decode_all(&[0xC3, b'a']), "a");
+        // Overlong encoding / invalid lead
+        assert_eq!(decode_all(&[0xC0, 0xAF]), "\u{FFFD}\u{FFFD}");
+        // Surrogate (ED A0 80)
+        assert_eq!(decode_all(&[0xED, 0xA0, 0x80]), "\u{FFFD}");
+    }
+
+    struct MockBytes {
+        input: heapless::Deque<u8, 16>,
+        output: heapless::Vec<u8, 32>,
+    }
+
+    impl ByteIo for MockBytes {
+        type Error = ();
+        fn get_byte(&mut self) -> Result<Option<u8>, ()> {
+            Ok(self.input.pop_front())
+        }
+        fn put_byte(&mut self, b: u8) -> Result<(), ()> {
+            self.output.push(b).map_err(|_| ())
+        }
+    }
+
+    #[test]
+    fn test_utf8_io_roundtrip() {
+        let mut input = heapless::Deque::new();
+        for &b in "aé".as_bytes() {
+            input.push_back(b).unwrap();
+        }
+        let mut io = Utf8Io::new(MockBytes {
+            input,
+            output: heapless::Vec::new(),
+        });
+
+        assert_eq!(io.get_char(), Ok(Some('a')));
+        assert_eq!(io.read(), Ok(ReadState::Char('é')));
+        assert_eq!(io.read(), Ok(ReadState::None));
+
+        io.put_char('ü').unwrap();
+        io.write_str("ok").unwrap();
+        assert_eq!(io.inner().output.as_slice(), "üok".as_bytes());
+    }
+
+    #[test]
+    fn test_deferred_io_buffers_until_drained() {
+        let mut io = DeferredIo::<8>::new();
+        assert!(!io.has_pending());
+        assert_eq!(io.get_char(), Ok(None));
+
+        io.write_str("ab").unwrap();
+        io.put_char('é').unwrap();
+        assert!(io.has_pending());
+        assert_eq!(io.pending(), "abé".as_bytes());
+
+        let mut sent = heapless::Vec::<u8, 8>::new();
+        io.drain(|bytes| sent.extend_from_slice(bytes).unwrap());
+        assert_eq!(sent.as_slice(), "abé".as_bytes());
+        assert!(!io.has_pending());
+
+        // Drain with nothing pending never calls back
+        io.drain(|_| panic!("nothing pending"));
+    }
+
+    #[test]
+    fn test_deferred_io_rejects_overflow_whole() {
+        let mut io = DeferredIo::<4>::new();
+        io.write_str("abc").unwrap();
+        assert_eq!(io.write_str("de"), Err(()));
+        assert_eq!(io.put_char('é'), Err(()));
+        assert_eq!(io.pending(), b"abc");
+
+        io.clear();
+        io.write_str("abcd").unwrap();
+        assert_eq!(io.pending(), b"abcd");
+    }
+
+    struct MockChars {
+        output: heapless::String<64>,
+    }
+
+    impl CharIo for MockChars {
+        type Error = ();
+        fn get_char(&mut self) -> Result<Option<char>, ()> {
+            Ok(None)
+        }
+        fn put_char(&mut self, c: char) -> Result<(), ()> {
+            self.output.push(c).map_err(|_| ())
+        }
+    }
+
+    #[test]
+    fn test_null_io_discards_output() {
+        let mut io = NullIo;
+        assert_eq!(io.write_str("ignored"), Ok(()));
+        assert_eq!(io.put_char('x'), Ok(()));
+        assert_eq!(io.read(), Ok(ReadState::None));
+    }
+
+    #[test]
+    fn test_crlf_translate_expands_lone_newlines() {
+        let mut io = CrlfTranslate::new(DeferredIo::<64>::new());
+        io.write_str("a\nb\r\n").unwrap();
+        io.put_char('\n').unwrap();
+        // "\r\n" split across writes is not doubled
+        io.write_str("c\r").unwrap();
+        io.write_str("\nd").unwrap();
+        io.put_char('\r').unwrap();
+        io.put_char('\n').unwrap();
+        assert_eq!(io.inner().pending(), b"a\r\nb\r\n\r\nc\r\nd\r\n");
+    }
+
+    #[test]
+    fn test_fmt_writer_translates_newlines() {
+        use core::fmt::Write;
+
+        let mut io = MockChars {
+            output: heapless::String::new(),
+        };
+        let mut out = FmtWriter::new(&mut io);
+        write!(out, "a={}\nb={}\r\n", 1, 2).unwrap();
+        assert_eq!(io.output, "a=1\r\nb=2\r\n");
+
+        let mut io = MockChars {
+            output: heapless::String::new(),
+        };
+        let mut out = FmtWriter::with_line_ending(&mut io, "\n");
+        out.write_str("x\r\ny\n").unwrap();
+        assert_eq!(io.output, "x\ny\n");
+    }
+
+    #[test]
+    fn test_fmt_writer_crlf_split_across_writes() {
+        use core::fmt::Write;
+
+        let mut io = MockChars {
+            output: heapless::String::new(),
+        };
+        let mut out = FmtWriter::new(&mut io);
+        out.write_str("line\r").unwrap();
+        out.write_str("\nnext").unwrap();
+        assert_eq!(io.output, "line\r\nnext");
+    }
+
+    #[test]
+    fn test_fmt_writer_surfaces_io_error() {
+        use core::fmt::Write;
+
+        let mut io = MockChars {
+            output: heapless::String::new(),
+        };
+        let mut out = FmtWriter::new(&mut io);
+        let long = [b'x'; 65];
+        let long = core::str::from_utf8(&long).unwrap();
+        assert!(out.write_str(long).is_err());
+    }
 }
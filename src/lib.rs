@@ -14,9 +14,15 @@
 //! ## Optional Features
 //!
 //! - `authentication` - User login/logout, password hashing, credential providers
+//! - `pbkdf2` - PBKDF2-HMAC-SHA256 password hasher (implies `authentication`)
+//! - `argon2` - Argon2id password hasher (implies `authentication`)
+//! - `roles` - Per-command role requirements alongside access levels (implies `authentication`)
 //! - `completion` - Tab completion for commands and paths
 //! - `history` - Command history with up/down arrow navigation
 //! - `async` - Async command execution support
+//! - `dynamic` - Runtime command registration alongside the const tree
+//! - `defmt` - `defmt` trace points in path resolution, dispatch, and login (bare-metal targets)
+//! - `testing` - `ScriptedSession` keystroke-scripting harness for tests
 //!
 //! The library provides a `#[derive(AccessLevel)]` macro that's always available.
 //!
@@ -36,6 +42,15 @@ extern crate sha2;
 #[cfg(feature = "authentication")]
 extern crate subtle;
 
+#[cfg(feature = "pbkdf2")]
+extern crate pbkdf2;
+
+#[cfg(feature = "argon2")]
+extern crate argon2;
+
+#[cfg(feature = "defmt")]
+extern crate defmt;
+
 // Re-export derive macro (always available)
 pub use nut_shell_macros::AccessLevel;
 
@@ -43,6 +58,10 @@ pub use nut_shell_macros::AccessLevel;
 // Module Declarations
 // ============================================================================
 
+// Internal trace macros (must precede modules using them)
+#[macro_use]
+mod trace;
+
 // I/O & Access Control Foundation
 pub mod config;
 pub mod io;
@@ -62,35 +81,59 @@ pub mod response;
 // Shell orchestration
 pub mod shell;
 
+// Test harness
+#[cfg(feature = "testing")]
+pub mod testing;
+
 // ============================================================================
 // Re-exports - Public API
 // ============================================================================
 
 // Core I/O
-pub use io::CharIo;
+pub use io::{ByteIo, CharIo, CrlfTranslate, DeferredIo, NullIo, ReadState, Utf8Io};
 
 // Configuration
-pub use config::{DefaultConfig, MinimalConfig, ShellConfig};
+pub use config::{BellMode, DefaultConfig, MinimalConfig, ShellConfig};
 
 // Error types
 pub use error::CliError;
 
 // Tree types
-pub use tree::{CommandKind, CommandMeta, Directory, Node};
+pub use tree::{ArgType, CommandKind, CommandMeta, Directory, Node, NodeRef};
 
 // Access control (always available, even without authentication feature)
-pub use auth::{AccessLevel, User};
+pub use auth::{AccessLevel, User, UserBuilder};
 
 // Response types
-pub use response::Response;
+pub use response::{Response, ShellAction};
 
 // Shell types
-pub use shell::handler::CommandHandler;
-pub use shell::{CliState, HistoryDirection, Request, Shell};
+pub use shell::handler::{CommandContext, CommandHandler};
+pub use shell::{
+    AuditEvent, CliState, HistoryDirection, Request, ResolveKind, SessionState, Shell,
+};
+
+#[cfg(feature = "async")]
+pub use shell::handler::CancelToken;
+
+#[cfg(feature = "async")]
+pub use shell::timer::Timer;
 
 // Optional feature re-exports (authentication-only types)
 #[cfg(feature = "authentication")]
-pub use auth::{ConstCredentialProvider, CredentialProvider, PasswordHasher, Sha256Hasher};
+pub use auth::{
+    AuthResult, ConstCredentialProvider, CredentialProvider, EntropySource, PasswordHasher,
+    RamCredentialProvider, Sha256Hasher,
+};
+
+#[cfg(feature = "pbkdf2")]
+pub use auth::Pbkdf2Hasher;
+
+#[cfg(feature = "argon2")]
+pub use auth::Argon2Hasher;
+
+#[cfg(feature = "roles")]
+pub use auth::RoleSet;
 
 // ============================================================================
 // Library Metadata
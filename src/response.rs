@@ -2,15 +2,41 @@
 //!
 //! `Response` represents successful execution with message and formatting flags.
 
-use crate::config::ShellConfig;
+use crate::config::{ResponseBuffer, ShellConfig, StringBuffer};
+use crate::error::CliError;
 use core::marker::PhantomData;
 
+/// Output producer for streamed responses.
+///
+/// Called by the shell with an emitter; each `emit(chunk)` is written straight to I/O.
+/// Return `Err(())` (or propagate the emitter's error) to stop early.
+pub type StreamFn = fn(emit: &mut dyn FnMut(&str) -> Result<(), ()>) -> Result<(), ()>;
+
+/// Session change requested by a command, applied by the shell after the response.
+///
+/// Only interactive input (`process_char()`) acts on it; `execute_line()` callers get the
+/// response back and decide themselves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ShellAction {
+    /// No change
+    #[default]
+    None,
+    /// End the session and show the login prompt (like `logout`; no-op without authentication)
+    Logout,
+    /// Deactivate the shell (like `exit`; no prompt until `activate()`)
+    Deactivate,
+    /// Clear the screen before the response is written
+    ClearScreen,
+}
+
 /// Command execution response with message and formatting flags.
-/// Command failures return `Err(CliError::CommandFailed(msg))`, not `Response`.
-#[derive(Debug, Clone, PartialEq)]
+/// Command failures return `Err(CliError::CommandFailed(msg))`, not `Response`,
+/// unless the command wants to report a specific non-zero `exit_code` (see `failure()`).
+#[derive(Debug, PartialEq)]
+#[allow(unpredictable_function_pointer_comparisons)] // `stream` compared by address (test-only use)
 pub struct Response<C: ShellConfig> {
-    /// Response message (uses C::MAX_RESPONSE buffer size)
-    pub message: heapless::String<256>, // TODO: Use C::MAX_RESPONSE when const generics stabilize
+    /// Response message (`C::Buffers` response size)
+    pub message: ResponseBuffer<C>,
 
     /// Message is inline (don't echo newline after command input)
     pub inline_message: bool,
@@ -31,7 +57,16 @@ pub struct Response<C: ShellConfig> {
     #[cfg(feature = "history")]
     pub exclude_from_history: bool,
 
-    /// Phantom data for config type (will be used when const generics stabilize)
+    /// Streamed output (replaces `message`, not limited by `MAX_RESPONSE`)
+    pub stream: Option<StreamFn>,
+
+    /// Exit code for scripted callers (0 = success), see `Shell::last_exit_code()`
+    pub exit_code: u8,
+
+    /// Session change to apply after the response (see `with_action()`)
+    pub action: ShellAction,
+
+    /// Config type marker (zero-size)
     _phantom: PhantomData<C>,
 }
 
@@ -39,9 +74,12 @@ impl<C: ShellConfig> Response<C> {
     /// Create success response with default formatting.
     ///
     /// Default: include in history, show prompt, add postfix newline.
+    /// Messages longer than `C::MAX_RESPONSE` bytes are dropped (empty message).
     pub fn success(message: &str) -> Self {
-        let mut msg = heapless::String::new();
-        let _ = msg.push_str(message);
+        let mut msg = ResponseBuffer::<C>::default();
+        if message.len() <= C::MAX_RESPONSE {
+            let _ = msg.push_str(message);
+        }
 
         Self {
             message: msg,
@@ -52,10 +90,34 @@ impl<C: ShellConfig> Response<C> {
             show_prompt: true,
             #[cfg(feature = "history")]
             exclude_from_history: false,
+            stream: None,
+            exit_code: 0,
+            action: ShellAction::None,
             _phantom: PhantomData,
         }
     }
 
+    /// Create response whose output is produced in chunks by `stream`.
+    ///
+    /// For output larger than `MAX_RESPONSE` (memory dumps, long listings). Formatting
+    /// flags apply to the streamed text; chunks should not split a line ending. Paged output
+    /// (`ShellConfig::PAGE_SIZE`) re-runs `stream` for each page, so it should emit the same
+    /// text on every run.
+    pub fn streamed(stream: StreamFn) -> Self {
+        let mut response = Self::success("");
+        response.stream = Some(stream);
+        response
+    }
+
+    /// Create response reporting failure with a non-zero exit `code`.
+    ///
+    /// Output is formatted like `success()`; only `exit_code` differs.
+    pub fn failure(message: &str, code: u8) -> Self {
+        let mut response = Self::success(message);
+        response.exit_code = code;
+        response
+    }
+
     /// Create success response that excludes input from history.
     ///
     /// Use for commands handling sensitive data (passwords, credentials).
@@ -102,6 +164,99 @@ impl<C: ShellConfig> Response<C> {
         self.show_prompt = false;
         self
     }
+
+    /// Builder method to request a session change (logout, deactivate, clear screen).
+    pub fn with_action(mut self, action: ShellAction) -> Self {
+        self.action = action;
+        self
+    }
+}
+
+// Manual impl: derive would require `C: Clone`, but `C` is only a marker
+impl<C: ShellConfig> Clone for Response<C> {
+    fn clone(&self) -> Self {
+        Self {
+            message: self.message.clone(),
+            inline_message: self.inline_message,
+            prefix_newline: self.prefix_newline,
+            indent_message: self.indent_message,
+            postfix_newline: self.postfix_newline,
+            show_prompt: self.show_prompt,
+            #[cfg(feature = "history")]
+            exclude_from_history: self.exclude_from_history,
+            stream: self.stream,
+            exit_code: self.exit_code,
+            action: self.action,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+/// Column-aligned text table for response messages.
+///
+/// Rows hold borrowed cells; `render()` pads each column to its widest cell
+/// (two-space gap, last column unpadded) with `C::LINE_ENDING` between rows.
+#[derive(Debug, Clone)]
+pub struct Table<'a, const COLS: usize, const ROWS: usize> {
+    rows: heapless::Vec<[&'a str; COLS], ROWS>,
+}
+
+impl<'a, const COLS: usize, const ROWS: usize> Table<'a, COLS, ROWS> {
+    /// Create empty table.
+    pub const fn new() -> Self {
+        Self {
+            rows: heapless::Vec::new(),
+        }
+    }
+
+    /// Append row (`BufferFull` if `ROWS` rows already pushed).
+    pub fn push_row(&mut self, row: [&'a str; COLS]) -> Result<(), CliError> {
+        self.rows.push(row).map_err(|_| CliError::BufferFull)
+    }
+
+    /// Number of rows pushed.
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Returns true if no rows were pushed.
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Render aligned rows for a shell using `C` (`BufferFull` if the output exceeds `N` bytes).
+    pub fn render<C: ShellConfig, const N: usize>(&self) -> Result<heapless::String<N>, CliError> {
+        // Column widths in chars (not bytes) so non-ASCII cells align
+        let mut widths = [0usize; COLS];
+        for row in self.rows.iter() {
+            for (width, cell) in widths.iter_mut().zip(row.iter()) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let mut out = heapless::String::new();
+        for (r, row) in self.rows.iter().enumerate() {
+            if r > 0 {
+                out.push_str(C::LINE_ENDING)
+                    .map_err(|_| CliError::BufferFull)?;
+            }
+            for (c, cell) in row.iter().enumerate() {
+                out.push_str(cell).map_err(|_| CliError::BufferFull)?;
+                if c + 1 < COLS {
+                    for _ in cell.chars().count()..widths[c] + 2 {
+                        out.push(' ').map_err(|_| CliError::BufferFull)?;
+                    }
+                }
+            }
+        }
+        Ok(out)
+    }
+}
+
+impl<const COLS: usize, const ROWS: usize> Default for Table<'_, COLS, ROWS> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[cfg(test)]
@@ -121,6 +276,15 @@ mod tests {
 
         #[cfg(feature = "history")]
         assert!(!response.exclude_from_history);
+        assert_eq!(response.exit_code, 0);
+    }
+
+    #[test]
+    fn test_failure_response() {
+        let response = Response::<DefaultConfig>::failure("Sensor offline", 3);
+        assert_eq!(response.message.as_str(), "Sensor offline");
+        assert_eq!(response.exit_code, 3);
+        assert!(response.show_prompt);
     }
 
     #[test]
@@ -209,4 +373,73 @@ mod tests {
         assert!(!response.show_prompt);
         assert!(response.exclude_from_history);
     }
+
+    #[test]
+    fn test_message_limited_by_max_response() {
+        use crate::config::MinimalConfig;
+
+        // Storage is sized from the config
+        let fits = "x".repeat(MinimalConfig::MAX_RESPONSE);
+        let response = Response::<MinimalConfig>::success(&fits);
+        assert_eq!(response.message.len(), MinimalConfig::MAX_RESPONSE);
+        assert_eq!(response.message.capacity(), MinimalConfig::MAX_RESPONSE);
+
+        let too_long = "x".repeat(MinimalConfig::MAX_RESPONSE + 1);
+        let response = Response::<MinimalConfig>::success(&too_long);
+        assert_eq!(response.message.len(), 0);
+    }
+
+    #[test]
+    fn test_streamed_response() {
+        fn produce(emit: &mut dyn FnMut(&str) -> Result<(), ()>) -> Result<(), ()> {
+            emit("chunk")
+        }
+
+        let response = Response::<DefaultConfig>::streamed(produce).indented();
+        assert!(response.message.is_empty());
+        assert!(response.stream.is_some());
+        assert!(response.indent_message);
+        assert!(response.show_prompt);
+    }
+
+    #[test]
+    fn test_table_aligns_columns() {
+        let mut table: Table<'_, 3, 4> = Table::new();
+        table.push_row(["Name", "Value", "Unit"]).unwrap();
+        table.push_row(["temp", "21.5", "°C"]).unwrap();
+        table.push_row(["humidity", "40", "%"]).unwrap();
+
+        let out = table.render::<DefaultConfig, 128>().unwrap();
+        assert_eq!(
+            out.as_str(),
+            "Name      Value  Unit\r\ntemp      21.5   °C\r\nhumidity  40     %"
+        );
+    }
+
+    #[test]
+    fn test_table_non_ascii_width() {
+        let mut table: Table<'_, 2, 2> = Table::new();
+        table.push_row(["é", "x"]).unwrap();
+        table.push_row(["ab", "y"]).unwrap();
+
+        let out = table.render::<DefaultConfig, 32>().unwrap();
+        assert_eq!(out.as_str(), "é   x\r\nab  y");
+    }
+
+    #[test]
+    fn test_table_capacity_errors() {
+        let mut table: Table<'_, 2, 1> = Table::new();
+        assert!(table.is_empty());
+        table.push_row(["key", "value"]).unwrap();
+        assert_eq!(table.push_row(["a", "b"]), Err(CliError::BufferFull));
+        assert_eq!(table.len(), 1);
+
+        assert_eq!(
+            table.render::<DefaultConfig, 4>(),
+            Err(CliError::BufferFull)
+        );
+
+        let empty: Table<'_, 2, 1> = Table::default();
+        assert_eq!(empty.render::<DefaultConfig, 4>().unwrap().as_str(), "");
+    }
 }
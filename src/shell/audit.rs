@@ -0,0 +1,44 @@
+//! Audit events for command and login attempts.
+//!
+//! Delivered to an optional borrowed hook (`Shell::set_audit_hook()`), so
+//! applications can forward records to a log UART or flash ring buffer
+//! without touching handlers.
+
+use crate::auth::AccessLevel;
+use crate::error::CliError;
+
+/// Audit callback (borrowed trait object, no allocation).
+pub type AuditHook<'h, L> = dyn Fn(AuditEvent<'_, L>) + 'h;
+
+/// Security-relevant shell event.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AuditEvent<'a, L: AccessLevel> {
+    /// Tree command attempt (after tokenizing; navigation and global commands are not audited).
+    Command {
+        /// Logged-in user (`None` without authentication)
+        username: Option<&'a str>,
+        /// Logged-in user's access level
+        access_level: Option<L>,
+        /// Command id, or `None` if the path did not resolve to a command
+        id: Option<&'a str>,
+        /// Path as typed
+        path: &'a str,
+        /// Resolved command as names from the root, or `None` if the path did not resolve
+        command_path: Option<&'a [&'a str]>,
+        /// Number of arguments supplied
+        arg_count: usize,
+        /// `Ok(())` if the handler succeeded, otherwise the error reported to the user
+        outcome: Result<(), &'a CliError>,
+    },
+
+    /// Login attempt
+    #[cfg(feature = "authentication")]
+    Login {
+        /// Username as entered
+        username: &'a str,
+        /// Whether credentials were accepted
+        success: bool,
+        /// Whether the username exists (for logs only; the operator sees the same message)
+        user_found: bool,
+    },
+}
@@ -1,6 +1,6 @@
 //! Input decoder for terminal character sequences.
 //!
-//! State machine for ANSI escape sequences (arrow keys) and double-ESC clear.
+//! State machine for ANSI escape sequences (arrow keys, bracketed paste) and double-ESC clear.
 //! Pure decoder: converts raw chars to logical events, no buffer or I/O management.
 
 /// Decoder state for escape sequence handling.
@@ -14,6 +14,9 @@ pub enum InputState {
 
     /// Saw ESC [ (start of escape sequence)
     EscapeSequence,
+
+    /// Saw ESC [ followed by digits (numeric parameter so far)
+    EscapeParam(u16),
 }
 
 /// Logical input event from terminal.
@@ -42,14 +45,32 @@ pub enum InputEvent {
 
     /// Double ESC pressed
     DoubleEsc,
+
+    /// Lone ESC (no follow-up before `InputDecoder::tick_timeout()`)
+    Escape,
+
+    /// Ctrl+C (ETX, 0x03)
+    Cancel,
+
+    /// Bracketed paste start (`ESC [ 200 ~`)
+    PasteStart,
+
+    /// Bracketed paste end (`ESC [ 201 ~`)
+    PasteEnd,
 }
 
+/// Event filter callback: return `false` to consume the event before the shell acts on it.
+pub type EventFilter<'h> = dyn Fn(InputEvent) -> bool + 'h;
+
 /// Terminal input decoder with escape sequence state machine.
 /// Converts raw terminal chars to logical input events without managing buffers or I/O.
 #[derive(Debug)]
 pub struct InputDecoder {
     /// Current decoder state
     state: InputState,
+
+    /// Line ending (`\r` or `\n`) that produced the previous Enter, if the last char was one
+    last_eol: Option<char>,
 }
 
 impl InputDecoder {
@@ -57,15 +78,28 @@ impl InputDecoder {
     pub fn new() -> Self {
         Self {
             state: InputState::Normal,
+            last_eol: None,
         }
     }
 
     /// Decode single character into input event (returns `None` for incomplete sequences).
+    ///
+    /// `\r\n` and `\n\r` pairs produce a single `Enter`.
     pub fn decode_char(&mut self, c: char) -> InputEvent {
+        let last_eol = self.last_eol.take();
+        if self.state == InputState::Normal && matches!(c, '\n' | '\r') {
+            // Second half of a CR LF / LF CR pair - already reported
+            if last_eol.is_some_and(|prev| prev != c) {
+                return InputEvent::None;
+            }
+            self.last_eol = Some(c);
+        }
+
         match self.state {
             InputState::Normal => self.decode_normal(c),
             InputState::EscapeStart => self.decode_escape_start(c),
             InputState::EscapeSequence => self.decode_escape_sequence(c),
+            InputState::EscapeParam(param) => self.decode_escape_param(param, c),
         }
     }
 
@@ -87,6 +121,9 @@ impl InputDecoder {
             // Backspace - ASCII BS (0x08) or DEL (0x7F)
             '\x08' | '\x7f' => InputEvent::Backspace,
 
+            // Ctrl+C - raw-mode terminals send ETX instead of raising SIGINT
+            '\x03' => InputEvent::Cancel,
+
             // Control characters (except those handled above) - ignore
             c if c.is_control() => InputEvent::None,
 
@@ -129,6 +166,12 @@ impl InputDecoder {
             'A' => InputEvent::UpArrow,
             'B' => InputEvent::DownArrow,
 
+            // Numeric parameter (e.g. bracketed paste markers)
+            '0'..='9' => {
+                self.state = InputState::EscapeParam(c as u16 - '0' as u16);
+                InputEvent::None
+            }
+
             // Future: could add C (right arrow), D (left arrow), H (home), F (end)
             // Currently, only up/down arrows are implemented
             // See PHILOSOPHY.md "Recommended Additions"
@@ -138,11 +181,42 @@ impl InputDecoder {
         }
     }
 
+    /// Decode character after ESC [ and digits (terminated by `~`).
+    fn decode_escape_param(&mut self, param: u16, c: char) -> InputEvent {
+        if let Some(digit) = c.to_digit(10) {
+            let param = param.saturating_mul(10).saturating_add(digit as u16);
+            self.state = InputState::EscapeParam(param);
+            return InputEvent::None;
+        }
+
+        self.state = InputState::Normal;
+        match (param, c) {
+            (200, '~') => InputEvent::PasteStart,
+            (201, '~') => InputEvent::PasteEnd,
+
+            // Unknown sequence (Home/End/Delete etc.) - ignore
+            _ => InputEvent::None,
+        }
+    }
+
+    /// Resolve a pending sequence after an input gap (caller-timed; decoder has no clock).
+    ///
+    /// A lone ESC becomes `Escape`; an incomplete `ESC [` sequence is dropped (`None`).
+    pub fn tick_timeout(&mut self) -> InputEvent {
+        let pending = self.state;
+        self.state = InputState::Normal;
+        match pending {
+            InputState::EscapeStart => InputEvent::Escape,
+            _ => InputEvent::None,
+        }
+    }
+
     /// Reset decoder state to Normal.
     ///
     /// Useful after handling special events or errors.
     pub fn reset(&mut self) {
         self.state = InputState::Normal;
+        self.last_eol = None;
     }
 
     /// Get current decoder state (for testing/debugging).
@@ -240,6 +314,30 @@ mod tests {
         assert_eq!(event, InputEvent::Enter);
     }
 
+    #[test]
+    fn test_crlf_is_single_enter() {
+        let mut decoder = InputDecoder::new();
+
+        for pair in ["\r\n", "\n\r"] {
+            let events: heapless::Vec<InputEvent, 4> =
+                pair.chars().map(|c| decoder.decode_char(c)).collect();
+            assert_eq!(events.as_slice(), &[InputEvent::Enter, InputEvent::None]);
+        }
+    }
+
+    #[test]
+    fn test_repeated_line_endings_are_blank_lines() {
+        // Same ending twice, or a pair interrupted by input, is two Enters
+        for input in ["\r\r", "\n\n", "\rx\n"] {
+            let mut decoder = InputDecoder::new();
+            let enters = input
+                .chars()
+                .filter(|&c| decoder.decode_char(c) == InputEvent::Enter)
+                .count();
+            assert_eq!(enters, 2, "{:?}", input);
+        }
+    }
+
     #[test]
     fn test_tab() {
         let mut decoder = InputDecoder::new();
@@ -282,6 +380,24 @@ mod tests {
         assert_eq!(decoder.state(), InputState::EscapeStart);
     }
 
+    #[test]
+    fn test_tick_timeout_flushes_lone_esc() {
+        let mut decoder = InputDecoder::new();
+
+        // Nothing pending
+        assert_eq!(decoder.tick_timeout(), InputEvent::None);
+
+        decoder.decode_char('\x1b');
+        assert_eq!(decoder.tick_timeout(), InputEvent::Escape);
+        assert_eq!(decoder.state(), InputState::Normal);
+
+        // Incomplete CSI sequence is dropped, not reported
+        decoder.decode_char('\x1b');
+        decoder.decode_char('[');
+        assert_eq!(decoder.tick_timeout(), InputEvent::None);
+        assert_eq!(decoder.decode_char('A'), InputEvent::Char('A'));
+    }
+
     #[test]
     fn test_double_esc() {
         let mut decoder = InputDecoder::new();
@@ -368,14 +484,19 @@ mod tests {
         let mut decoder = InputDecoder::new();
 
         // Various control characters (except handled ones)
-        for c in [
-            '\x00', '\x01', '\x02', '\x03', '\x04', '\x05', '\x06', '\x07',
-        ] {
+        for c in ['\x00', '\x01', '\x02', '\x04', '\x05', '\x06', '\x07'] {
             let event = decoder.decode_char(c);
             assert_eq!(event, InputEvent::None);
         }
     }
 
+    #[test]
+    fn test_ctrl_c_is_cancel() {
+        let mut decoder = InputDecoder::new();
+        assert_eq!(decoder.decode_char('\x03'), InputEvent::Cancel);
+        assert_eq!(decoder.state(), InputState::Normal);
+    }
+
     // ========================================
     // Integration Tests
     // ========================================
@@ -431,4 +552,33 @@ mod tests {
         decoder.decode_char('[');
         assert_eq!(decoder.decode_char('B'), InputEvent::DownArrow);
     }
+
+    #[test]
+    fn test_bracketed_paste_markers() {
+        let mut decoder = InputDecoder::new();
+
+        for c in "\x1b[200".chars() {
+            assert_eq!(decoder.decode_char(c), InputEvent::None);
+        }
+        assert_eq!(decoder.state(), InputState::EscapeParam(200));
+        assert_eq!(decoder.decode_char('~'), InputEvent::PasteStart);
+        assert_eq!(decoder.state(), InputState::Normal);
+
+        for c in "\x1b[201".chars() {
+            decoder.decode_char(c);
+        }
+        assert_eq!(decoder.decode_char('~'), InputEvent::PasteEnd);
+    }
+
+    #[test]
+    fn test_unknown_numeric_sequence_ignored() {
+        let mut decoder = InputDecoder::new();
+
+        // ESC [ 3 ~ (Delete key) - consumed without producing characters
+        for c in "\x1b[3~".chars() {
+            assert_eq!(decoder.decode_char(c), InputEvent::None);
+        }
+        assert_eq!(decoder.state(), InputState::Normal);
+        assert_eq!(decoder.decode_char('x'), InputEvent::Char('x'));
+    }
 }
@@ -3,10 +3,14 @@
 //! Maps command IDs to execution functions, implementing the execution side
 //! of the metadata/execution separation pattern.
 
+use crate::auth::AccessLevel;
 use crate::config::ShellConfig;
 use crate::error::CliError;
 use crate::response::Response;
 
+#[cfg(feature = "async")]
+use core::cell::Cell;
+
 /// Command execution handler trait.
 /// Maps command IDs to execution functions (dispatches on unique ID, not display name).
 pub trait CommandHandler<C: ShellConfig> {
@@ -15,9 +19,127 @@ pub trait CommandHandler<C: ShellConfig> {
 
     /// Execute asynchronous command by unique ID (requires `async` feature).
     /// Uses `async fn` without Send bounds for both single and multi-threaded executors.
+    /// Long-running handlers should poll `cancel` at await points and return `CliError::Cancelled`.
+    #[cfg(feature = "async")]
+    #[allow(async_fn_in_trait)]
+    async fn execute_async(
+        &self,
+        id: &str,
+        args: &[&str],
+        cancel: &CancelToken,
+    ) -> Result<Response<C>, CliError>;
+
+    /// Execute synchronous command with session context (who runs it, and where).
+    ///
+    /// The shell always dispatches through this method; the default forwards to
+    /// `execute_sync()`, so only handlers needing the context override it.
+    fn execute_sync_ctx<L: AccessLevel>(
+        &self,
+        ctx: &CommandContext<'_, L>,
+        id: &str,
+        args: &[&str],
+    ) -> Result<Response<C>, CliError> {
+        let _ = ctx;
+        self.execute_sync(id, args)
+    }
+
+    /// Execute asynchronous command with session context (default forwards to `execute_async()`).
     #[cfg(feature = "async")]
     #[allow(async_fn_in_trait)]
-    async fn execute_async(&self, id: &str, args: &[&str]) -> Result<Response<C>, CliError>;
+    async fn execute_async_ctx<L: AccessLevel>(
+        &self,
+        ctx: &CommandContext<'_, L>,
+        id: &str,
+        args: &[&str],
+        cancel: &CancelToken,
+    ) -> Result<Response<C>, CliError> {
+        let _ = ctx;
+        self.execute_async(id, args, cancel).await
+    }
+
+    /// Called after access and argument checks, right before a tree command is dispatched.
+    ///
+    /// Returning `Err` aborts the command with that error - one choke point for invariants
+    /// shared by many commands (e.g. a subsystem must be powered; match on `id` or
+    /// `ctx.command_path`, since `ctx.path` is the current directory). The default allows
+    /// every command.
+    fn before_execute<L: AccessLevel>(
+        &self,
+        ctx: &CommandContext<'_, L>,
+        id: &str,
+    ) -> Result<(), CliError> {
+        let _ = (ctx, id);
+        Ok(())
+    }
+
+    /// Called when Enter is pressed on an empty (or whitespace-only) command line.
+    ///
+    /// Returning `Some` renders the response (e.g. "press Enter to refresh"); the default
+    /// `None` just shows a fresh prompt. Never added to history.
+    fn on_empty_enter(&self) -> Option<Response<C>> {
+        None
+    }
+}
+
+/// Session context for a command dispatch (see `CommandHandler::execute_sync_ctx()`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CommandContext<'a, L: AccessLevel> {
+    /// Logged-in user (`None` without authentication)
+    pub username: Option<&'a str>,
+
+    /// Logged-in user's access level (`None` without authentication)
+    pub access_level: Option<L>,
+
+    /// Current directory as names from the root (empty at `/`)
+    pub path: &'a [&'a str],
+
+    /// Resolved command as names from the root, ending with its own name
+    /// (e.g. `["system", "network", "status"]`, whichever directory it was typed from)
+    pub command_path: &'a [&'a str],
+}
+
+/// Cooperative cancellation flag for async commands (requires `async` feature).
+///
+/// Single-threaded by design (`Cell`, not an atomic). The shell resets it before each
+/// async dispatch; set it from a task sharing the executor (e.g. on Ctrl+C).
+#[cfg(feature = "async")]
+#[derive(Debug, Default)]
+pub struct CancelToken {
+    cancelled: Cell<bool>,
+}
+
+#[cfg(feature = "async")]
+impl CancelToken {
+    /// Create a token in the not-cancelled state.
+    pub const fn new() -> Self {
+        Self {
+            cancelled: Cell::new(false),
+        }
+    }
+
+    /// Request cancellation.
+    pub fn cancel(&self) {
+        self.cancelled.set(true);
+    }
+
+    /// Returns true if cancellation was requested.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.get()
+    }
+
+    /// Clear a pending cancellation request.
+    pub fn reset(&self) {
+        self.cancelled.set(false);
+    }
+
+    /// Returns `Err(CliError::Cancelled)` if cancellation was requested.
+    pub fn check(&self) -> Result<(), CliError> {
+        if self.is_cancelled() {
+            Err(CliError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
 }
 
 #[cfg(test)]
@@ -45,7 +167,9 @@ mod tests {
             &self,
             id: &str,
             _args: &[&str],
+            cancel: &CancelToken,
         ) -> Result<Response<DefaultConfig>, CliError> {
+            cancel.check()?;
             match id {
                 "async-test" => Ok(Response::success("Async OK")),
                 _ => Err(CliError::CommandNotFound),
@@ -68,11 +192,28 @@ mod tests {
     #[tokio::test]
     async fn test_async_handler() {
         let handler = TestHandler;
-        let result = handler.execute_async("async-test", &[]).await;
+        let cancel = CancelToken::new();
+        let result = handler.execute_async("async-test", &[], &cancel).await;
         assert!(result.is_ok());
         assert_eq!(result.unwrap().message.as_str(), "Async OK");
 
-        let result = handler.execute_async("unknown", &[]).await;
+        let result = handler.execute_async("unknown", &[], &cancel).await;
         assert_eq!(result, Err(CliError::CommandNotFound));
     }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_cancel_token() {
+        let handler = TestHandler;
+        let cancel = CancelToken::new();
+        assert!(!cancel.is_cancelled());
+
+        cancel.cancel();
+        assert!(cancel.is_cancelled());
+        let result = handler.execute_async("async-test", &[], &cancel).await;
+        assert_eq!(result, Err(CliError::Cancelled));
+
+        cancel.reset();
+        assert_eq!(cancel.check(), Ok(()));
+    }
 }
@@ -4,29 +4,34 @@
 
 #![cfg_attr(not(feature = "history"), allow(unused_variables))]
 
-#[cfg(not(feature = "history"))]
+use crate::config::{InputBuffer, ShellConfig};
 use core::marker::PhantomData;
 
+#[cfg(feature = "history")]
+use crate::config::{HistoryBuffer, StringBuffer, VecBuffer};
+
 /// Command history storage (ring buffer when enabled, zero-size stub when disabled).
-#[derive(Debug)]
-pub struct CommandHistory<const N: usize, const INPUT_SIZE: usize> {
+///
+/// Holds up to `C::Buffers` history entries of the config's input size.
+pub struct CommandHistory<C: ShellConfig> {
+    /// Entries oldest first, each with whether the command succeeded
     #[cfg(feature = "history")]
-    buffer: heapless::Vec<heapless::String<INPUT_SIZE>, N>,
+    buffer: HistoryBuffer<C>,
 
     #[cfg(feature = "history")]
     position: Option<usize>,
 
-    #[cfg(not(feature = "history"))]
-    _phantom: PhantomData<[u8; INPUT_SIZE]>,
+    _config: PhantomData<C>,
 }
 
-impl<const N: usize, const INPUT_SIZE: usize> CommandHistory<N, INPUT_SIZE> {
+impl<C: ShellConfig> CommandHistory<C> {
     /// Create new command history.
     #[cfg(feature = "history")]
     pub fn new() -> Self {
         Self {
-            buffer: heapless::Vec::new(),
+            buffer: HistoryBuffer::<C>::default(),
             position: None,
+            _config: PhantomData,
         }
     }
 
@@ -34,15 +39,26 @@ impl<const N: usize, const INPUT_SIZE: usize> CommandHistory<N, INPUT_SIZE> {
     #[cfg(not(feature = "history"))]
     pub fn new() -> Self {
         Self {
-            _phantom: PhantomData,
+            _config: PhantomData,
         }
     }
 
-    /// Add command to history.
-    #[cfg(feature = "history")]
+    /// Add command to history, keeping at most `C::HISTORY_SIZE` entries.
     pub fn add(&mut self, cmd: &str) {
+        self.add_with_limit(cmd, C::HISTORY_SIZE);
+    }
+
+    /// Add command to history, keeping at most `limit` entries (clamped to the storage; 0 keeps none).
+    pub fn add_with_limit(&mut self, cmd: &str, limit: usize) {
+        self.add_with_outcome(cmd, true, limit);
+    }
+
+    /// Add command to history with its outcome (`false` if it failed), keeping at most
+    /// `limit` entries. Repeating the most recent command updates its outcome.
+    #[cfg(feature = "history")]
+    pub fn add_with_outcome(&mut self, cmd: &str, success: bool, limit: usize) {
         // Don't add empty commands
-        if cmd.is_empty() {
+        if cmd.is_empty() || limit == 0 {
             return;
         }
 
@@ -50,75 +66,124 @@ impl<const N: usize, const INPUT_SIZE: usize> CommandHistory<N, INPUT_SIZE> {
         self.position = None;
 
         // Don't add if same as most recent
-        if let Some(last) = self.buffer.last()
+        if let Some((last, last_success)) = self.buffer.last_mut()
             && last.as_str() == cmd
         {
+            *last_success = success;
             return;
         }
 
-        let mut entry = heapless::String::new();
+        let mut entry = InputBuffer::<C>::default();
         if entry.push_str(cmd).is_ok() {
             // Ring buffer behavior - remove oldest if full
-            if self.buffer.is_full() {
+            if self.buffer.is_full() || self.buffer.len() >= limit {
                 self.buffer.remove(0);
             }
-            let _ = self.buffer.push(entry);
+            let _ = self.buffer.push((entry, success));
         }
     }
 
-    /// Add command to history (stub version - no-op).
+    /// Add command to history with its outcome (stub version - no-op).
     #[cfg(not(feature = "history"))]
-    pub fn add(&mut self, _cmd: &str) {
+    pub fn add_with_outcome(&mut self, _cmd: &str, _success: bool, _limit: usize) {
         // No-op
     }
 
-    /// Navigate to previous command (up arrow).
+    /// Navigate to previous command (up arrow), stopping at the oldest entry.
+    pub fn previous_command(&mut self) -> Option<InputBuffer<C>> {
+        self.previous_command_with_wrap(false)
+    }
+
+    /// Navigate to next command (down arrow), stopping at the empty line after the newest.
+    pub fn next_command(&mut self) -> Option<InputBuffer<C>> {
+        self.next_command_with_wrap(false)
+    }
+
+    /// Navigate to previous command; with `wrap`, Up at the oldest entry goes to the empty
+    /// line (as if below the newest), so the next Up continues from the newest.
     #[cfg(feature = "history")]
-    pub fn previous_command(&mut self) -> Option<heapless::String<INPUT_SIZE>> {
+    pub fn previous_command_with_wrap(&mut self, wrap: bool) -> Option<InputBuffer<C>> {
         if self.buffer.is_empty() {
             return None;
         }
 
         let pos = match self.position {
             None => self.buffer.len() - 1,
+            Some(0) if wrap => {
+                self.position = None;
+                return Some(InputBuffer::<C>::default());
+            }
             Some(0) => 0, // Already at oldest
             Some(p) => p - 1,
         };
 
         self.position = Some(pos);
-        self.buffer.get(pos).cloned()
+        self.buffer.get(pos).map(|(cmd, _)| cmd.clone())
     }
 
     /// Navigate to previous command (stub version - returns None).
     #[cfg(not(feature = "history"))]
-    pub fn previous_command(&mut self) -> Option<heapless::String<INPUT_SIZE>> {
+    pub fn previous_command_with_wrap(&mut self, wrap: bool) -> Option<InputBuffer<C>> {
         None
     }
 
-    /// Navigate to next command (down arrow).
+    /// Navigate to next command; with `wrap`, Down on the empty line goes to the oldest
+    /// entry, completing the cycle oldest → newest → empty line → oldest.
     #[cfg(feature = "history")]
-    pub fn next_command(&mut self) -> Option<heapless::String<INPUT_SIZE>> {
+    pub fn next_command_with_wrap(&mut self, wrap: bool) -> Option<InputBuffer<C>> {
         match self.position {
+            None if wrap && !self.buffer.is_empty() => {
+                self.position = Some(0);
+                self.buffer.first().map(|(cmd, _)| cmd.clone())
+            }
             None => None, // Not navigating
             Some(p) if p >= self.buffer.len() - 1 => {
                 // At newest - go to empty
                 self.position = None;
-                Some(heapless::String::new()) // Return empty string to clear buffer
+                Some(InputBuffer::<C>::default()) // Return empty string to clear buffer
             }
             Some(p) => {
                 let pos = p + 1;
                 self.position = Some(pos);
-                self.buffer.get(pos).cloned()
+                self.buffer.get(pos).map(|(cmd, _)| cmd.clone())
             }
         }
     }
 
     /// Navigate to next command (stub version - returns None).
     #[cfg(not(feature = "history"))]
-    pub fn next_command(&mut self) -> Option<heapless::String<INPUT_SIZE>> {
+    pub fn next_command_with_wrap(&mut self, wrap: bool) -> Option<InputBuffer<C>> {
         None
     }
 
+    /// Stored commands, oldest first (e.g. to persist and replay with `add()`).
+    #[cfg(feature = "history")]
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.buffer.iter().map(|(cmd, _)| cmd.as_str())
+    }
+
+    /// Stored commands with their outcome (`false` = failed), oldest first.
+    ///
+    /// Lets a `history` listing mark failed entries, e.g. with `✗`.
+    #[cfg(feature = "history")]
+    pub fn entries(&self) -> impl Iterator<Item = (&str, bool)> {
+        self.buffer
+            .iter()
+            .map(|(cmd, success)| (cmd.as_str(), *success))
+    }
+
+    /// Stored commands (stub version - always empty).
+    #[cfg(not(feature = "history"))]
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        core::iter::empty()
+    }
+
+    /// Stored commands with their outcome (stub version - always empty).
+    #[cfg(not(feature = "history"))]
+    pub fn entries(&self) -> impl Iterator<Item = (&str, bool)> {
+        core::iter::empty()
+    }
+
     /// Reset navigation position.
     #[cfg(feature = "history")]
     pub fn reset_position(&mut self) {
@@ -132,20 +197,50 @@ impl<const N: usize, const INPUT_SIZE: usize> CommandHistory<N, INPUT_SIZE> {
     }
 }
 
-impl<const N: usize, const INPUT_SIZE: usize> Default for CommandHistory<N, INPUT_SIZE> {
+impl<C: ShellConfig> Default for CommandHistory<C> {
     fn default() -> Self {
         Self::new()
     }
 }
 
+// Manual impls: derives would require `C: Debug + Clone` of the config marker
+impl<C: ShellConfig> core::fmt::Debug for CommandHistory<C> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut debug_struct = f.debug_struct("CommandHistory");
+
+        #[cfg(feature = "history")]
+        debug_struct
+            .field("buffer", &self.buffer)
+            .field("position", &self.position);
+
+        debug_struct.finish()
+    }
+}
+
+impl<C: ShellConfig> Clone for CommandHistory<C> {
+    fn clone(&self) -> Self {
+        Self {
+            #[cfg(feature = "history")]
+            buffer: self.buffer.clone(),
+            #[cfg(feature = "history")]
+            position: self.position,
+            _config: PhantomData,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::DefaultConfig;
+
+    #[cfg(feature = "history")]
+    use crate::config::MinimalConfig;
 
     #[test]
     #[cfg(feature = "history")]
     fn test_add_and_navigate() {
-        let mut history = CommandHistory::<5, 128>::new();
+        let mut history = CommandHistory::<DefaultConfig>::new();
 
         history.add("cmd1");
         history.add("cmd2");
@@ -167,12 +262,63 @@ mod tests {
         assert_eq!(history.next_command().unwrap().as_str(), "");
     }
 
+    #[test]
+    #[cfg(feature = "history")]
+    fn test_navigate_with_wrap() {
+        let mut history = CommandHistory::<DefaultConfig>::new();
+        history.add("cmd1");
+        history.add("cmd2");
+
+        // Up past the oldest passes the empty line, then continues from the newest
+        assert_eq!(history.previous_command_with_wrap(true).unwrap(), "cmd2");
+        assert_eq!(history.previous_command_with_wrap(true).unwrap(), "cmd1");
+        assert_eq!(history.previous_command_with_wrap(true).unwrap(), "");
+        assert_eq!(history.previous_command_with_wrap(true).unwrap(), "cmd2");
+
+        // Down past the newest passes the empty line, then continues from the oldest
+        assert_eq!(history.next_command_with_wrap(true).unwrap(), "");
+        assert_eq!(history.next_command_with_wrap(true).unwrap(), "cmd1");
+        assert_eq!(history.next_command_with_wrap(true).unwrap(), "cmd2");
+
+        // Without wrap, Down on the empty line does nothing
+        history.reset_position();
+        assert!(history.next_command().is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "history")]
+    fn test_iter_oldest_first() {
+        let mut history = CommandHistory::<MinimalConfig>::new();
+        for cmd in ["cmd1", "cmd2", "cmd3", "cmd4", "cmd5"] {
+            history.add(cmd);
+        }
+
+        let entries: heapless::Vec<&str, 4> = history.iter().collect();
+        assert_eq!(entries, ["cmd2", "cmd3", "cmd4", "cmd5"]);
+    }
+
+    #[test]
+    #[cfg(feature = "history")]
+    fn test_entries_with_outcome() {
+        let mut history = CommandHistory::<DefaultConfig>::new();
+        history.add("cmd1");
+        history.add_with_outcome("cmd2", false, 5);
+        history.add_with_outcome("cmd3", false, 5);
+        // Repeating the newest command records its latest outcome
+        history.add_with_outcome("cmd3", true, 5);
+
+        assert_eq!(history.previous_command().unwrap(), "cmd3");
+        let entries: heapless::Vec<(&str, bool), 5> = history.entries().collect();
+        assert_eq!(entries, [("cmd1", true), ("cmd2", false), ("cmd3", true)]);
+    }
+
     #[test]
     #[cfg(not(feature = "history"))]
     fn test_stub_behavior() {
-        let mut history = CommandHistory::<5, 128>::new();
+        let mut history = CommandHistory::<DefaultConfig>::new();
 
         history.add("cmd1");
+        assert_eq!(history.iter().count(), 0);
         assert!(history.previous_command().is_none());
         assert!(history.next_command().is_none());
     }
@@ -180,17 +326,20 @@ mod tests {
     #[test]
     #[cfg(feature = "history")]
     fn test_ring_buffer_behavior() {
-        let mut history = CommandHistory::<3, 128>::new();
+        // MinimalConfig keeps 4 entries
+        let mut history = CommandHistory::<MinimalConfig>::new();
 
         // Fill buffer to capacity
         history.add("cmd1");
         history.add("cmd2");
         history.add("cmd3");
+        history.add("cmd4");
 
         // Add one more - should remove oldest (cmd1)
-        history.add("cmd4");
+        history.add("cmd5");
 
         // Navigate to oldest (should be cmd2 now)
+        assert_eq!(history.previous_command().unwrap().as_str(), "cmd5");
         assert_eq!(history.previous_command().unwrap().as_str(), "cmd4");
         assert_eq!(history.previous_command().unwrap().as_str(), "cmd3");
         assert_eq!(history.previous_command().unwrap().as_str(), "cmd2");
@@ -199,10 +348,36 @@ mod tests {
         assert_eq!(history.previous_command().unwrap().as_str(), "cmd2"); // Stay at oldest
     }
 
+    #[test]
+    #[cfg(feature = "history")]
+    fn test_limit_below_capacity() {
+        let mut history = CommandHistory::<DefaultConfig>::new();
+
+        history.add_with_limit("cmd1", 2);
+        history.add_with_limit("cmd2", 2);
+        history.add_with_limit("cmd3", 2);
+
+        assert_eq!(history.previous_command().unwrap().as_str(), "cmd3");
+        assert_eq!(history.previous_command().unwrap().as_str(), "cmd2");
+        assert_eq!(history.previous_command().unwrap().as_str(), "cmd2"); // cmd1 evicted
+
+        // Limit 0 keeps nothing
+        let mut history = CommandHistory::<DefaultConfig>::new();
+        history.add_with_limit("cmd1", 0);
+        assert!(history.previous_command().is_none());
+
+        // Limit above capacity is clamped
+        let mut history = CommandHistory::<MinimalConfig>::new();
+        for cmd in ["cmd1", "cmd2", "cmd3", "cmd4", "cmd5"] {
+            history.add_with_limit(cmd, 10);
+        }
+        assert_eq!(history.iter().next(), Some("cmd2"));
+    }
+
     #[test]
     #[cfg(feature = "history")]
     fn test_empty_commands_ignored() {
-        let mut history = CommandHistory::<5, 128>::new();
+        let mut history = CommandHistory::<DefaultConfig>::new();
 
         history.add("");
         history.add("cmd1");
@@ -218,7 +393,7 @@ mod tests {
     #[test]
     #[cfg(feature = "history")]
     fn test_duplicate_commands_ignored() {
-        let mut history = CommandHistory::<5, 128>::new();
+        let mut history = CommandHistory::<DefaultConfig>::new();
 
         history.add("cmd1");
         history.add("cmd1"); // Duplicate - should be ignored
@@ -235,7 +410,7 @@ mod tests {
     #[test]
     #[cfg(feature = "history")]
     fn test_navigation_without_adding() {
-        let mut history = CommandHistory::<5, 128>::new();
+        let mut history = CommandHistory::<DefaultConfig>::new();
 
         // Try to navigate when empty
         assert!(history.previous_command().is_none());
@@ -245,7 +420,7 @@ mod tests {
     #[test]
     #[cfg(feature = "history")]
     fn test_reset_position() {
-        let mut history = CommandHistory::<5, 128>::new();
+        let mut history = CommandHistory::<DefaultConfig>::new();
 
         history.add("cmd1");
         history.add("cmd2");
@@ -265,7 +440,7 @@ mod tests {
     #[test]
     #[cfg(feature = "history")]
     fn test_position_resets_on_add() {
-        let mut history = CommandHistory::<5, 128>::new();
+        let mut history = CommandHistory::<DefaultConfig>::new();
 
         history.add("cmd1");
         history.add("cmd2");
@@ -283,7 +458,7 @@ mod tests {
     #[test]
     #[cfg(feature = "history")]
     fn test_position_resets_on_duplicate_add() {
-        let mut history = CommandHistory::<5, 128>::new();
+        let mut history = CommandHistory::<DefaultConfig>::new();
 
         history.add("cmd_a");
         history.add("cmd_b");
@@ -301,7 +476,7 @@ mod tests {
     #[test]
     #[cfg(feature = "history")]
     fn test_default() {
-        let history = CommandHistory::<5, 128>::default();
+        let history = CommandHistory::<DefaultConfig>::default();
         let mut history2 = history;
         assert!(history2.previous_command().is_none());
     }
@@ -309,7 +484,7 @@ mod tests {
     #[test]
     #[cfg(not(feature = "history"))]
     fn test_stub_reset_position() {
-        let mut history = CommandHistory::<5, 128>::new();
+        let mut history = CommandHistory::<DefaultConfig>::new();
         history.reset_position(); // Should not panic
     }
 }
@@ -0,0 +1,213 @@
+//! Line-window output for long responses (`ShellConfig::PAGE_SIZE`).
+//!
+//! Buffered messages, streamed output and `ls` listings are all written through a
+//! [`PageWindow`], which shows at most one page of lines and reports when more follow.
+//! Resuming re-runs the source and skips the lines already shown, so a paused listing or
+//! stream keeps only its source and a line count.
+
+use crate::config::ShellConfig;
+use crate::io::CharIo;
+
+#[cfg(feature = "paging")]
+use crate::{
+    auth::AccessLevel,
+    config::InputBuffer,
+    response::Response,
+    tree::{Directory, path::PathStack},
+};
+
+/// Output paused at the `--More--` prompt, waiting for a key.
+#[cfg(feature = "paging")]
+#[derive(Debug)]
+pub(super) struct Pager<'tree, L: AccessLevel, C: ShellConfig> {
+    /// Output to re-run for the next page
+    pub(super) source: PageSource<'tree, L, C>,
+
+    /// Lines already shown
+    pub(super) shown: usize,
+}
+
+/// What a paused page continues (see `Pager`).
+#[cfg(feature = "paging")]
+#[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
+pub(super) enum PageSource<'tree, L: AccessLevel, C: ShellConfig> {
+    /// Command response (a stream is re-run from its first chunk)
+    Response(Response<C>),
+
+    /// `ls` output, re-listed with the current user's access
+    Listing {
+        /// Directory being listed
+        dir: &'tree Directory<L>,
+        /// Its path (for runtime-registered commands)
+        dir_path: PathStack<C>,
+        /// Glob filter from the command line
+        pattern: Option<InputBuffer<C>>,
+    },
+}
+
+/// Writes lines `skip..skip + limit` of text split by `C::LINE_ENDING` (limit 0 = all).
+///
+/// A line ending is held back until the next line starts, so the last line written never
+/// ends with one: indented output drops it (as a prompt or postfix newline follows), plain
+/// output writes it in `finish()`, and a full page leaves it for the `--More--` prompt.
+#[derive(Debug)]
+pub(super) struct PageWindow {
+    line_ending: &'static str,
+    indent: Option<&'static str>,
+    limit: usize,
+    skip: usize,
+
+    /// Index of the line being written (line endings seen so far)
+    line: usize,
+
+    /// Current line started (line ending before it and indent written)
+    open: bool,
+
+    /// Previous line's ending not written yet
+    pending_newline: bool,
+
+    /// Page filled and more text followed
+    full: bool,
+}
+
+impl PageWindow {
+    /// Window over the page after the first `skip` lines (`indent`: prefix every line with `C::INDENT`).
+    pub(super) fn new<C: ShellConfig>(skip: usize, indent: bool) -> Self {
+        Self {
+            line_ending: C::LINE_ENDING,
+            indent: indent.then_some(C::INDENT),
+            limit: C::PAGE_SIZE,
+            skip,
+            line: 0,
+            open: false,
+            pending_newline: false,
+            full: false,
+        }
+    }
+
+    /// Write a chunk of text; returns false once the page is full and more text follows.
+    pub(super) fn write<IO: CharIo>(&mut self, io: &mut IO, text: &str) -> Result<bool, IO::Error> {
+        for (i, segment) in text.split(self.line_ending).enumerate() {
+            if i > 0 {
+                // Line ending: an empty visible line still gets its start (and indent)
+                if self.line >= self.skip {
+                    if !self.begin_line(io)? {
+                        return Ok(false);
+                    }
+                    self.pending_newline = true;
+                }
+                self.line += 1;
+                self.open = false;
+            }
+            if !segment.is_empty() && self.line >= self.skip {
+                if !self.begin_line(io)? {
+                    return Ok(false);
+                }
+                io.write_str(segment)?;
+            }
+        }
+        Ok(true)
+    }
+
+    /// End the output: an empty indented message still gets its indent, and plain output
+    /// gets its final line ending.
+    pub(super) fn finish<IO: CharIo>(&mut self, io: &mut IO) -> Result<(), IO::Error> {
+        if self.line == 0 && !self.open {
+            self.begin_line(io)?;
+        }
+        if self.pending_newline && self.indent.is_none() {
+            io.write_str(self.line_ending)?;
+            self.pending_newline = false;
+        }
+        Ok(())
+    }
+
+    /// True if the page filled before the text ended.
+    #[cfg(feature = "paging")]
+    pub(super) fn is_full(&self) -> bool {
+        self.full
+    }
+
+    /// Lines shown so far, counting skipped ones (the next window's `skip` after a full page).
+    #[cfg(feature = "paging")]
+    pub(super) fn shown(&self) -> usize {
+        self.line
+    }
+
+    /// Start the current line unless it falls past the page.
+    fn begin_line<IO: CharIo>(&mut self, io: &mut IO) -> Result<bool, IO::Error> {
+        if self.open {
+            return Ok(true);
+        }
+        if self.full || (self.limit > 0 && self.line - self.skip >= self.limit) {
+            self.full = true;
+            return Ok(false);
+        }
+        if self.pending_newline {
+            io.write_str(self.line_ending)?;
+            self.pending_newline = false;
+        }
+        if let Some(indent) = self.indent {
+            io.write_str(indent)?;
+        }
+        self.open = true;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DefaultConfig;
+    use crate::io::CharIo;
+
+    /// Collects writes; infallible.
+    #[derive(Default)]
+    struct Sink(heapless::String<128>);
+
+    impl CharIo for Sink {
+        type Error = ();
+
+        fn get_char(&mut self) -> Result<Option<char>, ()> {
+            Ok(None)
+        }
+
+        fn put_char(&mut self, c: char) -> Result<(), ()> {
+            self.0.push(c).map_err(|_| ())
+        }
+    }
+
+    fn render(chunks: &[&str], indent: bool) -> heapless::String<128> {
+        let mut io = Sink::default();
+        let mut window = PageWindow::new::<DefaultConfig>(0, indent);
+        for chunk in chunks {
+            assert!(window.write(&mut io, chunk).unwrap());
+        }
+        window.finish(&mut io).unwrap();
+        io.0
+    }
+
+    #[test]
+    fn test_indented_lines_drop_trailing_ending() {
+        assert_eq!(render(&["a\r\nb\r\n"], true), "  a\r\n  b");
+        assert_eq!(render(&["a\r\n", "b"], true), "  a\r\n  b");
+        assert_eq!(render(&["a\r\n\r\nb"], true), "  a\r\n  \r\n  b");
+        assert_eq!(render(&[""], true), "  ");
+    }
+
+    #[test]
+    fn test_plain_lines_keep_trailing_ending() {
+        assert_eq!(render(&["a\r\n", "b\r\n"], false), "a\r\nb\r\n");
+        assert_eq!(render(&["a", "b"], false), "ab");
+    }
+
+    #[test]
+    fn test_skip_resumes_after_shown_lines() {
+        let mut io = Sink::default();
+        let mut window = PageWindow::new::<DefaultConfig>(2, true);
+        assert!(window.write(&mut io, "a\r\nb\r\nc\r\nd").unwrap());
+        window.finish(&mut io).unwrap();
+        assert_eq!(io.0, "  c\r\n  d");
+    }
+}
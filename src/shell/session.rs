@@ -0,0 +1,49 @@
+//! Session state captured for rebuilding a shell after a transport drop.
+//!
+//! `Shell::export_session()` copies the current directory and command history;
+//! `Shell::restore_session()` applies them to a fresh shell. Login state is never captured.
+
+use crate::config::ShellConfig;
+use crate::shell::history::CommandHistory;
+use crate::tree::path::PathStack;
+
+/// Current directory and command history of a shell session.
+///
+/// Plain data, so a supervisor can keep it across reconnects or persist it:
+/// `path` holds child indices from the root, and history entries are available
+/// through `CommandHistory::iter()` or `entries()` (rebuild with `CommandHistory::add()`).
+pub struct SessionState<C: ShellConfig> {
+    /// Current directory as child indices from the root (empty = root)
+    pub path: PathStack<C>,
+
+    /// Command history (empty stub without the `history` feature)
+    pub history: CommandHistory<C>,
+}
+
+// Manual impls: derives would require `C: Debug + Clone + Default` of the config marker
+impl<C: ShellConfig> core::fmt::Debug for SessionState<C> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SessionState")
+            .field("path", &self.path)
+            .field("history", &self.history)
+            .finish()
+    }
+}
+
+impl<C: ShellConfig> Clone for SessionState<C> {
+    fn clone(&self) -> Self {
+        Self {
+            path: self.path.clone(),
+            history: self.history.clone(),
+        }
+    }
+}
+
+impl<C: ShellConfig> Default for SessionState<C> {
+    fn default() -> Self {
+        Self {
+            path: PathStack::<C>::default(),
+            history: CommandHistory::default(),
+        }
+    }
+}
@@ -0,0 +1,20 @@
+//! Application-supplied timer for bounding async command duration.
+//!
+//! `core` has no clock, so the platform provides expiry via a poll-based,
+//! object-safe trait (no allocation, usable as `&dyn Timer`).
+
+use core::task::{Context, Poll};
+
+/// One-shot timer used to enforce `ShellConfig::ASYNC_TIMEOUT_MS`.
+///
+/// The shell calls `start()` before dispatching an async command, then polls
+/// `poll_expired()` alongside the command future. Only one timeout is armed at a time.
+pub trait Timer {
+    /// Arm the timer to expire `ms` milliseconds from now (replaces any previous deadline).
+    fn start(&self, ms: u32);
+
+    /// Poll for expiry.
+    ///
+    /// When returning `Pending`, MUST arrange for `cx`'s waker to be woken at the deadline.
+    fn poll_expired(&self, cx: &mut Context<'_>) -> Poll<()>;
+}
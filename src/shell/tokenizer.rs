@@ -0,0 +1,292 @@
+//! Command line tokenizer.
+//!
+//! Splits an input line into path and arguments, honoring double quotes and backslash escapes.
+//! Unescaped token text is written to a caller-provided buffer; tokens borrow from it (no heap).
+
+use crate::config::StringBuffer;
+use crate::error::CliError;
+
+/// Split `input` into tokens, unquoting and unescaping into `buf`.
+///
+/// - Whitespace separates tokens (outside quotes)
+/// - `"..."` groups text containing whitespace; quotes may appear mid-token (`a"b c"` → `ab c`)
+/// - `\` escapes the next character (`\"`, `\\`, `\ `); a trailing `\` is kept literally
+/// - `""` yields an empty token
+///
+/// Returns `UnterminatedQuote` for an unclosed quote, `TooManyArgs` for more than `N` tokens,
+/// or `BufferFull` if `buf` overflows.
+/// Output never exceeds input length, so a `buf` as large as the input buffer always fits.
+pub fn tokenize<'b, B: StringBuffer, const N: usize>(
+    input: &str,
+    buf: &'b mut B,
+) -> Result<heapless::Vec<&'b str, N>, CliError> {
+    tokenize_with(input, buf, false)
+}
+
+/// `tokenize()`, optionally interpreting control escapes inside quotes.
+///
+/// With `quote_escapes`, `\n`, `\t`, `\r`, and `\xNN` (`00`-`7F`) within `"..."` become the
+/// corresponding character; other escapes, and all escapes outside quotes, are unchanged.
+pub fn tokenize_with<'b, B: StringBuffer, const N: usize>(
+    input: &str,
+    buf: &'b mut B,
+    quote_escapes: bool,
+) -> Result<heapless::Vec<&'b str, N>, CliError> {
+    tokenize_delimited(input, buf, quote_escapes, ' ')
+}
+
+/// `tokenize_with()`, also separating tokens at `delimiter` (outside quotes).
+///
+/// Whitespace still separates tokens, and runs of separators collapse like spaces do
+/// (`led,,on` → `led`, `on`; use `""` for an empty argument). `\,` keeps a literal delimiter.
+pub fn tokenize_delimited<'b, B: StringBuffer, const N: usize>(
+    input: &str,
+    buf: &'b mut B,
+    quote_escapes: bool,
+    delimiter: char,
+) -> Result<heapless::Vec<&'b str, N>, CliError> {
+    buf.clear();
+
+    // Token spans as byte ranges into buf (buf is still being written)
+    let mut spans: heapless::Vec<(usize, usize), N> = heapless::Vec::new();
+    let mut start: Option<usize> = None;
+    let mut in_quotes = false;
+    let mut escaped = false;
+
+    let mut chars = input.char_indices();
+    while let Some((i, c)) = chars.next() {
+        if escaped {
+            escaped = false;
+            let c = match c {
+                _ if !(quote_escapes && in_quotes) => c,
+                'n' => '\n',
+                't' => '\t',
+                'r' => '\r',
+                'x' => match hex_escape(input.get(i + 1..i + 3)) {
+                    Some(decoded) => {
+                        chars.nth(1); // Skip the two hex digits
+                        decoded
+                    }
+                    None => c,
+                },
+                c => c,
+            };
+            buf.push(c).map_err(|_| CliError::BufferFull)?;
+            continue;
+        }
+
+        match c {
+            '\\' => {
+                escaped = true;
+                start.get_or_insert(buf.len());
+            }
+            '"' => {
+                in_quotes = !in_quotes;
+                start.get_or_insert(buf.len());
+            }
+            c if (c.is_whitespace() || c == delimiter) && !in_quotes => {
+                if let Some(s) = start.take() {
+                    spans
+                        .push((s, buf.len()))
+                        .map_err(|_| CliError::TooManyArgs)?;
+                }
+            }
+            c => {
+                start.get_or_insert(buf.len());
+                buf.push(c).map_err(|_| CliError::BufferFull)?;
+            }
+        }
+    }
+
+    if in_quotes {
+        return Err(CliError::UnterminatedQuote);
+    }
+
+    // Nothing left to escape - keep the backslash
+    if escaped {
+        buf.push('\\').map_err(|_| CliError::BufferFull)?;
+    }
+
+    if let Some(s) = start {
+        spans
+            .push((s, buf.len()))
+            .map_err(|_| CliError::TooManyArgs)?;
+    }
+
+    let text: &'b str = buf.as_str();
+    let mut tokens = heapless::Vec::new();
+    for (s, e) in spans {
+        // Capacities match, cannot fail
+        let _ = tokens.push(&text[s..e]);
+    }
+
+    Ok(tokens)
+}
+
+/// Split `input` at each `separator` outside double quotes (not escaped with `\`).
+///
+/// Segments are returned raw (quotes and escapes intact) for tokenizing one by one.
+pub fn split_unquoted(input: &str, separator: char) -> SplitUnquoted<'_> {
+    SplitUnquoted {
+        rest: Some(input),
+        separator,
+    }
+}
+
+/// Iterator returned by `split_unquoted()`.
+#[derive(Debug, Clone)]
+pub struct SplitUnquoted<'a> {
+    rest: Option<&'a str>,
+    separator: char,
+}
+
+impl<'a> Iterator for SplitUnquoted<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        let rest = self.rest?;
+        let mut in_quotes = false;
+        let mut escaped = false;
+
+        for (i, c) in rest.char_indices() {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_quotes = !in_quotes;
+            } else if c == self.separator && !in_quotes {
+                self.rest = Some(&rest[i + c.len_utf8()..]);
+                return Some(&rest[..i]);
+            }
+        }
+
+        self.rest = None;
+        Some(rest)
+    }
+}
+
+/// ASCII character for the two hex digits of a `\xNN` escape.
+fn hex_escape(digits: Option<&str>) -> Option<char> {
+    let digits = digits.filter(|d| d.bytes().all(|b| b.is_ascii_hexdigit()))?;
+    let byte = u8::from_str_radix(digits, 16).ok()?;
+    byte.is_ascii().then_some(byte as char)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(input: &str, expected: &[&str]) {
+        let mut buf: heapless::String<128> = heapless::String::new();
+        let tokens: heapless::Vec<&str, 17> = tokenize(input, &mut buf).unwrap();
+        assert_eq!(tokens.as_slice(), expected, "input: {:?}", input);
+    }
+
+    #[test]
+    fn test_whitespace_splitting() {
+        check("", &[]);
+        check("   ", &[]);
+        check("echo", &["echo"]);
+        check("  led   on\t1 ", &["led", "on", "1"]);
+    }
+
+    #[test]
+    fn test_double_quotes() {
+        check(r#"echo "hello world""#, &["echo", "hello world"]);
+        check(r#"echo "" x"#, &["echo", "", "x"]);
+        check(r#"set a"b c"d"#, &["set", "ab cd"]);
+        check(r#"echo "  padded  ""#, &["echo", "  padded  "]);
+    }
+
+    #[test]
+    fn test_backslash_escapes() {
+        check(r#"echo \"quoted\""#, &["echo", "\"quoted\""]);
+        check(r"echo a\ b", &["echo", "a b"]);
+        check(r"echo a\\b", &["echo", r"a\b"]);
+        check(r#"echo "say \"hi\"""#, &["echo", r#"say "hi""#]);
+        check(r"echo C:\", &["echo", r"C:\"]);
+    }
+
+    #[test]
+    fn test_quote_escapes() {
+        fn check_escapes(input: &str, expected: &[&str]) {
+            let mut buf: heapless::String<128> = heapless::String::new();
+            let tokens: heapless::Vec<&str, 17> = tokenize_with(input, &mut buf, true).unwrap();
+            assert_eq!(tokens.as_slice(), expected, "input: {:?}", input);
+        }
+
+        check_escapes(r#"echo "a\nb\t\r""#, &["echo", "a\nb\t\r"]);
+        check_escapes(r#"echo "\x1b[2J\x41""#, &["echo", "\x1b[2JA"]);
+        // Outside quotes, non-ASCII, and malformed escapes keep today's behavior
+        check_escapes(r"echo \n", &["echo", "n"]);
+        check_escapes(r#"echo "\xff\xg1\x4""#, &["echo", "xffxg1x4"]);
+        check_escapes(r#"echo "\"\\""#, &["echo", r#""\"#]);
+
+        // Off by default
+        check(r#"echo "a\nb""#, &["echo", "anb"]);
+    }
+
+    #[test]
+    fn test_delimiter() {
+        fn check_delimited(input: &str, expected: &[&str]) {
+            let mut buf: heapless::String<128> = heapless::String::new();
+            let tokens: heapless::Vec<&str, 17> =
+                tokenize_delimited(input, &mut buf, false, ',').unwrap();
+            assert_eq!(tokens.as_slice(), expected, "input: {:?}", input);
+        }
+
+        check_delimited("led,on", &["led", "on"]);
+        check_delimited("set,name,\"a,b\"", &["set", "name", "a,b"]);
+        check_delimited(r"echo a\,b", &["echo", "a,b"]);
+        check_delimited("led,,on, 1", &["led", "on", "1"]);
+        check_delimited(r#"echo,"",x"#, &["echo", "", "x"]);
+
+        // Without a delimiter, commas are ordinary characters
+        check("led,on", &["led,on"]);
+    }
+
+    #[test]
+    fn test_split_unquoted() {
+        fn split(input: &str) -> heapless::Vec<&str, 8> {
+            split_unquoted(input, ';').collect()
+        }
+
+        assert_eq!(split("led on"), ["led on"]);
+        assert_eq!(
+            split("led on; status;led off"),
+            ["led on", " status", "led off"]
+        );
+        assert_eq!(
+            split(r#"echo "a;b"; echo c\;d"#),
+            [r#"echo "a;b""#, r" echo c\;d"]
+        );
+        assert_eq!(split("a;;"), ["a", "", ""]);
+    }
+
+    #[test]
+    fn test_unterminated_quote() {
+        let mut buf: heapless::String<128> = heapless::String::new();
+        let result: Result<heapless::Vec<&str, 17>, _> = tokenize(r#"echo "oops"#, &mut buf);
+        assert_eq!(result, Err(CliError::UnterminatedQuote));
+
+        // Escaped quote does not open a quoted section
+        check(r#"echo \"ok"#, &["echo", "\"ok"]);
+    }
+
+    #[test]
+    fn test_token_overflow() {
+        let mut buf: heapless::String<128> = heapless::String::new();
+        let result: Result<heapless::Vec<&str, 2>, _> = tokenize("a b c", &mut buf);
+        assert_eq!(result, Err(CliError::TooManyArgs));
+
+        // Trailing whitespace: overflow detected at the separator
+        let mut buf2: heapless::String<128> = heapless::String::new();
+        let result: Result<heapless::Vec<&str, 2>, _> = tokenize("a b c ", &mut buf2);
+        assert_eq!(result, Err(CliError::TooManyArgs));
+
+        let mut small: heapless::String<2> = heapless::String::new();
+        let result: Result<heapless::Vec<&str, 4>, _> = tokenize("abc", &mut small);
+        assert_eq!(result, Err(CliError::BufferFull));
+    }
+}
@@ -0,0 +1,46 @@
+//! Terminal column width of characters.
+//!
+//! Minimal East Asian Width check (wide and fullwidth ranges) so line editing can erase
+//! the columns a glyph actually occupies. Not a full `wcwidth`: combining marks count as 1.
+
+/// Columns occupied by `c` on a terminal (2 for wide/fullwidth, otherwise 1).
+pub(crate) fn char_width(c: char) -> usize {
+    let cp = c as u32;
+    let wide = matches!(
+        cp,
+        0x1100..=0x115F         // Hangul Jamo initials
+            | 0x2E80..=0x303E   // CJK radicals, Kangxi, CJK symbols and punctuation
+            | 0x3041..=0x33FF   // Hiragana, Katakana, Bopomofo, CJK compatibility
+            | 0x3400..=0x4DBF   // CJK Extension A
+            | 0x4E00..=0x9FFF   // CJK Unified Ideographs
+            | 0xA000..=0xA4CF   // Yi
+            | 0xAC00..=0xD7A3   // Hangul syllables
+            | 0xF900..=0xFAFF   // CJK compatibility ideographs
+            | 0xFE30..=0xFE4F   // CJK compatibility forms
+            | 0xFF00..=0xFF60   // Fullwidth forms
+            | 0xFFE0..=0xFFE6   // Fullwidth signs
+            | 0x1F300..=0x1F64F // Pictographs and emoticons
+            | 0x1F900..=0x1F9FF // Supplemental symbols and pictographs
+            | 0x20000..=0x3FFFD // CJK Extensions B and beyond
+    );
+    if wide { 2 } else { 1 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_narrow_characters() {
+        for c in ['a', '~', 'é', 'ж', '→'] {
+            assert_eq!(char_width(c), 1, "{:?}", c);
+        }
+    }
+
+    #[test]
+    fn test_wide_characters() {
+        for c in ['中', 'あ', 'カ', '한', 'Ａ', '😀', '𠀋'] {
+            assert_eq!(char_width(c), 2, "{:?}", c);
+        }
+    }
+}
@@ -0,0 +1,294 @@
+//! Scripted keystroke sessions for testing command trees and handlers.
+//!
+//! `ScriptedSession` drives a `Shell` over the in-memory `MockIo`, replacing hand-written
+//! `process_char()` loops and output scraping:
+//!
+//! ```rust,ignore
+//! let mut session = ScriptedSession::new(shell);
+//! session.activate().clear_output();
+//! session.type_line("system/status").assert_contains("OK");
+//! session.press(InputEvent::UpArrow).assert_contains("system/status");
+//! ```
+//!
+//! Only available with the `testing` feature.
+
+use crate::auth::AccessLevel;
+use crate::config::ShellConfig;
+use crate::io::CharIo;
+use crate::shell::{CommandHandler, InputEvent, Shell};
+
+/// `CharIo` capturing output in memory (`N` bytes); `get_char()` always returns `None`.
+///
+/// Output that doesn't fit is rejected with `Err(())`, so a test can't silently miss it.
+#[derive(Debug, Clone, Default)]
+pub struct MockIo<const N: usize = 4096> {
+    output: heapless::String<N>,
+}
+
+impl<const N: usize> MockIo<N> {
+    /// Create with empty output.
+    pub const fn new() -> Self {
+        Self {
+            output: heapless::String::new(),
+        }
+    }
+
+    /// Output captured since creation or the last `clear_output()`.
+    pub fn output(&self) -> &str {
+        &self.output
+    }
+
+    /// Discard captured output.
+    pub fn clear_output(&mut self) {
+        self.output.clear();
+    }
+}
+
+impl<const N: usize> CharIo for MockIo<N> {
+    type Error = ();
+
+    fn get_char(&mut self) -> Result<Option<char>, Self::Error> {
+        // Input is fed to the shell directly, not polled
+        Ok(None)
+    }
+
+    fn put_char(&mut self, c: char) -> Result<(), Self::Error> {
+        self.output.push(c).map_err(|_| ())
+    }
+
+    fn write_str(&mut self, s: &str) -> Result<(), Self::Error> {
+        self.output.push_str(s).map_err(|_| ())
+    }
+}
+
+/// `Shell` over `MockIo` driven by scripted keystrokes, with output assertions.
+///
+/// Methods chain and panic on failure (including a full output capture), like `assert!`.
+pub struct ScriptedSession<'tree, L, H, C>
+where
+    L: AccessLevel,
+    H: CommandHandler<C>,
+    C: ShellConfig,
+{
+    shell: Shell<'tree, L, MockIo, H, C>,
+}
+
+impl<'tree, L, H, C> ScriptedSession<'tree, L, H, C>
+where
+    L: AccessLevel,
+    H: CommandHandler<C>,
+    C: ShellConfig,
+{
+    /// Wrap a shell (not yet activated).
+    pub fn new(shell: Shell<'tree, L, MockIo, H, C>) -> Self {
+        Self { shell }
+    }
+
+    /// Activate the shell (welcome message and first prompt).
+    pub fn activate(&mut self) -> &mut Self {
+        self.shell.activate().expect(FULL);
+        self
+    }
+
+    /// Type `keys` one char at a time, without pressing Enter.
+    pub fn type_str(&mut self, keys: &str) -> &mut Self {
+        for c in keys.chars() {
+            self.shell.process_char(c).expect(FULL);
+        }
+        self
+    }
+
+    /// Type `line` and press Enter.
+    pub fn type_line(&mut self, line: &str) -> &mut Self {
+        self.type_str(line).press(InputEvent::Enter)
+    }
+
+    /// Press the key (or key sequence) that decodes to `event`.
+    ///
+    /// `Escape` is followed by `Shell::escape_timeout()`; `None` sends nothing.
+    pub fn press(&mut self, event: InputEvent) -> &mut Self {
+        let mut buf = [0u8; 4];
+        let keys = match event {
+            InputEvent::None => "",
+            InputEvent::Char(c) => c.encode_utf8(&mut buf),
+            InputEvent::Backspace => "\x7f",
+            InputEvent::Enter => "\r",
+            InputEvent::Tab => "\t",
+            InputEvent::UpArrow => "\x1b[A",
+            InputEvent::DownArrow => "\x1b[B",
+            InputEvent::DoubleEsc => "\x1b\x1b",
+            InputEvent::Escape => "\x1b",
+            InputEvent::Cancel => "\x03",
+            InputEvent::PasteStart => "\x1b[200~",
+            InputEvent::PasteEnd => "\x1b[201~",
+        };
+        self.type_str(keys);
+
+        if event == InputEvent::Escape {
+            self.shell.escape_timeout().expect(FULL);
+        }
+        self
+    }
+
+    /// Output captured since activation or the last `clear_output()`.
+    pub fn output(&self) -> &str {
+        self.shell.io().output()
+    }
+
+    /// Discard captured output (e.g. the welcome banner before asserting on a command).
+    pub fn clear_output(&mut self) -> &mut Self {
+        self.shell.io_mut().clear_output();
+        self
+    }
+
+    /// Panic unless the captured output contains `expected`.
+    #[track_caller]
+    pub fn assert_contains(&mut self, expected: &str) -> &mut Self {
+        assert!(
+            self.output().contains(expected),
+            "expected output to contain {:?}, got {:?}",
+            expected,
+            self.output()
+        );
+        self
+    }
+
+    /// Panic if the captured output contains `unexpected`.
+    #[track_caller]
+    pub fn assert_not_contains(&mut self, unexpected: &str) -> &mut Self {
+        assert!(
+            !self.output().contains(unexpected),
+            "expected output not to contain {:?}, got {:?}",
+            unexpected,
+            self.output()
+        );
+        self
+    }
+
+    /// The wrapped shell.
+    pub fn shell(&self) -> &Shell<'tree, L, MockIo, H, C> {
+        &self.shell
+    }
+
+    /// The wrapped shell (mutable, e.g. to install hooks mid-script).
+    pub fn shell_mut(&mut self) -> &mut Shell<'tree, L, MockIo, H, C> {
+        &mut self.shell
+    }
+
+    /// Unwrap the shell.
+    pub fn into_shell(self) -> Shell<'tree, L, MockIo, H, C> {
+        self.shell
+    }
+}
+
+impl<L, H, C> core::fmt::Debug for ScriptedSession<'_, L, H, C>
+where
+    L: AccessLevel,
+    H: CommandHandler<C>,
+    C: ShellConfig,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ScriptedSession")
+            .field("output", &self.output())
+            .finish_non_exhaustive()
+    }
+}
+
+const FULL: &str = "ScriptedSession output capture full (call clear_output())";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_io_rejects_overflow() {
+        let mut io: MockIo<4> = MockIo::new();
+        assert_eq!(io.write_str("abc"), Ok(()));
+        assert_eq!(io.write_str("de"), Err(()));
+        assert_eq!(io.put_char('d'), Ok(()));
+        assert_eq!(io.output(), "abcd");
+    }
+
+    #[cfg(not(feature = "authentication"))]
+    mod session {
+        use crate::config::DefaultConfig;
+        use crate::error::CliError;
+        use crate::response::Response;
+        use crate::testing::*;
+        use crate::tree::{CommandKind, CommandMeta, Directory, Node};
+
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+        enum Level {
+            User = 0,
+        }
+
+        impl AccessLevel for Level {
+            fn from_str(s: &str) -> Option<Self> {
+                (s == "User").then_some(Level::User)
+            }
+
+            fn as_str(&self) -> &'static str {
+                "User"
+            }
+
+            fn rank(&self) -> u8 {
+                *self as u8
+            }
+        }
+
+        const STATUS: CommandMeta<Level> =
+            CommandMeta::new("status", "status", Level::User, CommandKind::Sync)
+                .with_description("Show status");
+
+        const ROOT: Directory<Level> = Directory::new("/", &[Node::Command(&STATUS)], Level::User);
+
+        struct Handler;
+
+        impl CommandHandler<DefaultConfig> for Handler {
+            fn execute_sync(
+                &self,
+                _id: &str,
+                _args: &[&str],
+            ) -> Result<Response<DefaultConfig>, CliError> {
+                Ok(Response::success("all good"))
+            }
+
+            #[cfg(feature = "async")]
+            async fn execute_async(
+                &self,
+                id: &str,
+                args: &[&str],
+                _cancel: &crate::CancelToken,
+            ) -> Result<Response<DefaultConfig>, CliError> {
+                self.execute_sync(id, args)
+            }
+        }
+
+        #[test]
+        fn test_scripted_session() {
+            let shell: Shell<_, _, _, DefaultConfig> = Shell::new(&ROOT, Handler, MockIo::new());
+            let mut session = ScriptedSession::new(shell);
+
+            session
+                .activate()
+                .assert_contains(DefaultConfig::MSG_WELCOME)
+                .clear_output()
+                .type_line("status")
+                .assert_contains("all good")
+                .assert_not_contains("Error");
+
+            // Partially typed line is discarded by Ctrl+C
+            session
+                .clear_output()
+                .type_str("sta")
+                .press(InputEvent::Cancel)
+                .assert_contains("sta^C");
+
+            #[cfg(feature = "history")]
+            session
+                .clear_output()
+                .press(InputEvent::UpArrow)
+                .assert_contains("status");
+        }
+    }
+}
@@ -0,0 +1,20 @@
+//! Internal trace points for diagnosing resolution, dispatch, and login on hardware.
+//!
+//! Forward to `defmt` with the `defmt` feature on bare-metal targets (hosted builds have
+//! no defmt logger to link against); otherwise expand to nothing.
+
+/// `defmt::trace!` when enabled, nothing otherwise.
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        #[cfg(all(feature = "defmt", target_os = "none"))]
+        defmt::trace!($($arg)*);
+    };
+}
+
+/// `defmt::debug!` when enabled, nothing otherwise.
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        #[cfg(all(feature = "defmt", target_os = "none"))]
+        defmt::debug!($($arg)*);
+    };
+}
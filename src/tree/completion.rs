@@ -9,10 +9,9 @@ use crate::auth::AccessLevel;
 use crate::error::CliError;
 use crate::tree::Directory;
 
-#[cfg(feature = "completion")]
-use crate::tree::Node;
-
 /// Tab completion result with type-safe variants for different match outcomes.
+///
+/// Names borrow from the (static) tree, so a result holds no string buffers.
 #[derive(Debug, Clone, PartialEq)]
 pub enum CompletionResult<const MAX_MATCHES: usize> {
     /// No matches found for the input prefix
@@ -20,9 +19,8 @@ pub enum CompletionResult<const MAX_MATCHES: usize> {
 
     /// Exactly one match found (auto-completable)
     Single {
-        /// The completed name (with "/" appended for directories)
-        // TODO: Use C::MAX_INPUT when const generics stabilize
-        completion: heapless::String<128>,
+        /// The completed name (append "/" when `is_directory`)
+        completion: &'static str,
 
         /// True if the match is a directory
         is_directory: bool,
@@ -31,12 +29,10 @@ pub enum CompletionResult<const MAX_MATCHES: usize> {
     /// Multiple matches found (show options to user)
     Multiple {
         /// Common prefix of all matches
-        // TODO: Use C::MAX_INPUT when const generics stabilize
-        common_prefix: heapless::String<128>,
+        common_prefix: &'static str,
 
         /// All matching node names (for display)
-        // TODO: Consider using C::MAX_INPUT or a separate config constant when const generics stabilize
-        all_matches: heapless::Vec<heapless::String<64>, MAX_MATCHES>,
+        all_matches: heapless::Vec<&'static str, MAX_MATCHES>,
     },
 }
 
@@ -52,30 +48,40 @@ impl<const MAX_MATCHES: usize> CompletionResult<MAX_MATCHES> {
 // ============================================================================
 
 /// Suggest completions for partial input using prefix matching.
-/// Directories get "/" appended for single matches.
 #[cfg(feature = "completion")]
 pub fn suggest_completions<L: AccessLevel, const MAX_MATCHES: usize>(
     dir: &Directory<L>,
     input: &str,
     current_user: Option<&crate::auth::User<L>>,
+) -> Result<CompletionResult<MAX_MATCHES>, CliError> {
+    suggest_completions_with(dir, input, current_user, &[])
+}
+
+/// `suggest_completions()`, also offering `extra` command names (e.g. the shell's global
+/// commands) after the directory's children. Extras are skipped for empty input.
+#[cfg(feature = "completion")]
+pub fn suggest_completions_with<L: AccessLevel, const MAX_MATCHES: usize>(
+    dir: &Directory<L>,
+    input: &str,
+    current_user: Option<&crate::auth::User<L>>,
+    extra: &[&'static str],
 ) -> Result<CompletionResult<MAX_MATCHES>, CliError> {
     // Find all matching nodes
-    let mut matches: heapless::Vec<(&str, bool), MAX_MATCHES> = heapless::Vec::new();
+    let mut matches: heapless::Vec<(&'static str, bool), MAX_MATCHES> = heapless::Vec::new();
 
     for child in dir.children.iter() {
-        // Check access control
-        let node_level = match child {
-            Node::Command(cmd) => cmd.access_level,
-            Node::Directory(d) => d.access_level,
-        };
-
-        // Filter by access level
+        // Filter by access level and role
         if let Some(user) = current_user
-            && user.access_level < node_level
+            && !user.can_access(child.access_level(), child.required_role())
         {
             continue; // User lacks access, skip this node
         }
 
+        // Hidden nodes must be typed in full
+        if child.is_hidden() {
+            continue;
+        }
+
         let name = child.name();
         let is_dir = child.is_directory();
 
@@ -87,6 +93,17 @@ pub fn suggest_completions<L: AccessLevel, const MAX_MATCHES: usize>(
         }
     }
 
+    // Extra names shadowed by a child are offered once
+    if !input.is_empty() {
+        for &name in extra {
+            if name.starts_with(input) && !matches.iter().any(|&(m, _)| m == name) {
+                matches
+                    .push((name, false))
+                    .map_err(|_| CliError::BufferFull)?;
+            }
+        }
+    }
+
     // No matches
     if matches.is_empty() {
         return Ok(CompletionResult::None);
@@ -95,40 +112,17 @@ pub fn suggest_completions<L: AccessLevel, const MAX_MATCHES: usize>(
     // Single match - complete!
     if matches.len() == 1 {
         let (name, is_dir) = matches[0];
-        let mut completion = heapless::String::new();
-        completion
-            .push_str(name)
-            .map_err(|_| CliError::BufferFull)?;
-
-        // Auto-append "/" for directories
-        if is_dir {
-            completion.push('/').map_err(|_| CliError::BufferFull)?;
-        }
-
         return Ok(CompletionResult::Single {
-            completion,
+            completion: name,
             is_directory: is_dir,
         });
     }
 
     // Multiple matches - find common prefix
-    let common_prefix_str = find_common_prefix(&matches);
-
-    let mut common_prefix = heapless::String::new();
-    common_prefix
-        .push_str(common_prefix_str)
-        .map_err(|_| CliError::BufferFull)?;
-
-    // Collect all match names for display
-    // TODO: Consider using C::MAX_INPUT or a separate config constant when const generics stabilize
-    let mut all_matches: heapless::Vec<heapless::String<64>, MAX_MATCHES> = heapless::Vec::new();
-    for (name, _) in matches.iter() {
-        let mut match_str = heapless::String::new();
-        match_str.push_str(name).map_err(|_| CliError::BufferFull)?;
-        all_matches
-            .push(match_str)
-            .map_err(|_| CliError::BufferFull)?;
-    }
+    let common_prefix = find_common_prefix(&matches);
+
+    // Collect all match names for display (same capacity as matches, cannot fail)
+    let all_matches = matches.iter().map(|&(name, _)| name).collect();
 
     Ok(CompletionResult::Multiple {
         common_prefix,
@@ -177,6 +171,17 @@ pub fn suggest_completions<L: AccessLevel, const MAX_MATCHES: usize>(
     Ok(CompletionResult::empty())
 }
 
+/// Stub implementation when completion feature is disabled.
+#[cfg(not(feature = "completion"))]
+pub fn suggest_completions_with<L: AccessLevel, const MAX_MATCHES: usize>(
+    _dir: &Directory<L>,
+    _input: &str,
+    _current_user: Option<&crate::auth::User<L>>,
+    _extra: &[&'static str],
+) -> Result<CompletionResult<MAX_MATCHES>, CliError> {
+    Ok(CompletionResult::empty())
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -212,64 +217,37 @@ mod tests {
                 Self::Admin => "Admin",
             }
         }
+
+        fn rank(&self) -> u8 {
+            *self as u8
+        }
     }
 
     // Test fixtures
-    const CMD_STATUS: CommandMeta<TestLevel> = CommandMeta {
-        id: "status",
-        name: "status",
-        description: "Show status",
-        access_level: TestLevel::User,
-        kind: CommandKind::Sync,
-        min_args: 0,
-        max_args: 0,
-    };
-
-    const CMD_START: CommandMeta<TestLevel> = CommandMeta {
-        id: "start",
-        name: "start",
-        description: "Start service",
-        access_level: TestLevel::User,
-        kind: CommandKind::Sync,
-        min_args: 0,
-        max_args: 1,
-    };
-
-    const CMD_STOP: CommandMeta<TestLevel> = CommandMeta {
-        id: "stop",
-        name: "stop",
-        description: "Stop service",
-        access_level: TestLevel::User,
-        kind: CommandKind::Sync,
-        min_args: 0,
-        max_args: 0,
-    };
-
-    const CMD_REBOOT: CommandMeta<TestLevel> = CommandMeta {
-        id: "reboot",
-        name: "reboot",
-        description: "Reboot system",
-        access_level: TestLevel::Admin,
-        kind: CommandKind::Sync,
-        min_args: 0,
-        max_args: 0,
-    };
-
-    const DIR_SYSTEM: Directory<TestLevel> = Directory {
-        name: "system",
-        children: &[],
-        access_level: TestLevel::User,
-    };
-
-    const DIR_SERVICES: Directory<TestLevel> = Directory {
-        name: "services",
-        children: &[],
-        access_level: TestLevel::User,
-    };
-
-    const TEST_DIR: Directory<TestLevel> = Directory {
-        name: "test",
-        children: &[
+    const CMD_STATUS: CommandMeta<TestLevel> =
+        CommandMeta::new("status", "status", TestLevel::User, CommandKind::Sync)
+            .with_description("Show status");
+
+    const CMD_START: CommandMeta<TestLevel> =
+        CommandMeta::new("start", "start", TestLevel::User, CommandKind::Sync)
+            .with_description("Start service")
+            .with_args(0, 1);
+
+    const CMD_STOP: CommandMeta<TestLevel> =
+        CommandMeta::new("stop", "stop", TestLevel::User, CommandKind::Sync)
+            .with_description("Stop service");
+
+    const CMD_REBOOT: CommandMeta<TestLevel> =
+        CommandMeta::new("reboot", "reboot", TestLevel::Admin, CommandKind::Sync)
+            .with_description("Reboot system");
+
+    const DIR_SYSTEM: Directory<TestLevel> = Directory::new("system", &[], TestLevel::User);
+
+    const DIR_SERVICES: Directory<TestLevel> = Directory::new("services", &[], TestLevel::User);
+
+    const TEST_DIR: Directory<TestLevel> = Directory::new(
+        "test",
+        &[
             Node::Command(&CMD_STATUS),
             Node::Command(&CMD_START),
             Node::Command(&CMD_STOP),
@@ -277,8 +255,8 @@ mod tests {
             Node::Directory(&DIR_SYSTEM),
             Node::Directory(&DIR_SERVICES),
         ],
-        access_level: TestLevel::Guest,
-    };
+        TestLevel::Guest,
+    );
 
     #[test]
     #[cfg(feature = "completion")]
@@ -290,7 +268,7 @@ mod tests {
                 completion,
                 is_directory,
             } => {
-                assert_eq!(completion.as_str(), "reboot");
+                assert_eq!(completion, "reboot");
                 assert!(!is_directory);
             }
             _ => panic!("Expected Single variant"),
@@ -307,7 +285,7 @@ mod tests {
                 completion,
                 is_directory,
             } => {
-                assert_eq!(completion.as_str(), "system/");
+                assert_eq!(completion, "system");
                 assert!(is_directory);
             }
             _ => panic!("Expected Single variant"),
@@ -325,14 +303,14 @@ mod tests {
                 all_matches,
             } => {
                 // Common prefix is "st" for "status", "start", "stop"
-                assert_eq!(common_prefix.as_str(), "st");
+                assert_eq!(common_prefix, "st");
                 assert_eq!(all_matches.len(), 3);
 
                 // Check all matches present (verify each is in the result)
                 let match_names: [&str; 3] = ["status", "start", "stop"];
                 for expected in &match_names {
                     assert!(
-                        all_matches.iter().any(|m| m.as_str() == *expected),
+                        all_matches.contains(expected),
                         "Expected to find '{}' in matches",
                         expected
                     );
@@ -353,7 +331,7 @@ mod tests {
                 all_matches,
             } => {
                 // Should match: status, start, stop, system, services
-                assert_eq!(common_prefix.as_str(), "s");
+                assert_eq!(common_prefix, "s");
                 assert_eq!(all_matches.len(), 5);
             }
             _ => panic!("Expected Multiple variant"),
@@ -383,7 +361,7 @@ mod tests {
                 completion,
                 is_directory,
             } => {
-                assert_eq!(completion.as_str(), "status");
+                assert_eq!(completion, "status");
                 assert!(!is_directory);
             }
             _ => panic!("Expected Single variant"),
@@ -400,7 +378,7 @@ mod tests {
                 completion,
                 is_directory,
             } => {
-                assert_eq!(completion.as_str(), "system/");
+                assert_eq!(completion, "system");
                 assert!(is_directory);
             }
             _ => panic!("Expected Single variant"),
@@ -421,6 +399,37 @@ mod tests {
         }
     }
 
+    #[test]
+    #[cfg(feature = "completion")]
+    fn test_extra_names() {
+        const EXTRA: &[&str] = &["clear", "stop", "exit"];
+
+        let result =
+            suggest_completions_with::<TestLevel, 16>(&TEST_DIR, "cl", None, EXTRA).unwrap();
+        match result {
+            CompletionResult::Single {
+                completion,
+                is_directory,
+            } => {
+                assert_eq!(completion, "clear");
+                assert!(!is_directory);
+            }
+            _ => panic!("Expected Single variant"),
+        }
+
+        // A child with the same name is offered once
+        let result =
+            suggest_completions_with::<TestLevel, 16>(&TEST_DIR, "sto", None, EXTRA).unwrap();
+        assert!(matches!(result, CompletionResult::Single { .. }));
+
+        // Empty input lists the directory only
+        let result = suggest_completions_with::<TestLevel, 16>(&TEST_DIR, "", None, EXTRA).unwrap();
+        match result {
+            CompletionResult::Multiple { all_matches, .. } => assert_eq!(all_matches.len(), 6),
+            _ => panic!("Expected Multiple variant"),
+        }
+    }
+
     #[test]
     #[cfg(feature = "completion")]
     fn test_case_sensitive_matching() {
@@ -453,18 +462,7 @@ mod tests {
         use crate::auth::User;
 
         // Create guest user (no access to Admin commands)
-        let guest_user = User {
-            username: {
-                let mut s = heapless::String::new();
-                s.push_str("guest").unwrap();
-                s
-            },
-            access_level: TestLevel::Guest,
-            #[cfg(feature = "authentication")]
-            password_hash: [0u8; 32],
-            #[cfg(feature = "authentication")]
-            salt: [0u8; 16],
-        };
+        let guest_user = User::builder("guest", TestLevel::Guest).build().unwrap();
 
         // "r" should NOT match "reboot" (Admin only) for guest user
         let result =
@@ -478,18 +476,7 @@ mod tests {
         }
 
         // Create admin user
-        let admin_user = User {
-            username: {
-                let mut s = heapless::String::new();
-                s.push_str("admin").unwrap();
-                s
-            },
-            access_level: TestLevel::Admin,
-            #[cfg(feature = "authentication")]
-            password_hash: [0u8; 32],
-            #[cfg(feature = "authentication")]
-            salt: [0u8; 16],
-        };
+        let admin_user = User::builder("admin", TestLevel::Admin).build().unwrap();
 
         // "r" should match "reboot" for admin user
         let result =
@@ -497,10 +484,38 @@ mod tests {
 
         match result {
             CompletionResult::Single { completion, .. } => {
-                assert_eq!(completion.as_str(), "reboot");
+                assert_eq!(completion, "reboot");
+            }
+            _ => panic!("Expected Single variant"),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "completion")]
+    fn test_hidden_nodes_not_suggested() {
+        const CMD_SELFTEST: CommandMeta<TestLevel> =
+            CommandMeta::new("selftest", "selftest", TestLevel::User, CommandKind::Sync)
+                .with_description("Factory self-test")
+                .hidden();
+
+        const DIR: Directory<TestLevel> = Directory::new(
+            "test",
+            &[Node::Command(&CMD_STATUS), Node::Command(&CMD_SELFTEST)],
+            TestLevel::Guest,
+        );
+
+        // "s" matches only the visible "status"
+        let result = suggest_completions::<TestLevel, 16>(&DIR, "s", None).unwrap();
+        match result {
+            CompletionResult::Single { completion, .. } => {
+                assert_eq!(completion, "status");
             }
             _ => panic!("Expected Single variant"),
         }
+
+        // Full prefix of hidden name still yields nothing
+        let result = suggest_completions::<TestLevel, 16>(&DIR, "selftest", None).unwrap();
+        assert_eq!(result, CompletionResult::None);
     }
 
     #[test]
@@ -524,53 +539,29 @@ mod tests {
     #[cfg(feature = "completion")]
     fn test_max_matches_exceeded() {
         // Create directory with more nodes than MAX_MATCHES
-        const CMD1: CommandMeta<TestLevel> = CommandMeta {
-            id: "a1",
-            name: "a1",
-            description: "Command 1",
-            access_level: TestLevel::Guest,
-            kind: CommandKind::Sync,
-            min_args: 0,
-            max_args: 0,
-        };
-        const CMD2: CommandMeta<TestLevel> = CommandMeta {
-            id: "a2",
-            name: "a2",
-            description: "Command 2",
-            access_level: TestLevel::Guest,
-            kind: CommandKind::Sync,
-            min_args: 0,
-            max_args: 0,
-        };
-        const CMD3: CommandMeta<TestLevel> = CommandMeta {
-            id: "a3",
-            name: "a3",
-            description: "Command 3",
-            access_level: TestLevel::Guest,
-            kind: CommandKind::Sync,
-            min_args: 0,
-            max_args: 0,
-        };
-        const CMD4: CommandMeta<TestLevel> = CommandMeta {
-            id: "a4",
-            name: "a4",
-            description: "Command 4",
-            access_level: TestLevel::Guest,
-            kind: CommandKind::Sync,
-            min_args: 0,
-            max_args: 0,
-        };
-
-        const OVERFLOW_DIR: Directory<TestLevel> = Directory {
-            name: "overflow",
-            children: &[
+        const CMD1: CommandMeta<TestLevel> =
+            CommandMeta::new("a1", "a1", TestLevel::Guest, CommandKind::Sync)
+                .with_description("Command 1");
+        const CMD2: CommandMeta<TestLevel> =
+            CommandMeta::new("a2", "a2", TestLevel::Guest, CommandKind::Sync)
+                .with_description("Command 2");
+        const CMD3: CommandMeta<TestLevel> =
+            CommandMeta::new("a3", "a3", TestLevel::Guest, CommandKind::Sync)
+                .with_description("Command 3");
+        const CMD4: CommandMeta<TestLevel> =
+            CommandMeta::new("a4", "a4", TestLevel::Guest, CommandKind::Sync)
+                .with_description("Command 4");
+
+        const OVERFLOW_DIR: Directory<TestLevel> = Directory::new(
+            "overflow",
+            &[
                 Node::Command(&CMD1),
                 Node::Command(&CMD2),
                 Node::Command(&CMD3),
                 Node::Command(&CMD4),
             ],
-            access_level: TestLevel::Guest,
-        };
+            TestLevel::Guest,
+        );
 
         // Use MAX_MATCHES = 2, but we have 4 matching items
         let result = suggest_completions::<TestLevel, 2>(&OVERFLOW_DIR, "a", None);
@@ -582,83 +573,91 @@ mod tests {
     #[test]
     #[cfg(feature = "completion")]
     fn test_very_long_command_name() {
-        // Create a command with name > 128 characters
-        const LONG_CMD: CommandMeta<TestLevel> = CommandMeta {
-            id: "long",
-            name: "this_is_a_very_long_command_name_that_exceeds_the_maximum_buffer_size_of_128_characters_and_should_cause_a_buffer_overflow_error_when_completing",
-            description: "Long command",
-            access_level: TestLevel::Guest,
-            kind: CommandKind::Sync,
-            min_args: 0,
-            max_args: 0,
-        };
-
-        const LONG_DIR: Directory<TestLevel> = Directory {
-            name: "long",
-            children: &[Node::Command(&LONG_CMD)],
-            access_level: TestLevel::Guest,
-        };
-
-        // Try to complete - should return BufferFull error
+        // Create a command with name > 128 characters (names are borrowed, nothing to overflow)
+        const LONG_CMD: CommandMeta<TestLevel> = CommandMeta::new("long", "this_is_a_very_long_command_name_that_exceeds_the_maximum_buffer_size_of_128_characters_and_should_cause_a_buffer_overflow_error_when_completing", TestLevel::Guest, CommandKind::Sync)
+            .with_description("Long command");
+
+        const LONG_DIR: Directory<TestLevel> =
+            Directory::new("long", &[Node::Command(&LONG_CMD)], TestLevel::Guest);
+
         let result = suggest_completions::<TestLevel, 16>(&LONG_DIR, "this", None);
 
-        assert!(matches!(result, Err(CliError::BufferFull)));
+        assert_eq!(
+            result,
+            Ok(CompletionResult::Single {
+                completion: LONG_CMD.name,
+                is_directory: false,
+            })
+        );
     }
 
     #[test]
     #[cfg(feature = "completion")]
     fn test_long_directory_name_with_slash() {
-        // Create a directory with name = 128 characters (so adding "/" would overflow)
-        const LONG_DIR_CHILD: Directory<TestLevel> = Directory {
-            name: "this_is_exactly_one_hundred_twenty_eight_characters_long_directory_name_abcdefghijklmnopqrstuvwxyz_0123456789_more_padding_needed",
-            children: &[],
-            access_level: TestLevel::Guest,
-        };
-
-        const LONG_DIR: Directory<TestLevel> = Directory {
-            name: "parent",
-            children: &[Node::Directory(&LONG_DIR_CHILD)],
-            access_level: TestLevel::Guest,
-        };
-
-        // Try to complete - should return BufferFull error when trying to append "/"
+        // Create a directory with name = 128 characters (the "/" is left to the caller)
+        const LONG_DIR_CHILD: Directory<TestLevel> = Directory::new(
+            "this_is_exactly_one_hundred_twenty_eight_characters_long_directory_name_abcdefghijklmnopqrstuvwxyz_0123456789_more_padding_needed",
+            &[],
+            TestLevel::Guest,
+        );
+
+        const LONG_DIR: Directory<TestLevel> = Directory::new(
+            "parent",
+            &[Node::Directory(&LONG_DIR_CHILD)],
+            TestLevel::Guest,
+        );
+
         let result = suggest_completions::<TestLevel, 16>(&LONG_DIR, "this", None);
 
-        assert!(matches!(result, Err(CliError::BufferFull)));
+        assert_eq!(
+            result,
+            Ok(CompletionResult::Single {
+                completion: LONG_DIR_CHILD.name,
+                is_directory: true,
+            })
+        );
     }
 
     #[test]
     #[cfg(feature = "completion")]
     fn test_match_name_exceeds_64_chars() {
-        // Create commands with names > 64 characters (for all_matches buffer)
-        const LONG1: CommandMeta<TestLevel> = CommandMeta {
-            id: "m1",
-            name: "match_name_that_is_longer_than_sixty_four_characters_abcdefghijklm",
-            description: "Long 1",
-            access_level: TestLevel::Guest,
-            kind: CommandKind::Sync,
-            min_args: 0,
-            max_args: 0,
-        };
-        const LONG2: CommandMeta<TestLevel> = CommandMeta {
-            id: "m2",
-            name: "match_name_that_is_longer_than_sixty_four_characters_nopqrstuvwxyz",
-            description: "Long 2",
-            access_level: TestLevel::Guest,
-            kind: CommandKind::Sync,
-            min_args: 0,
-            max_args: 0,
-        };
-
-        const LONG_MATCH_DIR: Directory<TestLevel> = Directory {
-            name: "longmatch",
-            children: &[Node::Command(&LONG1), Node::Command(&LONG2)],
-            access_level: TestLevel::Guest,
-        };
-
-        // Multiple matches with long names should cause BufferFull when building all_matches
+        // Create commands with names > 64 characters
+        const LONG1: CommandMeta<TestLevel> = CommandMeta::new(
+            "m1",
+            "match_name_that_is_longer_than_sixty_four_characters_abcdefghijklm",
+            TestLevel::Guest,
+            CommandKind::Sync,
+        )
+        .with_description("Long 1");
+        const LONG2: CommandMeta<TestLevel> = CommandMeta::new(
+            "m2",
+            "match_name_that_is_longer_than_sixty_four_characters_nopqrstuvwxyz",
+            TestLevel::Guest,
+            CommandKind::Sync,
+        )
+        .with_description("Long 2");
+
+        const LONG_MATCH_DIR: Directory<TestLevel> = Directory::new(
+            "longmatch",
+            &[Node::Command(&LONG1), Node::Command(&LONG2)],
+            TestLevel::Guest,
+        );
+
+        // Multiple matches list long names in full
         let result = suggest_completions::<TestLevel, 16>(&LONG_MATCH_DIR, "match", None);
 
-        assert!(matches!(result, Err(CliError::BufferFull)));
+        match result {
+            Ok(CompletionResult::Multiple {
+                common_prefix,
+                all_matches,
+            }) => {
+                assert_eq!(
+                    common_prefix,
+                    "match_name_that_is_longer_than_sixty_four_characters_"
+                );
+                assert_eq!(all_matches.as_slice(), &[LONG1.name, LONG2.name]);
+            }
+            _ => panic!("Expected Multiple variant"),
+        }
     }
 }
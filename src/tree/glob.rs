@@ -0,0 +1,73 @@
+//! Shell-style wildcard matching for node names.
+//!
+//! Supports `*` (any run of characters, including none) and `?` (exactly one character).
+//! No allocation; backtracks only to the most recent `*`, so matching is linear in practice.
+
+/// Whether `name` matches the glob `pattern` (whole name, case-sensitive).
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    let mut p = pattern.chars();
+    let mut n = name.chars();
+
+    // Resume point after the last `*`: (pattern after star, name position it absorbed up to)
+    let mut star: Option<(core::str::Chars<'_>, core::str::Chars<'_>)> = None;
+
+    loop {
+        let n_before = n.clone();
+        match (p.next(), n.next()) {
+            (Some('*'), _) => {
+                // Star matches empty for now; remember where to retry
+                n = n_before;
+                star = Some((p.clone(), n.clone()));
+            }
+            (Some('?'), Some(_)) => {}
+            (Some(pc), Some(nc)) if pc == nc => {}
+            (None, None) => return true,
+            _ => {
+                // Mismatch: let the last star absorb one more character
+                let Some((star_p, star_n)) = &mut star else {
+                    return false;
+                };
+                if star_n.next().is_none() {
+                    return false;
+                }
+                p = star_p.clone();
+                n = star_n.clone();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_literal() {
+        assert!(glob_match("status", "status"));
+        assert!(!glob_match("status", "stat"));
+        assert!(!glob_match("stat", "status"));
+        assert!(glob_match("", ""));
+        assert!(!glob_match("", "a"));
+    }
+
+    #[test]
+    fn test_star() {
+        assert!(glob_match("*", ""));
+        assert!(glob_match("*", "anything"));
+        assert!(glob_match("sys*", "system"));
+        assert!(glob_match("*status*", "net_status_all"));
+        assert!(glob_match("*status*", "status"));
+        assert!(glob_match("a*b*c", "aXXbYYc"));
+        assert!(glob_match("a*c", "abcbc"));
+        assert!(!glob_match("sys*", "filesystem"));
+        assert!(!glob_match("a*b", "acbc"));
+    }
+
+    #[test]
+    fn test_question_mark() {
+        assert!(glob_match("le?", "led"));
+        assert!(!glob_match("le?", "le"));
+        assert!(!glob_match("le?", "leds"));
+        assert!(glob_match("?é?", "aéb"));
+    }
+}
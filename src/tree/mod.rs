@@ -4,10 +4,15 @@
 //! All tree structures are const-initializable and live in ROM.
 
 use crate::auth::AccessLevel;
+use crate::error::CliError;
 
 // Sub-modules
 pub mod completion;
+pub mod glob;
 pub mod path;
+pub mod walk;
+
+pub use walk::Walk;
 
 /// Command kind marker (sync or async).
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -20,6 +25,56 @@ pub enum CommandKind {
     Async,
 }
 
+/// Expected type of a positional argument, checked by the shell before dispatch.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ArgType {
+    /// Signed integer (`i64`)
+    Int,
+
+    /// Unsigned integer (`u64`)
+    UInt,
+
+    /// Any string (no check)
+    Str,
+
+    /// `on` or `off` (case-insensitive)
+    OnOff,
+
+    /// Hexadecimal `u64`, optional `0x` prefix
+    Hex,
+}
+
+impl ArgType {
+    /// Whether `arg` is a valid value of this type.
+    pub fn matches(self, arg: &str) -> bool {
+        match self {
+            ArgType::Int => arg.parse::<i64>().is_ok(),
+            ArgType::UInt => arg.parse::<u64>().is_ok(),
+            ArgType::Str => true,
+            ArgType::OnOff => arg.eq_ignore_ascii_case("on") || arg.eq_ignore_ascii_case("off"),
+            ArgType::Hex => {
+                let digits = arg
+                    .strip_prefix("0x")
+                    .or_else(|| arg.strip_prefix("0X"))
+                    .unwrap_or(arg);
+                // from_str_radix accepts a leading '+', a hex argument should not
+                !digits.starts_with('+') && u64::from_str_radix(digits, 16).is_ok()
+            }
+        }
+    }
+
+    /// Description used in `CliError::InvalidArgumentFormat`.
+    pub fn expected(self) -> &'static str {
+        match self {
+            ArgType::Int => "integer",
+            ArgType::UInt => "unsigned integer",
+            ArgType::Str => "string",
+            ArgType::OnOff => "on or off",
+            ArgType::Hex => "hex number",
+        }
+    }
+}
+
 /// Command metadata (const-initializable, no execution logic).
 /// Execution via `CommandHandler` trait enables sync and async commands with const-initialization.
 /// Unique `id` field allows duplicate names across directories.
@@ -46,6 +101,121 @@ pub struct CommandMeta<L: AccessLevel> {
 
     /// Maximum number of arguments
     pub max_args: usize,
+
+    /// Require the current user's password before each execution (`sudo`-style).
+    /// Ignored when the `authentication` feature is disabled.
+    pub requires_reauth: bool,
+
+    /// Refuse to run without a logged-in user (`CliError::AuthRequired`).
+    /// Implied by login when `authentication` is enabled; without it the command is always refused.
+    pub requires_auth: bool,
+
+    /// Omit from `ls` and tab completion (still executable when typed explicitly).
+    pub hidden: bool,
+
+    /// Role (`RoleSet` bit) required in addition to `access_level`.
+    /// Without the `roles` feature no user holds a role, so the command is refused to everyone.
+    pub required_role: Option<u8>,
+
+    /// Types of the leading arguments, checked before dispatch (empty = no checks).
+    /// Arguments past the end of the spec are not checked.
+    pub arg_spec: &'static [ArgType],
+
+    /// Minimum time between runs in milliseconds (0 = unlimited), measured with the clock
+    /// set via `Shell::set_clock()`; ignored without a clock.
+    pub min_interval_ms: u32,
+}
+
+impl<L: AccessLevel> CommandMeta<L> {
+    /// Command with no description, no arguments and every option off.
+    /// Chain the `const` setters below to fill in the rest.
+    pub const fn new(
+        id: &'static str,
+        name: &'static str,
+        access_level: L,
+        kind: CommandKind,
+    ) -> Self {
+        Self {
+            id,
+            name,
+            description: "",
+            access_level,
+            kind,
+            min_args: 0,
+            max_args: 0,
+            requires_reauth: false,
+            requires_auth: false,
+            hidden: false,
+            required_role: None,
+            arg_spec: &[],
+            min_interval_ms: 0,
+        }
+    }
+
+    /// Set the description shown by `ls`.
+    pub const fn with_description(mut self, description: &'static str) -> Self {
+        self.description = description;
+        self
+    }
+
+    /// Set the accepted argument count range.
+    pub const fn with_args(mut self, min_args: usize, max_args: usize) -> Self {
+        self.min_args = min_args;
+        self.max_args = max_args;
+        self
+    }
+
+    /// Require the user's password before each execution (see `requires_reauth`).
+    pub const fn requiring_reauth(mut self) -> Self {
+        self.requires_reauth = true;
+        self
+    }
+
+    /// Omit from `ls` and tab completion (see `hidden`).
+    pub const fn hidden(mut self) -> Self {
+        self.hidden = true;
+        self
+    }
+
+    /// Require role bit `role` in addition to `access_level` (see `required_role`).
+    pub const fn with_required_role(mut self, role: u8) -> Self {
+        self.required_role = Some(role);
+        self
+    }
+
+    /// Check leading arguments against `spec` before dispatch (see `arg_spec`).
+    pub const fn with_arg_spec(mut self, spec: &'static [ArgType]) -> Self {
+        self.arg_spec = spec;
+        self
+    }
+
+    /// Set the minimum time between runs in milliseconds (see `min_interval_ms`).
+    pub const fn with_min_interval_ms(mut self, min_interval_ms: u32) -> Self {
+        self.min_interval_ms = min_interval_ms;
+        self
+    }
+
+    /// Refuse to run without a logged-in user (see `requires_auth`).
+    pub const fn requiring_auth(mut self) -> Self {
+        self.requires_auth = true;
+        self
+    }
+
+    /// Check `args` against `arg_spec`, reporting the first mismatch.
+    pub fn check_args(&self, args: &[&str]) -> Result<(), CliError> {
+        for (arg_index, (arg, ty)) in args.iter().zip(self.arg_spec).enumerate() {
+            if !ty.matches(arg) {
+                let mut expected = heapless::String::new();
+                // Every ArgType::expected() string fits in 32 bytes
+                let _ = expected.push_str(ty.expected());
+                return Err(CliError::InvalidArgumentFormat {
+                    arg_index,
+                    expected,
+                });
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Directory node containing child nodes (const-initializable, stored in ROM).
@@ -60,6 +230,12 @@ pub struct Directory<L: AccessLevel> {
 
     /// Minimum access level required to access this directory
     pub access_level: L,
+
+    /// Omit from `ls` and tab completion (still navigable when typed explicitly).
+    pub hidden: bool,
+
+    /// Id of a command in this directory run on entering it (e.g. to show a menu).
+    pub default_command: Option<&'static str>,
 }
 
 /// Tree node (command or directory).
@@ -100,13 +276,316 @@ impl<L: AccessLevel> Node<L> {
             Node::Directory(dir) => dir.access_level,
         }
     }
+
+    /// Check if node is hidden from listings and completion.
+    pub fn is_hidden(&self) -> bool {
+        match self {
+            Node::Command(cmd) => cmd.hidden,
+            Node::Directory(dir) => dir.hidden,
+        }
+    }
+
+    /// Get role required by a command (`None` for directories).
+    pub fn required_role(&self) -> Option<u8> {
+        match self {
+            Node::Command(cmd) => cmd.required_role,
+            Node::Directory(_) => None,
+        }
+    }
+}
+
+/// Borrowed tree node.
+///
+/// Like `Node`, but borrows for any lifetime, so the root `Directory` is addressable too.
+#[derive(Debug, Clone, Copy)]
+pub enum NodeRef<'t, L: AccessLevel> {
+    /// Command node (metadata only)
+    Command(&'t CommandMeta<L>),
+
+    /// Directory node (including root)
+    Directory(&'t Directory<L>),
+}
+
+impl<'t, L: AccessLevel> NodeRef<'t, L> {
+    /// Check if this is a command node.
+    pub fn is_command(&self) -> bool {
+        matches!(self, NodeRef::Command(_))
+    }
+
+    /// Check if this is a directory node.
+    pub fn is_directory(&self) -> bool {
+        matches!(self, NodeRef::Directory(_))
+    }
+
+    /// Get node name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            NodeRef::Command(cmd) => cmd.name,
+            NodeRef::Directory(dir) => dir.name,
+        }
+    }
+
+    /// Get node access level.
+    pub fn access_level(&self) -> L {
+        match self {
+            NodeRef::Command(cmd) => cmd.access_level,
+            NodeRef::Directory(dir) => dir.access_level,
+        }
+    }
+
+    /// Check if node is hidden from listings and completion.
+    pub fn is_hidden(&self) -> bool {
+        match self {
+            NodeRef::Command(cmd) => cmd.hidden,
+            NodeRef::Directory(dir) => dir.hidden,
+        }
+    }
+
+    /// Get role required by a command (`None` for directories).
+    pub fn required_role(&self) -> Option<u8> {
+        match self {
+            NodeRef::Command(cmd) => cmd.required_role,
+            NodeRef::Directory(_) => None,
+        }
+    }
+}
+
+impl<'t, L: AccessLevel> From<&'t Node<L>> for NodeRef<'t, L> {
+    fn from(node: &'t Node<L>) -> Self {
+        match node {
+            Node::Command(cmd) => NodeRef::Command(cmd),
+            Node::Directory(dir) => NodeRef::Directory(dir),
+        }
+    }
+}
+
+impl<'t, L: AccessLevel> From<&'t Directory<L>> for NodeRef<'t, L> {
+    fn from(dir: &'t Directory<L>) -> Self {
+        NodeRef::Directory(dir)
+    }
 }
 
 impl<L: AccessLevel> Directory<L> {
+    /// Directory that is listed and has no default command.
+    pub const fn new(name: &'static str, children: &'static [Node<L>], access_level: L) -> Self {
+        Self {
+            name,
+            children,
+            access_level,
+            hidden: false,
+            default_command: None,
+        }
+    }
+
+    /// Omit from `ls` and tab completion (see `hidden`).
+    pub const fn hidden(mut self) -> Self {
+        self.hidden = true;
+        self
+    }
+
+    /// Run the command with this id on entering the directory (see `default_command`).
+    pub const fn with_default_command(mut self, id: &'static str) -> Self {
+        self.default_command = Some(id);
+        self
+    }
+
     /// Find child node by name (no access control, returns `None` if not found).
     pub fn find_child(&self, name: &str) -> Option<&Node<L>> {
         self.children.iter().find(|child| child.name() == name)
     }
+
+    /// Command named by `default_command`, if set and present among the children.
+    pub fn default_command_meta(&self) -> Option<&'static CommandMeta<L>> {
+        let id = self.default_command?;
+        self.children.iter().find_map(|child| match child {
+            Node::Command(cmd) if cmd.id == id => Some(*cmd),
+            _ => None,
+        })
+    }
+
+    /// Depth-first search of this subtree for the command with `id` (no access control).
+    pub fn find_by_id(&self, id: &str) -> Option<&'static CommandMeta<L>> {
+        self.children.iter().find_map(|child| match child {
+            Node::Command(cmd) if cmd.id == id => Some(*cmd),
+            Node::Command(_) => None,
+            Node::Directory(dir) => dir.find_by_id(id),
+        })
+    }
+
+    /// Depth-first (pre-order) iterator over all descendants.
+    ///
+    /// `MAX_DEPTH` bounds directory nesting, counting this directory (see `Walk`).
+    pub fn walk<const MAX_DEPTH: usize>(&self) -> Walk<'_, L, MAX_DEPTH> {
+        Walk::new(self)
+    }
+}
+
+// ============================================================================
+// Compile-time validation
+// ============================================================================
+
+/// Validate tree invariants: unique command ids, `min_args <= max_args`, `required_role < 32`,
+/// `arg_spec` no longer than `max_args`, and that each `default_command` names a child command accepting zero arguments.
+///
+/// `const fn` so mistakes fail the build; see `validate_tree!`. Runs in O(n²) over commands,
+/// which is irrelevant at compile time.
+pub const fn validate_tree<L: AccessLevel>(root: &Directory<L>) -> Result<(), &'static str> {
+    validate_dir(root, root)
+}
+
+/// Check every command under `dir` against the whole tree rooted at `root`.
+const fn validate_dir<L: AccessLevel>(
+    root: &Directory<L>,
+    dir: &Directory<L>,
+) -> Result<(), &'static str> {
+    if let Some(id) = dir.default_command
+        && !has_default_child(dir, id)
+    {
+        return Err("default_command is not a zero-argument command in its directory");
+    }
+
+    let mut i = 0;
+    while i < dir.children.len() {
+        match &dir.children[i] {
+            Node::Command(cmd) => {
+                if cmd.min_args > cmd.max_args {
+                    return Err("command has min_args > max_args");
+                }
+                if count_id(root, cmd.id) > 1 {
+                    return Err("duplicate command id");
+                }
+                if let Some(role) = cmd.required_role
+                    && role >= 32
+                {
+                    return Err("required_role exceeds the 32 roles of a RoleSet");
+                }
+                if cmd.arg_spec.len() > cmd.max_args {
+                    return Err("arg_spec is longer than max_args");
+                }
+            }
+            Node::Directory(sub) => {
+                if let Err(e) = validate_dir(root, sub) {
+                    return Err(e);
+                }
+            }
+        }
+        i += 1;
+    }
+    Ok(())
+}
+
+/// Validate command ids: each a non-empty ASCII identifier (`[A-Za-z_][A-Za-z0-9_]*`, e.g.
+/// `system_reboot`) and unique across the tree.
+///
+/// Opt-in and stricter than `validate_tree()` (which accepts any unique id, such as `led-on`):
+/// `const _: () = assert!(nut_shell::tree::validate_ids(&ROOT).is_ok());`
+pub const fn validate_ids<L: AccessLevel>(root: &Directory<L>) -> Result<(), &'static str> {
+    validate_ids_in(root, root)
+}
+
+/// Check every command id under `dir` against the whole tree rooted at `root`.
+const fn validate_ids_in<L: AccessLevel>(
+    root: &Directory<L>,
+    dir: &Directory<L>,
+) -> Result<(), &'static str> {
+    let mut i = 0;
+    while i < dir.children.len() {
+        match &dir.children[i] {
+            Node::Command(cmd) => {
+                if !is_identifier(cmd.id) {
+                    return Err("command id is not an ASCII identifier");
+                }
+                if count_id(root, cmd.id) > 1 {
+                    return Err("duplicate command id");
+                }
+            }
+            Node::Directory(sub) => {
+                if let Err(e) = validate_ids_in(root, sub) {
+                    return Err(e);
+                }
+            }
+        }
+        i += 1;
+    }
+    Ok(())
+}
+
+/// Whether `s` is a non-empty ASCII identifier (letter or `_`, then letters, digits, `_`).
+const fn is_identifier(s: &str) -> bool {
+    let bytes = s.as_bytes();
+    if bytes.is_empty() || bytes[0].is_ascii_digit() {
+        return false;
+    }
+    let mut i = 0;
+    while i < bytes.len() {
+        if !(bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Whether `dir` directly contains a command `id` callable without arguments.
+const fn has_default_child<L: AccessLevel>(dir: &Directory<L>, id: &str) -> bool {
+    let mut i = 0;
+    while i < dir.children.len() {
+        if let Node::Command(cmd) = &dir.children[i]
+            && str_eq(cmd.id, id)
+        {
+            return cmd.min_args == 0;
+        }
+        i += 1;
+    }
+    false
+}
+
+/// Count commands with `id` under `dir` (recursive).
+const fn count_id<L: AccessLevel>(dir: &Directory<L>, id: &str) -> usize {
+    let mut count = 0;
+    let mut i = 0;
+    while i < dir.children.len() {
+        match &dir.children[i] {
+            Node::Command(cmd) => {
+                if str_eq(cmd.id, id) {
+                    count += 1;
+                }
+            }
+            Node::Directory(sub) => count += count_id(sub, id),
+        }
+        i += 1;
+    }
+    count
+}
+
+/// Const string equality (`PartialEq` is not const).
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Fail the build if a command tree is invalid (see `tree::validate_tree`).
+///
+/// Place at module level next to the tree: `nut_shell::validate_tree!(ROOT);`
+#[macro_export]
+macro_rules! validate_tree {
+    ($root:expr) => {
+        const _: () = {
+            if let Err(msg) = $crate::tree::validate_tree(&$root) {
+                panic!("{}", msg);
+            }
+        };
+    };
 }
 
 #[cfg(test)]
@@ -136,6 +615,10 @@ mod tests {
                 Self::User => "User",
             }
         }
+
+        fn rank(&self) -> u8 {
+            *self as u8
+        }
     }
 
     #[test]
@@ -148,19 +631,198 @@ mod tests {
 
     #[test]
     fn test_node_type_checking() {
-        const CMD: CommandMeta<TestAccessLevel> = CommandMeta {
-            id: "test",
-            name: "test",
-            description: "Test command",
-            access_level: TestAccessLevel::User,
-            kind: CommandKind::Sync,
-            min_args: 0,
-            max_args: 0,
-        };
+        const CMD: CommandMeta<TestAccessLevel> =
+            CommandMeta::new("test", "test", TestAccessLevel::User, CommandKind::Sync)
+                .with_description("Test command");
 
         let node = Node::Command(&CMD);
         assert!(node.is_command());
         assert!(!node.is_directory());
         assert_eq!(node.name(), "test");
     }
+
+    #[test]
+    fn test_node_ref_covers_root() {
+        const CMD: CommandMeta<TestAccessLevel> =
+            CommandMeta::new("test", "test", TestAccessLevel::User, CommandKind::Sync)
+                .with_description("Test command");
+        const ROOT: Directory<TestAccessLevel> =
+            Directory::new("/", &[Node::Command(&CMD)], TestAccessLevel::Guest);
+
+        let root = NodeRef::from(&ROOT);
+        assert!(root.is_directory());
+        assert_eq!(root.name(), "/");
+        assert_eq!(root.access_level(), TestAccessLevel::Guest);
+
+        let child = NodeRef::from(&ROOT.children[0]);
+        assert!(child.is_command());
+        assert_eq!(child.name(), "test");
+    }
+
+    #[test]
+    fn test_validate_tree() {
+        const A: CommandMeta<TestAccessLevel> =
+            CommandMeta::new("a", "a", TestAccessLevel::Guest, CommandKind::Sync).with_args(0, 1);
+        const B: CommandMeta<TestAccessLevel> =
+            CommandMeta::new("b", "b", TestAccessLevel::Guest, CommandKind::Sync).with_args(1, 1);
+        const A_AGAIN: CommandMeta<TestAccessLevel> =
+            CommandMeta::new("a", "a", TestAccessLevel::Guest, CommandKind::Sync);
+        const BAD_ARGS: CommandMeta<TestAccessLevel> =
+            CommandMeta::new("c", "c", TestAccessLevel::Guest, CommandKind::Sync).with_args(2, 1);
+
+        const SUB: Directory<TestAccessLevel> =
+            Directory::new("sub", &[Node::Command(&B)], TestAccessLevel::Guest);
+        const VALID: Directory<TestAccessLevel> = Directory::new(
+            "/",
+            &[Node::Command(&A), Node::Directory(&SUB)],
+            TestAccessLevel::Guest,
+        );
+        const _: () = assert!(validate_tree(&VALID).is_ok());
+
+        // Duplicate across directories
+        const DUP_SUB: Directory<TestAccessLevel> =
+            Directory::new("sub", &[Node::Command(&A_AGAIN)], TestAccessLevel::Guest);
+        const DUP: Directory<TestAccessLevel> = Directory::new(
+            "/",
+            &[Node::Command(&A), Node::Directory(&DUP_SUB)],
+            TestAccessLevel::Guest,
+        );
+        assert_eq!(validate_tree(&DUP), Err("duplicate command id"));
+
+        const BAD: Directory<TestAccessLevel> =
+            Directory::new("/", &[Node::Command(&BAD_ARGS)], TestAccessLevel::Guest);
+        assert_eq!(validate_tree(&BAD), Err("command has min_args > max_args"));
+
+        // Default command must be a zero-argument child
+        const MENU_ERR: &str = "default_command is not a zero-argument command in its directory";
+        const MENU_OK: Directory<TestAccessLevel> =
+            Directory::new("/", &[Node::Command(&A)], TestAccessLevel::Guest)
+                .with_default_command("a");
+        const MENU_ARGS: Directory<TestAccessLevel> =
+            Directory::new("sub", &[Node::Command(&B)], TestAccessLevel::Guest)
+                .with_default_command("b");
+        const MENU_MISSING: Directory<TestAccessLevel> =
+            Directory::new("sub", &[Node::Directory(&SUB)], TestAccessLevel::Guest)
+                .with_default_command("b");
+        assert_eq!(validate_tree(&MENU_OK), Ok(()));
+        assert_eq!(MENU_OK.default_command_meta().map(|c| c.id), Some("a"));
+        assert_eq!(validate_tree(&MENU_ARGS), Err(MENU_ERR));
+        assert_eq!(validate_tree(&MENU_MISSING), Err(MENU_ERR));
+        assert!(MENU_MISSING.default_command_meta().is_none());
+
+        // Lookup by id searches nested directories
+        assert_eq!(VALID.find_by_id("b").map(|c| c.min_args), Some(1));
+        assert_eq!(VALID.find_by_id("a").map(|c| c.name), Some("a"));
+        assert!(VALID.find_by_id("c").is_none());
+        assert!(SUB.find_by_id("a").is_none());
+
+        // Roles are bits of a u32
+        const ROLE_OK: CommandMeta<TestAccessLevel> =
+            CommandMeta::new("r31", "r31", TestAccessLevel::Guest, CommandKind::Sync)
+                .with_required_role(31);
+        const ROLE_BAD: CommandMeta<TestAccessLevel> =
+            CommandMeta::new("r32", "r32", TestAccessLevel::Guest, CommandKind::Sync)
+                .with_required_role(32);
+        const ROLES: Directory<TestAccessLevel> = Directory::new(
+            "/",
+            &[Node::Command(&ROLE_OK), Node::Command(&ROLE_BAD)],
+            TestAccessLevel::Guest,
+        );
+        assert_eq!(
+            validate_tree(&ROLES),
+            Err("required_role exceeds the 32 roles of a RoleSet")
+        );
+    }
+
+    #[test]
+    fn test_validate_tree_arg_spec_length() {
+        const SPEC_BAD: CommandMeta<TestAccessLevel> =
+            CommandMeta::new("spec", "spec", TestAccessLevel::Guest, CommandKind::Sync)
+                .with_args(0, 1)
+                .with_arg_spec(&[ArgType::Int, ArgType::Int]);
+        const SPEC: Directory<TestAccessLevel> =
+            Directory::new("/", &[Node::Command(&SPEC_BAD)], TestAccessLevel::Guest);
+        assert_eq!(
+            validate_tree(&SPEC),
+            Err("arg_spec is longer than max_args")
+        );
+    }
+
+    #[test]
+    fn test_validate_ids() {
+        const fn root(children: &'static [Node<TestAccessLevel>]) -> Directory<TestAccessLevel> {
+            Directory::new("/", children, TestAccessLevel::Guest)
+        }
+
+        const REBOOT: CommandMeta<TestAccessLevel> = CommandMeta::new(
+            "system_reboot",
+            "system_reboot",
+            TestAccessLevel::Guest,
+            CommandKind::Sync,
+        );
+        const LED: CommandMeta<TestAccessLevel> =
+            CommandMeta::new("_led2", "_led2", TestAccessLevel::Guest, CommandKind::Sync);
+        const OK: Directory<TestAccessLevel> = root(&[Node::Command(&REBOOT), Node::Command(&LED)]);
+        const _: () = assert!(validate_ids(&OK).is_ok());
+
+        const ID_ERR: &str = "command id is not an ASCII identifier";
+        const EMPTY: CommandMeta<TestAccessLevel> =
+            CommandMeta::new("", "", TestAccessLevel::Guest, CommandKind::Sync);
+        const DASHED: CommandMeta<TestAccessLevel> = CommandMeta::new(
+            "led-on",
+            "led-on",
+            TestAccessLevel::Guest,
+            CommandKind::Sync,
+        );
+        const DIGIT: CommandMeta<TestAccessLevel> =
+            CommandMeta::new("2fa", "2fa", TestAccessLevel::Guest, CommandKind::Sync);
+        const UNICODE: CommandMeta<TestAccessLevel> =
+            CommandMeta::new("grüß", "grüß", TestAccessLevel::Guest, CommandKind::Sync);
+        assert_eq!(validate_ids(&root(&[Node::Command(&EMPTY)])), Err(ID_ERR));
+        assert_eq!(validate_ids(&root(&[Node::Command(&DASHED)])), Err(ID_ERR));
+        assert_eq!(validate_ids(&root(&[Node::Command(&DIGIT)])), Err(ID_ERR));
+        assert_eq!(validate_ids(&root(&[Node::Command(&UNICODE)])), Err(ID_ERR));
+
+        // Unique per tree, as in `validate_tree()`
+        const SUB: Directory<TestAccessLevel> =
+            Directory::new("sub", &[Node::Command(&REBOOT)], TestAccessLevel::Guest);
+        const DUP: Directory<TestAccessLevel> =
+            root(&[Node::Command(&REBOOT), Node::Directory(&SUB)]);
+        assert_eq!(validate_ids(&DUP), Err("duplicate command id"));
+    }
+
+    #[test]
+    fn test_arg_type_matches() {
+        assert!(ArgType::Int.matches("-42"));
+        assert!(!ArgType::Int.matches("4x"));
+        assert!(ArgType::UInt.matches("42"));
+        assert!(!ArgType::UInt.matches("-1"));
+        assert!(ArgType::Str.matches(""));
+        assert!(ArgType::OnOff.matches("ON"));
+        assert!(!ArgType::OnOff.matches("1"));
+        assert!(ArgType::Hex.matches("0xFF"));
+        assert!(ArgType::Hex.matches("beef"));
+        assert!(!ArgType::Hex.matches("0x"));
+        assert!(!ArgType::Hex.matches("+f"));
+    }
+
+    #[test]
+    fn test_check_args_reports_first_mismatch() {
+        const SPEC: CommandMeta<TestAccessLevel> =
+            CommandMeta::new("spec", "spec", TestAccessLevel::Guest, CommandKind::Sync)
+                .with_args(0, 3)
+                .with_arg_spec(&[ArgType::UInt, ArgType::OnOff]);
+        assert_eq!(SPEC.check_args(&["3", "off", "anything"]), Ok(()));
+        assert_eq!(SPEC.check_args(&["3"]), Ok(()));
+
+        let Err(CliError::InvalidArgumentFormat {
+            arg_index,
+            expected,
+        }) = SPEC.check_args(&["3", "maybe"])
+        else {
+            panic!("expected InvalidArgumentFormat");
+        };
+        assert_eq!(arg_index, 1);
+        assert_eq!(expected.as_str(), "on or off");
+    }
 }
@@ -1,8 +1,15 @@
 //! Path parsing and navigation.
 //!
 //! Unix-style path resolution with `.` and `..` support.
+//! Tree-walking functions are shared by `Shell` and custom front-ends.
 
+use crate::auth::{AccessLevel, User};
+use crate::config::{BufferSizes, ShellConfig, VecBuffer, capacity};
 use crate::error::CliError;
+use crate::tree::{Directory, Node, NodeRef};
+
+/// Navigation stack of child indices from root (one entry per directory level), sized by config `C`.
+pub type PathStack<C> = <<C as ShellConfig>::Buffers as BufferSizes>::Path;
 
 /// Unix-style path parser and representation.
 ///
@@ -95,6 +102,161 @@ impl<'a, const MAX_DEPTH: usize> Path<'a, MAX_DEPTH> {
     pub fn segment_count(&self) -> usize {
         self.segments.len()
     }
+
+    /// Collapse `.` and `..` against `start` (the current directory for relative paths).
+    ///
+    /// Returns the directory stack (`P`, e.g. `PathStack<C>`) the remaining names are relative
+    /// to, and those names.
+    /// `..` cancels the preceding name lexically (`a/../b` = `b`, even if `a` doesn't exist),
+    /// otherwise climbs from `start`; extra `..` at root stay at root.
+    pub fn canonicalize<P: VecBuffer<usize>>(
+        &self,
+        start: &[usize],
+    ) -> Result<(P, heapless::Vec<&'a str, MAX_DEPTH>), CliError> {
+        let mut base = if self.is_absolute {
+            P::default()
+        } else {
+            P::from_slice(start).map_err(|_| CliError::PathTooDeep)?
+        };
+        let mut names = heapless::Vec::new();
+
+        for &segment in &self.segments {
+            match segment {
+                "." => {}
+                ".." => {
+                    if names.pop().is_none() {
+                        base.pop();
+                    }
+                }
+                // At most as many names as segments - cannot overflow
+                name => names.push(name).map_err(|_| CliError::PathTooDeep)?,
+            }
+        }
+
+        Ok((base, names))
+    }
+}
+
+/// Resolve a path string against `root`, starting at `current_path` for relative paths.
+///
+/// Returns (node, path_stack) where path_stack is the navigation path
+/// (empty when path resolves to root directory).
+/// Nodes above `user`'s access level (or needing a role it lacks) are treated as non-existent
+/// (or `PermissionDenied` with `C::REVEAL_ACCESS_DENIED`); `None` user skips access checks.
+pub fn resolve<'t, C: ShellConfig, L: AccessLevel>(
+    root: &'t Directory<L>,
+    current_path: &[usize],
+    path_str: &str,
+    user: Option<&User<L>>,
+) -> Result<(NodeRef<'t, L>, PathStack<C>), CliError> {
+    // Collapse `.`/`..` first; empty input names the current directory
+    let (mut working_path, segments) = if path_str.is_empty() {
+        (
+            PathStack::<C>::from_slice(current_path).map_err(|_| CliError::PathTooDeep)?,
+            heapless::Vec::new(),
+        )
+    } else {
+        Path::<{ capacity::PATH_DEPTH }>::parse(path_str)?.canonicalize(current_path)?
+    };
+
+    // Navigate through segments
+    for (seg_idx, segment) in segments.iter().enumerate() {
+        let is_last_segment = seg_idx == segments.len() - 1;
+
+        // Find child with this name
+        let current_dir = dir_at(root, &working_path)?;
+        let mut found = false;
+
+        for (index, child) in current_dir.children.iter().enumerate() {
+            // Check access control (role mismatches are never revealed)
+            let node_level = child.access_level();
+
+            if let Some(user) = user
+                && !user.can_access(node_level, child.required_role())
+            {
+                if child.name() == *segment {
+                    trace!("resolve: access denied for {=str}", *segment);
+                    if C::REVEAL_ACCESS_DENIED && user.access_level < node_level {
+                        return Err(CliError::PermissionDenied {
+                            required: node_level.as_str(),
+                        });
+                    }
+                }
+                continue; // User lacks access, skip this node
+            }
+
+            if child.name() == *segment {
+                // Found it!
+                trace!("resolve: segment {=str} -> index {=usize}", *segment, index);
+                if child.is_directory() {
+                    // Navigate into directory
+                    if working_path.len() >= C::MAX_PATH_DEPTH {
+                        return Err(CliError::PathTooDeep);
+                    }
+                    working_path
+                        .push(index)
+                        .map_err(|_| CliError::PathTooDeep)?;
+                } else {
+                    // It's a command - can only return if this is the last segment
+                    if is_last_segment {
+                        return Ok((child.into(), working_path));
+                    } else {
+                        // Trying to navigate through a command
+                        return Err(CliError::NotADirectory { name: child.name() });
+                    }
+                }
+                found = true;
+                break;
+            }
+        }
+
+        if !found {
+            trace!("resolve: segment {=str} not found", *segment);
+            return Err(CliError::CommandNotFound);
+        }
+    }
+
+    // Path resolved to a directory (root when path is empty)
+    let dir_node = node_at(root, &working_path)?;
+    Ok((dir_node, working_path))
+}
+
+/// Get directory at index path (no access control).
+///
+/// Returns `InvalidPath` if an index is out of range or names a command.
+pub fn dir_at<'t, L: AccessLevel>(
+    root: &'t Directory<L>,
+    path: &[usize],
+) -> Result<&'t Directory<L>, CliError> {
+    let mut current = root;
+
+    for &index in path.iter() {
+        match current.children.get(index) {
+            Some(Node::Directory(dir)) => current = dir,
+            Some(Node::Command(_)) | None => return Err(CliError::InvalidPath),
+        }
+    }
+
+    Ok(current)
+}
+
+/// Get node at index path (no access control).
+///
+/// The empty path addresses `root` itself.
+pub fn node_at<'t, L: AccessLevel>(
+    root: &'t Directory<L>,
+    path: &[usize],
+) -> Result<NodeRef<'t, L>, CliError> {
+    let Some((&last_index, parent_path)) = path.split_last() else {
+        return Ok(NodeRef::Directory(root));
+    };
+    let parent_dir = dir_at(root, parent_path)?;
+
+    parent_dir
+        .children
+        .get(last_index)
+        .map(NodeRef::from)
+        .ok_or(CliError::InvalidPath)
 }
 
 #[cfg(test)]
@@ -246,4 +408,145 @@ mod tests {
         let result = MinimalPath::parse("a/b/c/d/e/f/g/h");
         assert_eq!(result, Err(CliError::PathTooDeep));
     }
+
+    // ------------------------------------------------------------------------
+    // Tree resolution
+    // ------------------------------------------------------------------------
+
+    use crate::tree::{CommandKind, CommandMeta};
+
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+    enum Level {
+        Guest = 0,
+        Admin = 1,
+    }
+
+    impl AccessLevel for Level {
+        fn from_str(s: &str) -> Option<Self> {
+            match s {
+                "Guest" => Some(Self::Guest),
+                "Admin" => Some(Self::Admin),
+                _ => None,
+            }
+        }
+
+        fn as_str(&self) -> &'static str {
+            match self {
+                Self::Guest => "Guest",
+                Self::Admin => "Admin",
+            }
+        }
+
+        fn rank(&self) -> u8 {
+            *self as u8
+        }
+    }
+
+    const STATUS: CommandMeta<Level> =
+        CommandMeta::new("status", "status", Level::Guest, CommandKind::Sync);
+    const REBOOT: CommandMeta<Level> =
+        CommandMeta::new("reboot", "reboot", Level::Admin, CommandKind::Sync);
+
+    const SYSTEM: Directory<Level> = Directory::new(
+        "system",
+        &[Node::Command(&STATUS), Node::Command(&REBOOT)],
+        Level::Guest,
+    );
+
+    const ROOT: Directory<Level> = Directory::new("/", &[Node::Directory(&SYSTEM)], Level::Guest);
+
+    fn guest() -> User<Level> {
+        #[cfg(feature = "authentication")]
+        return User::new("guest", Level::Guest, [0; 32], [0; 16]).unwrap();
+        #[cfg(not(feature = "authentication"))]
+        return User::new("guest", Level::Guest).unwrap();
+    }
+
+    #[test]
+    fn test_resolve_absolute_and_relative() {
+        let (node, stack) = resolve::<DefaultConfig, _>(&ROOT, &[], "system/status", None).unwrap();
+        assert_eq!(node.name(), "status");
+        assert_eq!(stack.as_slice(), &[0]);
+
+        // Relative from inside system/
+        let (node, _) = resolve::<DefaultConfig, _>(&ROOT, &[0], "reboot", None).unwrap();
+        assert_eq!(node.name(), "reboot");
+
+        // Parent navigation back to root
+        let (node, stack) = resolve::<DefaultConfig, _>(&ROOT, &[0], "..", None).unwrap();
+        assert_eq!(node.name(), "/");
+        assert!(stack.is_empty());
+
+        let (node, stack) = resolve::<DefaultConfig, _>(&ROOT, &[0], "/system", None).unwrap();
+        assert_eq!(node.name(), "system");
+        assert_eq!(stack.as_slice(), &[0]);
+    }
+
+    #[test]
+    fn test_resolve_errors() {
+        assert_eq!(
+            resolve::<DefaultConfig, _>(&ROOT, &[], "missing", None).unwrap_err(),
+            CliError::CommandNotFound
+        );
+        assert_eq!(
+            resolve::<DefaultConfig, _>(&ROOT, &[], "system/status/extra", None).unwrap_err(),
+            CliError::NotADirectory { name: "status" }
+        );
+    }
+
+    #[test]
+    fn test_resolve_filters_by_access() {
+        let user = guest();
+        assert!(resolve::<DefaultConfig, _>(&ROOT, &[], "system/status", Some(&user)).is_ok());
+        assert_eq!(
+            resolve::<DefaultConfig, _>(&ROOT, &[], "system/reboot", Some(&user)).unwrap_err(),
+            CliError::CommandNotFound
+        );
+    }
+
+    #[test]
+    fn test_canonicalize() {
+        fn check(input: &str, start: &[usize], base: &[usize], names: &[&str]) {
+            let (b, n): (heapless::Vec<usize, 8>, _) =
+                TestPath::parse(input).unwrap().canonicalize(start).unwrap();
+            assert_eq!((b.as_slice(), n.as_slice()), (base, names), "{}", input);
+        }
+
+        check("a/./b", &[1], &[1], &["a", "b"]);
+        check("a/../b", &[1], &[1], &["b"]);
+        check("../b", &[1, 2], &[1], &["b"]);
+        check("/a/..", &[1, 2], &[], &[]);
+
+        // Extra `..` stay at root
+        check("../../../..", &[1], &[], &[]);
+        check("/../a", &[1], &[], &["a"]);
+    }
+
+    #[test]
+    fn test_resolve_collapses_dots() {
+        // `missing/..` cancels lexically, extra `..` clamp at root
+        let (node, stack) =
+            resolve::<DefaultConfig, _>(&ROOT, &[0], "missing/../status", None).unwrap();
+        assert_eq!(node.name(), "status");
+        assert_eq!(stack.as_slice(), &[0]);
+
+        let (node, stack) =
+            resolve::<DefaultConfig, _>(&ROOT, &[0], "../../../system/./reboot", None).unwrap();
+        assert_eq!(node.name(), "reboot");
+        assert_eq!(stack.as_slice(), &[0]);
+
+        let (node, _) = resolve::<DefaultConfig, _>(&ROOT, &[0], "", None).unwrap();
+        assert_eq!(node.name(), "system");
+    }
+
+    #[test]
+    fn test_dir_and_node_at() {
+        assert_eq!(dir_at(&ROOT, &[]).unwrap().name, "/");
+        assert_eq!(dir_at(&ROOT, &[0]).unwrap().name, "system");
+        assert_eq!(dir_at(&ROOT, &[0, 0]).unwrap_err(), CliError::InvalidPath);
+
+        assert_eq!(node_at(&ROOT, &[0, 1]).unwrap().name(), "reboot");
+        assert_eq!(node_at(&ROOT, &[]).unwrap().name(), "/");
+        assert_eq!(node_at(&ROOT, &[5]).unwrap_err(), CliError::InvalidPath);
+    }
 }
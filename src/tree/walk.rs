@@ -0,0 +1,167 @@
+//! Read-only tree traversal.
+//!
+//! Non-recursive depth-first walk for external tooling (docs, UI generation, tests).
+
+use crate::auth::AccessLevel;
+use crate::tree::{Directory, Node};
+
+/// Depth-first (pre-order) iterator over a command tree.
+///
+/// Yields `(depth, node, path_segments)`: depth 0 is a child of the walked directory and
+/// `path_segments` are the names from there down to (and including) the node.
+/// Hidden and access-restricted nodes are included; filter with `Node::is_hidden()` and
+/// `Node::access_level()` as needed.
+///
+/// Uses a `heapless` stack of `MAX_DEPTH` directories (including the starting one).
+/// Directories nested deeper are yielded but not descended into; check `is_truncated()`.
+#[derive(Debug)]
+pub struct Walk<'a, L: AccessLevel, const MAX_DEPTH: usize> {
+    /// Directories being traversed with next child index
+    stack: heapless::Vec<(&'a Directory<L>, usize), MAX_DEPTH>,
+
+    /// Names of stacked directories (excluding the starting one)
+    path: heapless::Vec<&'static str, MAX_DEPTH>,
+
+    /// Set when a directory was too deep to descend into
+    truncated: bool,
+}
+
+impl<'a, L: AccessLevel, const MAX_DEPTH: usize> Walk<'a, L, MAX_DEPTH> {
+    /// Start walk at `root` (not yielded itself).
+    pub fn new(root: &'a Directory<L>) -> Self {
+        let mut stack = heapless::Vec::new();
+        let truncated = stack.push((root, 0)).is_err();
+
+        Self {
+            stack,
+            path: heapless::Vec::new(),
+            truncated,
+        }
+    }
+
+    /// Returns true if some directory exceeded `MAX_DEPTH` and its children were skipped.
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+}
+
+impl<'a, L: AccessLevel, const MAX_DEPTH: usize> Iterator for Walk<'a, L, MAX_DEPTH> {
+    type Item = (usize, &'a Node<L>, heapless::Vec<&'static str, MAX_DEPTH>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (dir, next_index) = self.stack.last_mut()?;
+            let dir: &'a Directory<L> = dir;
+
+            let Some(node) = dir.children.get(*next_index) else {
+                // Directory exhausted - back up to parent
+                self.stack.pop();
+                self.path.pop();
+                continue;
+            };
+            *next_index += 1;
+
+            // path.len() == stack.len() - 1 < MAX_DEPTH, so the node's own name fits
+            let depth = self.path.len();
+            let mut segments = self.path.clone();
+            let _ = segments.push(node.name());
+
+            if let Node::Directory(sub) = node {
+                if self.stack.push((sub, 0)).is_ok() {
+                    let _ = self.path.push(sub.name);
+                } else {
+                    self.truncated = true;
+                }
+            }
+
+            return Some((depth, node, segments));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::{CommandKind, CommandMeta};
+
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+    enum Level {
+        Guest = 0,
+    }
+
+    impl AccessLevel for Level {
+        fn from_str(s: &str) -> Option<Self> {
+            match s {
+                "Guest" => Some(Self::Guest),
+                _ => None,
+            }
+        }
+
+        fn as_str(&self) -> &'static str {
+            "Guest"
+        }
+
+        fn rank(&self) -> u8 {
+            *self as u8
+        }
+    }
+
+    const LED: CommandMeta<Level> = CommandMeta::new("led", "led", Level::Guest, CommandKind::Sync);
+    const REBOOT: CommandMeta<Level> =
+        CommandMeta::new("reboot", "reboot", Level::Guest, CommandKind::Sync);
+    const HELP: CommandMeta<Level> =
+        CommandMeta::new("help", "help", Level::Guest, CommandKind::Sync);
+
+    const HW: Directory<Level> = Directory::new("hw", &[Node::Command(&LED)], Level::Guest);
+
+    const SYSTEM: Directory<Level> = Directory::new(
+        "system",
+        &[Node::Directory(&HW), Node::Command(&REBOOT)],
+        Level::Guest,
+    );
+
+    const ROOT: Directory<Level> = Directory::new(
+        "/",
+        &[Node::Command(&HELP), Node::Directory(&SYSTEM)],
+        Level::Guest,
+    );
+
+    #[test]
+    fn test_walk_depth_first_order() {
+        let mut walk = ROOT.walk::<4>();
+
+        let expected: [(usize, &[&str]); 5] = [
+            (0, &["help"]),
+            (0, &["system"]),
+            (1, &["system", "hw"]),
+            (2, &["system", "hw", "led"]),
+            (1, &["system", "reboot"]),
+        ];
+
+        for (depth, segments) in expected {
+            let (d, node, path) = walk.next().unwrap();
+            assert_eq!(d, depth);
+            assert_eq!(node.name(), *segments.last().unwrap());
+            assert_eq!(path.as_slice(), segments);
+        }
+        assert!(walk.next().is_none());
+        assert!(!walk.is_truncated());
+    }
+
+    #[test]
+    fn test_walk_truncates_at_max_depth() {
+        // Room for root + one subdirectory: "hw" is yielded but not entered
+        let mut walk = ROOT.walk::<2>();
+        let names: heapless::Vec<&str, 8> = walk.by_ref().map(|(_, n, _)| n.name()).collect();
+
+        assert_eq!(names.as_slice(), &["help", "system", "hw", "reboot"]);
+        assert!(walk.is_truncated());
+    }
+
+    #[test]
+    fn test_walk_empty_directory() {
+        const EMPTY: Directory<Level> = Directory::new("/", &[], Level::Guest);
+
+        assert!(EMPTY.walk::<1>().next().is_none());
+    }
+}
@@ -17,6 +17,41 @@ use nut_shell::shell::handler::CommandHandler;
 use nut_shell::tree::{CommandKind, CommandMeta, Directory, Node};
 use nut_shell_macros::AccessLevel;
 
+// ============================================================================
+// Test Configurations
+// ============================================================================
+
+/// Declare a `ShellConfig` with the shared test limits and messages, overriding only the
+/// listed constants (those with trait defaults).
+///
+/// ```ignore
+/// test_config!(PageConfig {
+///     const PAGE_SIZE: usize = 2;
+/// });
+/// ```
+#[allow(unused_macros)]
+macro_rules! test_config {
+    ($name:ident { $(const $item:ident: $ty:ty = $value:expr;)* }) => {
+        struct $name;
+
+        impl nut_shell::ShellConfig for $name {
+            type Buffers = nut_shell::config::Buffers<128, 8, 16, 64, 256, 10>;
+
+            const MSG_WELCOME: &'static str = "Welcome";
+            const MSG_LOGIN_PROMPT: &'static str = "Login> ";
+            const MSG_LOGIN_SUCCESS: &'static str = "Logged in";
+            const MSG_LOGIN_FAILED: &'static str = "Login failed";
+            const MSG_LOGOUT: &'static str = "Logged out";
+            const MSG_INVALID_LOGIN_FORMAT: &'static str = "Invalid format";
+
+            $(const $item: $ty = $value;)*
+        }
+    };
+}
+
+#[allow(unused_imports)]
+pub(crate) use test_config;
+
 // ============================================================================
 // MockIo - Test I/O Implementation
 // ============================================================================
@@ -148,265 +183,216 @@ pub enum MockAccessLevel {
 // ============================================================================
 
 /// Test command: help
-pub const CMD_HELP: CommandMeta<MockAccessLevel> = CommandMeta {
-    id: "help",
-    name: "help",
-    description: "Show help",
-    access_level: MockAccessLevel::Guest,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
+pub const CMD_HELP: CommandMeta<MockAccessLevel> =
+    CommandMeta::new("help", "help", MockAccessLevel::Guest, CommandKind::Sync)
+        .with_description("Show help");
 
 /// Test command: echo
-pub const CMD_ECHO: CommandMeta<MockAccessLevel> = CommandMeta {
-    id: "echo",
-    name: "echo",
-    description: "Echo arguments",
-    access_level: MockAccessLevel::Guest,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 16,
-};
+pub const CMD_ECHO: CommandMeta<MockAccessLevel> =
+    CommandMeta::new("echo", "echo", MockAccessLevel::Guest, CommandKind::Sync)
+        .with_description("Echo arguments")
+        .with_args(0, 16);
 
 /// Test command: reboot (requires admin)
-pub const CMD_REBOOT: CommandMeta<MockAccessLevel> = CommandMeta {
-    id: "reboot",
-    name: "reboot",
-    description: "Reboot system",
-    access_level: MockAccessLevel::Admin,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
+pub const CMD_REBOOT: CommandMeta<MockAccessLevel> = CommandMeta::new(
+    "reboot",
+    "reboot",
+    MockAccessLevel::Admin,
+    CommandKind::Sync,
+)
+.with_description("Reboot system");
 
 // ============================================================================
 // Test Commands for Response Formatting
 // ============================================================================
 
-pub const CMD_TEST_PREFIX_NEWLINE: CommandMeta<MockAccessLevel> = CommandMeta {
-    id: "test-prefix-newline",
-    name: "test-prefix-newline",
-    description: "Test prefix newline formatting",
-    access_level: MockAccessLevel::Guest,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
-
-pub const CMD_TEST_INDENTED: CommandMeta<MockAccessLevel> = CommandMeta {
-    id: "test-indented",
-    name: "test-indented",
-    description: "Test indented formatting",
-    access_level: MockAccessLevel::Guest,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
-
-pub const CMD_TEST_INLINE: CommandMeta<MockAccessLevel> = CommandMeta {
-    id: "test-inline",
-    name: "test-inline",
-    description: "Test inline formatting",
-    access_level: MockAccessLevel::Guest,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
-
-pub const CMD_TEST_NO_POSTFIX: CommandMeta<MockAccessLevel> = CommandMeta {
-    id: "test-no-postfix",
-    name: "test-no-postfix",
-    description: "Test without postfix newline",
-    access_level: MockAccessLevel::Guest,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
-
-pub const CMD_TEST_NO_PROMPT: CommandMeta<MockAccessLevel> = CommandMeta {
-    id: "test-no-prompt",
-    name: "test-no-prompt",
-    description: "Test without prompt",
-    access_level: MockAccessLevel::Guest,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
-
-pub const CMD_TEST_COMBINED: CommandMeta<MockAccessLevel> = CommandMeta {
-    id: "test-combined",
-    name: "test-combined",
-    description: "Test combined formatting flags",
-    access_level: MockAccessLevel::Guest,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
+pub const CMD_TEST_PREFIX_NEWLINE: CommandMeta<MockAccessLevel> = CommandMeta::new(
+    "test-prefix-newline",
+    "test-prefix-newline",
+    MockAccessLevel::Guest,
+    CommandKind::Sync,
+)
+.with_description("Test prefix newline formatting");
+
+pub const CMD_TEST_INDENTED: CommandMeta<MockAccessLevel> = CommandMeta::new(
+    "test-indented",
+    "test-indented",
+    MockAccessLevel::Guest,
+    CommandKind::Sync,
+)
+.with_description("Test indented formatting");
+
+pub const CMD_TEST_INLINE: CommandMeta<MockAccessLevel> = CommandMeta::new(
+    "test-inline",
+    "test-inline",
+    MockAccessLevel::Guest,
+    CommandKind::Sync,
+)
+.with_description("Test inline formatting");
+
+pub const CMD_TEST_NO_POSTFIX: CommandMeta<MockAccessLevel> = CommandMeta::new(
+    "test-no-postfix",
+    "test-no-postfix",
+    MockAccessLevel::Guest,
+    CommandKind::Sync,
+)
+.with_description("Test without postfix newline");
+
+pub const CMD_TEST_NO_PROMPT: CommandMeta<MockAccessLevel> = CommandMeta::new(
+    "test-no-prompt",
+    "test-no-prompt",
+    MockAccessLevel::Guest,
+    CommandKind::Sync,
+)
+.with_description("Test without prompt");
+
+pub const CMD_TEST_COMBINED: CommandMeta<MockAccessLevel> = CommandMeta::new(
+    "test-combined",
+    "test-combined",
+    MockAccessLevel::Guest,
+    CommandKind::Sync,
+)
+.with_description("Test combined formatting flags");
 
 /// Test command: status (in system/ directory)
-pub const CMD_STATUS: CommandMeta<MockAccessLevel> = CommandMeta {
-    id: "status",
-    name: "status",
-    description: "Show system status",
-    access_level: MockAccessLevel::User,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
+pub const CMD_STATUS: CommandMeta<MockAccessLevel> =
+    CommandMeta::new("status", "status", MockAccessLevel::User, CommandKind::Sync)
+        .with_description("Show system status");
 
 /// Test command: async-wait (async command for testing)
 #[cfg(feature = "async")]
-pub const CMD_ASYNC_WAIT: CommandMeta<MockAccessLevel> = CommandMeta {
-    id: "async-wait",
-    name: "async-wait",
-    description: "Async test command",
-    access_level: MockAccessLevel::User,
-    kind: CommandKind::Async,
-    min_args: 0,
-    max_args: 1,
-};
+pub const CMD_ASYNC_WAIT: CommandMeta<MockAccessLevel> = CommandMeta::new(
+    "async-wait",
+    "async-wait",
+    MockAccessLevel::User,
+    CommandKind::Async,
+)
+.with_description("Async test command")
+.with_args(0, 1);
 
 /// Test directory: system/
 #[cfg(not(feature = "async"))]
-pub const DIR_SYSTEM: Directory<MockAccessLevel> = Directory {
-    name: "system",
-    children: &[
+pub const DIR_SYSTEM: Directory<MockAccessLevel> = Directory::new(
+    "system",
+    &[
         Node::Command(&CMD_STATUS),
         Node::Command(&CMD_REBOOT),
         Node::Directory(&DIR_NETWORK),
         Node::Directory(&DIR_HARDWARE),
     ],
-    access_level: MockAccessLevel::User,
-};
+    MockAccessLevel::User,
+);
 
 /// Test directory: system/ (with async command)
 #[cfg(feature = "async")]
-pub const DIR_SYSTEM: Directory<MockAccessLevel> = Directory {
-    name: "system",
-    children: &[
+pub const DIR_SYSTEM: Directory<MockAccessLevel> = Directory::new(
+    "system",
+    &[
         Node::Command(&CMD_STATUS),
         Node::Command(&CMD_REBOOT),
         Node::Command(&CMD_ASYNC_WAIT),
         Node::Directory(&DIR_NETWORK),
         Node::Directory(&DIR_HARDWARE),
     ],
-    access_level: MockAccessLevel::User,
-};
+    MockAccessLevel::User,
+);
 
 // ============================================================================
 // Network Commands (system/network/)
 // ============================================================================
 
 /// Test command: network status
-pub const CMD_NET_STATUS: CommandMeta<MockAccessLevel> = CommandMeta {
-    id: "net_status",
-    name: "status",
-    description: "Show network status",
-    access_level: MockAccessLevel::User,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
+pub const CMD_NET_STATUS: CommandMeta<MockAccessLevel> = CommandMeta::new(
+    "net_status",
+    "status",
+    MockAccessLevel::User,
+    CommandKind::Sync,
+)
+.with_description("Show network status");
 
 /// Test command: network config
-pub const CMD_NET_CONFIG: CommandMeta<MockAccessLevel> = CommandMeta {
-    id: "net_config",
-    name: "config",
-    description: "Configure network settings",
-    access_level: MockAccessLevel::Admin,
-    kind: CommandKind::Sync,
-    min_args: 2,
-    max_args: 4,
-};
+pub const CMD_NET_CONFIG: CommandMeta<MockAccessLevel> = CommandMeta::new(
+    "net_config",
+    "config",
+    MockAccessLevel::Admin,
+    CommandKind::Sync,
+)
+.with_description("Configure network settings")
+.with_args(2, 4);
 
 /// Test command: network ping
-pub const CMD_NET_PING: CommandMeta<MockAccessLevel> = CommandMeta {
-    id: "net_ping",
-    name: "ping",
-    description: "Ping remote host",
-    access_level: MockAccessLevel::User,
-    kind: CommandKind::Sync,
-    min_args: 1,
-    max_args: 2,
-};
+pub const CMD_NET_PING: CommandMeta<MockAccessLevel> =
+    CommandMeta::new("net_ping", "ping", MockAccessLevel::User, CommandKind::Sync)
+        .with_description("Ping remote host")
+        .with_args(1, 2);
 
 /// Network subdirectory
-pub const DIR_NETWORK: Directory<MockAccessLevel> = Directory {
-    name: "network",
-    children: &[
+pub const DIR_NETWORK: Directory<MockAccessLevel> = Directory::new(
+    "network",
+    &[
         Node::Command(&CMD_NET_STATUS),
         Node::Command(&CMD_NET_CONFIG),
         Node::Command(&CMD_NET_PING),
     ],
-    access_level: MockAccessLevel::User,
-};
+    MockAccessLevel::User,
+);
 
 // ============================================================================
 // Hardware Commands (system/hardware/)
 // ============================================================================
 
 /// Test command: LED control
-pub const CMD_HW_LED: CommandMeta<MockAccessLevel> = CommandMeta {
-    id: "hw_led",
-    name: "led",
-    description: "Control LED state",
-    access_level: MockAccessLevel::User,
-    kind: CommandKind::Sync,
-    min_args: 1,
-    max_args: 1,
-};
+pub const CMD_HW_LED: CommandMeta<MockAccessLevel> =
+    CommandMeta::new("hw_led", "led", MockAccessLevel::User, CommandKind::Sync)
+        .with_description("Control LED state")
+        .with_args(1, 1);
 
 /// Test command: temperature sensor
-pub const CMD_HW_TEMP: CommandMeta<MockAccessLevel> = CommandMeta {
-    id: "hw_temp",
-    name: "temperature",
-    description: "Read temperature sensor",
-    access_level: MockAccessLevel::User,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 0,
-};
+pub const CMD_HW_TEMP: CommandMeta<MockAccessLevel> = CommandMeta::new(
+    "hw_temp",
+    "temperature",
+    MockAccessLevel::User,
+    CommandKind::Sync,
+)
+.with_description("Read temperature sensor");
 
 /// Hardware subdirectory
-pub const DIR_HARDWARE: Directory<MockAccessLevel> = Directory {
-    name: "hardware",
-    children: &[Node::Command(&CMD_HW_LED), Node::Command(&CMD_HW_TEMP)],
-    access_level: MockAccessLevel::User,
-};
+pub const DIR_HARDWARE: Directory<MockAccessLevel> = Directory::new(
+    "hardware",
+    &[Node::Command(&CMD_HW_LED), Node::Command(&CMD_HW_TEMP)],
+    MockAccessLevel::User,
+);
 
 // ============================================================================
 // Debug Commands
 // ============================================================================
 
 /// Test command: memory dump
-pub const CMD_DEBUG_MEM: CommandMeta<MockAccessLevel> = CommandMeta {
-    id: "debug_mem",
-    name: "memory",
-    description: "Dump memory contents",
-    access_level: MockAccessLevel::Admin,
-    kind: CommandKind::Sync,
-    min_args: 0,
-    max_args: 2,
-};
+pub const CMD_DEBUG_MEM: CommandMeta<MockAccessLevel> = CommandMeta::new(
+    "debug_mem",
+    "memory",
+    MockAccessLevel::Admin,
+    CommandKind::Sync,
+)
+.with_description("Dump memory contents")
+.with_args(0, 2);
 
 /// Test command: register read
-pub const CMD_DEBUG_REG: CommandMeta<MockAccessLevel> = CommandMeta {
-    id: "debug_reg",
-    name: "registers",
-    description: "Read hardware registers",
-    access_level: MockAccessLevel::Admin,
-    kind: CommandKind::Sync,
-    min_args: 1,
-    max_args: 1,
-};
+pub const CMD_DEBUG_REG: CommandMeta<MockAccessLevel> = CommandMeta::new(
+    "debug_reg",
+    "registers",
+    MockAccessLevel::Admin,
+    CommandKind::Sync,
+)
+.with_description("Read hardware registers")
+.with_args(1, 1);
 
 /// Test directory: debug/ (admin only)
-pub const DIR_DEBUG: Directory<MockAccessLevel> = Directory {
-    name: "debug",
-    children: &[Node::Command(&CMD_DEBUG_MEM), Node::Command(&CMD_DEBUG_REG)],
-    access_level: MockAccessLevel::Admin,
-};
+pub const DIR_DEBUG: Directory<MockAccessLevel> = Directory::new(
+    "debug",
+    &[Node::Command(&CMD_DEBUG_MEM), Node::Command(&CMD_DEBUG_REG)],
+    MockAccessLevel::Admin,
+);
 
 /// Root directory for testing.
 ///
@@ -440,9 +426,9 @@ pub const DIR_DEBUG: Directory<MockAccessLevel> = Directory {
 /// - Feature-gated commands (async-wait)
 /// - Both empty and populated directories at each level
 /// - All const-initializable (lives in ROM)
-pub const TEST_TREE: Directory<MockAccessLevel> = Directory {
-    name: "/",
-    children: &[
+pub const TEST_TREE: Directory<MockAccessLevel> = Directory::new(
+    "/",
+    &[
         Node::Command(&CMD_HELP),
         Node::Command(&CMD_ECHO),
         Node::Directory(&DIR_SYSTEM),
@@ -455,8 +441,8 @@ pub const TEST_TREE: Directory<MockAccessLevel> = Directory {
         Node::Command(&CMD_TEST_NO_PROMPT),
         Node::Command(&CMD_TEST_COMBINED),
     ],
-    access_level: MockAccessLevel::Guest,
-};
+    MockAccessLevel::Guest,
+);
 
 // ============================================================================
 // MockHandler - Command Execution Implementation
@@ -570,6 +556,7 @@ impl CommandHandler<DefaultConfig> for MockHandler {
         &self,
         id: &str,
         args: &[&str],
+        _cancel: &nut_shell::CancelToken,
     ) -> Result<Response<DefaultConfig>, CliError> {
         match id {
             "async-wait" => {
@@ -612,6 +599,15 @@ impl CommandHandler<DefaultConfig> for MockHandler {
 // Helper Functions
 // ============================================================================
 
+/// Sync command whose id is its name, for trees built inside a test.
+/// Chain the `CommandMeta` setters for anything else.
+pub const fn cmd(
+    name: &'static str,
+    access_level: MockAccessLevel,
+) -> CommandMeta<MockAccessLevel> {
+    CommandMeta::new(name, name, access_level, CommandKind::Sync)
+}
+
 /// Create MockIo with input ending in newline.
 pub fn io_with_command(cmd: &str) -> MockIo {
     let mut input = HString::<256>::new();
@@ -685,8 +681,18 @@ mod tests {
             Some(MockAccessLevel::Admin)
         );
         assert_eq!(MockAccessLevel::from_str("Invalid"), None);
+        assert_eq!(MockAccessLevel::from_str("admin"), None);
+
+        // Derived case-insensitive parsing
+        assert_eq!(
+            MockAccessLevel::from_str_ignore_case("aDMIN"),
+            Some(MockAccessLevel::Admin)
+        );
+        assert_eq!(MockAccessLevel::from_str_ignore_case("admins"), None);
 
         assert_eq!(MockAccessLevel::Admin.as_str(), "Admin");
+        assert_eq!(MockAccessLevel::Guest.rank(), 0);
+        assert_eq!(MockAccessLevel::Admin.rank(), 2);
     }
 
     #[test]
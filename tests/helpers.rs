@@ -38,7 +38,7 @@ static AUTH_PROVIDER: std::sync::OnceLock<
 
 /// Get or create the static auth provider.
 #[cfg(feature = "authentication")]
-fn get_auth_provider() -> &'static nut_shell::auth::ConstCredentialProvider<
+pub fn auth_provider() -> &'static nut_shell::auth::ConstCredentialProvider<
     MockAccessLevel,
     nut_shell::auth::password::Sha256Hasher,
     2,
@@ -68,7 +68,7 @@ fn get_auth_provider() -> &'static nut_shell::auth::ConstCredentialProvider<
 /// Create an authenticated shell with test provider.
 #[cfg(feature = "authentication")]
 pub fn create_auth_shell() -> Shell<'static, MockAccessLevel, MockIo, MockHandler, DefaultConfig> {
-    let provider = get_auth_provider();
+    let provider = auth_provider();
     let io = MockIo::new();
     let handler = MockHandler;
     let mut shell = Shell::new(&TEST_TREE, handler, provider, io);
@@ -20,7 +20,7 @@ fn test_command_history_zero_size_when_disabled() {
     // When history feature is disabled, CommandHistory should be zero-size
     #[cfg(not(feature = "history"))]
     {
-        let size = core::mem::size_of::<CommandHistory<10, 128>>();
+        let size = core::mem::size_of::<CommandHistory<DefaultConfig>>();
         assert_eq!(
             size, 0,
             "CommandHistory should be zero-size when history feature disabled, got: {} bytes",
@@ -31,7 +31,7 @@ fn test_command_history_zero_size_when_disabled() {
     // When history feature is enabled, it should have a non-zero size
     #[cfg(feature = "history")]
     {
-        let size = core::mem::size_of::<CommandHistory<10, 128>>();
+        let size = core::mem::size_of::<CommandHistory<DefaultConfig>>();
         assert!(
             size > 0,
             "CommandHistory should have non-zero size when history feature enabled"
@@ -41,15 +41,28 @@ fn test_command_history_zero_size_when_disabled() {
 
 #[test]
 fn test_empty_history_is_zero_size() {
-    // CommandHistory with N=0 should be zero-size even when feature enabled
+    // A config with no history entries should be near zero-size even when feature enabled
     #[cfg(feature = "history")]
     {
-        let size = core::mem::size_of::<CommandHistory<0, 128>>();
+        struct NoHistoryConfig;
+
+        impl ShellConfig for NoHistoryConfig {
+            type Buffers = nut_shell::config::Buffers<128, 8, 16, 64, 256, 0>;
+
+            const MSG_WELCOME: &'static str = "";
+            const MSG_LOGIN_PROMPT: &'static str = "";
+            const MSG_LOGIN_SUCCESS: &'static str = "";
+            const MSG_LOGIN_FAILED: &'static str = "";
+            const MSG_LOGOUT: &'static str = "";
+            const MSG_INVALID_LOGIN_FORMAT: &'static str = "";
+        }
+
+        let size = core::mem::size_of::<CommandHistory<NoHistoryConfig>>();
         // Note: Contains position field (Option<usize>) even when empty
         // Expect size of Option<usize> which is typically one pointer width
         assert!(
             size <= 24,
-            "CommandHistory<0> should be minimal size (Option<usize>), got: {} bytes",
+            "CommandHistory with 0 entries should be minimal size (Option<usize>), got: {} bytes",
             size
         );
     }
@@ -61,63 +74,39 @@ fn test_empty_history_is_zero_size() {
 
 #[test]
 fn test_command_meta_is_const_initializable() {
-    const _CMD: CommandMeta<MockAccessLevel> = CommandMeta {
-        id: "test",
-        name: "test",
-        description: "Test command",
-        access_level: MockAccessLevel::Guest,
-        kind: CommandKind::Sync,
-        min_args: 0,
-        max_args: 0,
-    };
+    const _CMD: CommandMeta<MockAccessLevel> =
+        CommandMeta::new("test", "test", MockAccessLevel::Guest, CommandKind::Sync)
+            .with_description("Test command");
 
     // If this compiles, const initialization works
 }
 
 #[test]
 fn test_directory_is_const_initializable() {
-    const _DIR: Directory<MockAccessLevel> = Directory {
-        name: "test",
-        children: &[],
-        access_level: MockAccessLevel::Guest,
-    };
+    const _DIR: Directory<MockAccessLevel> = Directory::new("test", &[], MockAccessLevel::Guest);
 
     // If this compiles, const initialization works
 }
 
 #[test]
 fn test_nested_tree_is_const_initializable() {
-    const CMD1: CommandMeta<MockAccessLevel> = CommandMeta {
-        id: "cmd1",
-        name: "cmd1",
-        description: "Command 1",
-        access_level: MockAccessLevel::Guest,
-        kind: CommandKind::Sync,
-        min_args: 0,
-        max_args: 0,
-    };
-
-    const CMD2: CommandMeta<MockAccessLevel> = CommandMeta {
-        id: "cmd2",
-        name: "cmd2",
-        description: "Command 2",
-        access_level: MockAccessLevel::User,
-        kind: CommandKind::Sync,
-        min_args: 0,
-        max_args: 1,
-    };
-
-    const SUBDIR: Directory<MockAccessLevel> = Directory {
-        name: "subdir",
-        children: &[Node::Command(&CMD2)],
-        access_level: MockAccessLevel::Guest,
-    };
-
-    const ROOT: Directory<MockAccessLevel> = Directory {
-        name: "/",
-        children: &[Node::Command(&CMD1), Node::Directory(&SUBDIR)],
-        access_level: MockAccessLevel::Guest,
-    };
+    const CMD1: CommandMeta<MockAccessLevel> =
+        CommandMeta::new("cmd1", "cmd1", MockAccessLevel::Guest, CommandKind::Sync)
+            .with_description("Command 1");
+
+    const CMD2: CommandMeta<MockAccessLevel> =
+        CommandMeta::new("cmd2", "cmd2", MockAccessLevel::User, CommandKind::Sync)
+            .with_description("Command 2")
+            .with_args(0, 1);
+
+    const SUBDIR: Directory<MockAccessLevel> =
+        Directory::new("subdir", &[Node::Command(&CMD2)], MockAccessLevel::Guest);
+
+    const ROOT: Directory<MockAccessLevel> = Directory::new(
+        "/",
+        &[Node::Command(&CMD1), Node::Directory(&SUBDIR)],
+        MockAccessLevel::Guest,
+    );
 
     // Verify we can reference the const tree
     assert_eq!(ROOT.name, "/");
@@ -148,23 +137,7 @@ fn test_test_tree_is_const() {
 fn test_config_constants_are_zero_cost() {
     // Config traits should have zero runtime cost (no vtable)
     // The struct implementing ShellConfig should be zero-size if it has no fields
-    struct TestConfig;
-
-    impl ShellConfig for TestConfig {
-        const MAX_INPUT: usize = 128;
-        const MAX_PATH_DEPTH: usize = 8;
-        const MAX_ARGS: usize = 16;
-        const MAX_PROMPT: usize = 64;
-        const MAX_RESPONSE: usize = 256;
-        const HISTORY_SIZE: usize = 10;
-
-        const MSG_WELCOME: &'static str = "";
-        const MSG_LOGIN_PROMPT: &'static str = "";
-        const MSG_LOGIN_SUCCESS: &'static str = "";
-        const MSG_LOGIN_FAILED: &'static str = "";
-        const MSG_LOGOUT: &'static str = "";
-        const MSG_INVALID_LOGIN_FORMAT: &'static str = "";
-    }
+    fixtures::test_config!(TestConfig {});
 
     assert_eq!(
         core::mem::size_of::<TestConfig>(),
@@ -189,20 +162,26 @@ fn test_command_meta_size_is_reasonable() {
     // CommandMeta should be small since it only contains static references
     let size = core::mem::size_of::<CommandMeta<MockAccessLevel>>();
 
-    // Expected: 3 pointers (id, name, description) + access_level + kind + 2 usizes
-    // On 64-bit: 3*8 + 1 + 1 + 2*8 = ~42 bytes (with padding to 8-byte alignment = ~72 bytes)
-    // On 32-bit: 3*4 + 1 + 1 + 2*4 = ~22 bytes (with padding to 4-byte alignment = ~40 bytes)
+    // Expected (64-bit / 32-bit bytes):
+    // - 4 fat pointers (id, name, description, arg_spec): 64 / 32
+    // - 2 usizes (min_args, max_args): 16 / 8
+    // - min_interval_ms: 4 / 4
+    // - access_level, kind, 3 bool flags, required_role (Option<u8>): 7 / 7
+    // = 91 / 51, padded to pointer alignment: 96 / 52
+    //
+    // arg_spec (+16 / +8) and min_interval_ms took this past the original 80 / 48 bound.
+    // Command metadata is const data in flash, so the growth is ROM per command, not RAM.
 
     #[cfg(target_pointer_width = "64")]
     assert!(
-        size <= 80,
+        size <= 96,
         "CommandMeta size should be reasonable on 64-bit (got {} bytes)",
         size
     );
 
     #[cfg(target_pointer_width = "32")]
     assert!(
-        size <= 48,
+        size <= 52,
         "CommandMeta size should be reasonable on 32-bit (got {} bytes)",
         size
     );
@@ -243,15 +222,9 @@ fn test_tree_has_static_lifetime() {
 
 #[test]
 fn test_command_meta_has_static_lifetime() {
-    const CMD: CommandMeta<MockAccessLevel> = CommandMeta {
-        id: "test",
-        name: "test",
-        description: "Test",
-        access_level: MockAccessLevel::Guest,
-        kind: CommandKind::Sync,
-        min_args: 0,
-        max_args: 0,
-    };
+    const CMD: CommandMeta<MockAccessLevel> =
+        CommandMeta::new("test", "test", MockAccessLevel::Guest, CommandKind::Sync)
+            .with_description("Test");
 
     let _cmd_ref: &'static CommandMeta<MockAccessLevel> = &CMD;
 
@@ -287,6 +260,17 @@ fn test_request_size_varies_with_features() {
     );
 }
 
+#[test]
+fn test_minimal_config_shrinks_buffers() {
+    use nut_shell::shell::Request;
+
+    // Buffers are sized from the config, not a shared ceiling
+    assert!(
+        core::mem::size_of::<Request<MinimalConfig>>()
+            < core::mem::size_of::<Request<DefaultConfig>>()
+    );
+}
+
 // ============================================================================
 // Compile-Time Computation Tests
 // ============================================================================
@@ -231,6 +231,172 @@ fn test_double_esc_clears_masked_input() {
 // Authentication Edge Cases (documents login behavior)
 // ============================================================================
 
+#[test]
+fn test_exit_deactivates_instead_of_logout() {
+    let mut shell = helpers::create_auth_shell();
+    helpers::execute_command_auth(&mut shell, "admin:admin123");
+
+    let output = helpers::execute_command_auth(&mut shell, "exit");
+    assert!(output.contains(DefaultConfig::MSG_GOODBYE), "{}", output);
+    assert!(
+        !output.contains(DefaultConfig::MSG_LOGIN_PROMPT),
+        "exit must not return to login: {}",
+        output
+    );
+
+    // Inactive shell ignores input until re-activated
+    let output = helpers::execute_command_auth(&mut shell, "admin:admin123");
+    assert!(
+        !output.contains(DefaultConfig::MSG_LOGIN_SUCCESS),
+        "{}",
+        output
+    );
+}
+
+#[test]
+fn test_session_state_getters() {
+    use helpers::fixtures::MockAccessLevel;
+    use nut_shell::CliState;
+
+    let mut shell = helpers::create_auth_shell();
+    assert_eq!(shell.state(), CliState::LoggedOut);
+    assert_eq!(shell.current_username(), None);
+    assert_eq!(shell.current_access_level(), None);
+
+    helpers::execute_command_auth(&mut shell, "admin:admin123");
+    assert_eq!(shell.state(), CliState::LoggedIn);
+    assert_eq!(shell.current_username(), Some("admin"));
+    assert_eq!(shell.current_access_level(), Some(MockAccessLevel::Admin));
+
+    helpers::execute_command_auth(&mut shell, "logout");
+    assert_eq!(shell.state(), CliState::LoggedOut);
+    assert_eq!(shell.current_username(), None);
+
+    helpers::execute_command_auth(&mut shell, "admin:admin123");
+    helpers::execute_command_auth(&mut shell, "exit");
+    assert_eq!(shell.state(), CliState::Inactive);
+    assert_eq!(shell.current_access_level(), None);
+}
+
+#[test]
+fn test_runtime_welcome_and_login_prompt() {
+    let mut shell = helpers::create_auth_shell();
+    shell.set_welcome("Unit 0042 ready").unwrap();
+    shell.set_login_prompt("badge: ").unwrap();
+    assert_eq!(
+        shell.set_welcome(&"x".repeat(nut_shell::config::capacity::MESSAGE + 1)),
+        Err(nut_shell::CliError::BufferFull)
+    );
+
+    helpers::execute_command_auth(&mut shell, "admin:admin123");
+    let output = helpers::execute_command_auth(&mut shell, "logout");
+    assert!(output.contains("badge: "), "{}", output);
+    assert!(
+        !output.contains(DefaultConfig::MSG_LOGIN_PROMPT),
+        "{}",
+        output
+    );
+
+    let output = helpers::execute_command_auth(&mut shell, "admin:wrong");
+    assert!(output.ends_with("badge: "), "{}", output);
+    assert_eq!(shell.current_prompt(), "badge: ");
+
+    helpers::execute_command_auth(&mut shell, "admin:admin123");
+    helpers::execute_command_auth(&mut shell, "exit");
+    shell.io_mut().clear_output();
+    shell.activate().unwrap();
+    let output = shell.io_mut().output();
+    assert!(output.contains("Unit 0042 ready"), "{}", output);
+    assert!(!output.contains(DefaultConfig::MSG_WELCOME), "{}", output);
+    assert!(output.ends_with("badge: "), "{}", output);
+}
+
+#[test]
+fn test_motd_shown_after_login() {
+    let mut shell = helpers::create_auth_shell();
+    shell.set_motd("Maintenance window 02:00 UTC").unwrap();
+
+    // Not shown before login or on failed attempts
+    assert!(!shell.io_mut().output().contains("Maintenance"));
+    let output = helpers::execute_command_auth(&mut shell, "admin:wrong");
+    assert!(!output.contains("Maintenance"), "{}", output);
+
+    let output = helpers::execute_command_auth(&mut shell, "admin:admin123");
+    let success = output.find(DefaultConfig::MSG_LOGIN_SUCCESS).unwrap();
+    let motd = output.find("Maintenance window 02:00 UTC").unwrap();
+    assert!(success < motd, "{}", output);
+    assert!(output.ends_with("admin@/> "), "{}", output);
+
+    let output = helpers::execute_command_auth(&mut shell, "echo hi");
+    assert!(!output.contains("Maintenance"), "{}", output);
+
+    // Shown again on the next login
+    helpers::execute_command_auth(&mut shell, "logout");
+    let output = helpers::execute_command_auth(&mut shell, "guest:guest123");
+    assert!(output.contains("Maintenance window"), "{}", output);
+}
+
+#[test]
+fn test_execute_line_requires_login_and_access() {
+    use nut_shell::CliError;
+
+    let mut shell = helpers::create_auth_shell();
+    assert_eq!(
+        shell.execute_line("echo hi").unwrap_err(),
+        CliError::NotAuthenticated
+    );
+
+    helpers::execute_command_auth(&mut shell, "guest:guest123");
+    shell.io_mut().clear_output();
+    assert_eq!(
+        shell.execute_line("echo hi").unwrap().message.as_str(),
+        "hi"
+    );
+    assert!(shell.execute_line("debug/memory").is_err());
+    assert_eq!(shell.io_mut().output(), "");
+}
+
+#[test]
+fn test_would_resolve_applies_login_and_access() {
+    use nut_shell::{CliError, ResolveKind};
+
+    let mut shell = helpers::create_auth_shell();
+    assert_eq!(shell.would_resolve("echo"), Err(CliError::NotAuthenticated));
+
+    helpers::execute_command_auth(&mut shell, "guest:guest123");
+    assert_eq!(shell.would_resolve("echo hi"), Ok(ResolveKind::Command));
+    assert_eq!(shell.would_resolve("logout"), Ok(ResolveKind::Global));
+    assert!(shell.would_resolve("debug/memory").is_err());
+    // `users` is only a global command once enabled for the user's level
+    assert!(shell.would_resolve("users").is_err());
+}
+
+#[test]
+fn test_users_command_gated_by_level() {
+    use helpers::fixtures::MockAccessLevel;
+
+    let mut shell = helpers::create_auth_shell();
+    helpers::execute_command_auth(&mut shell, "admin:admin123");
+
+    // Disabled by default
+    let output = helpers::execute_command_auth(&mut shell, "users");
+    assert!(output.contains("Error"), "{}", output);
+
+    shell.enable_users_command(MockAccessLevel::Admin);
+    let output = helpers::execute_command_auth(&mut shell, "users");
+    assert!(output.contains("  admin  Admin\r\n"), "{}", output);
+    assert!(output.contains("  guest  Guest\r\n"), "{}", output);
+    let output = helpers::execute_command_auth(&mut shell, "?");
+    assert!(output.contains("users"), "{}", output);
+
+    helpers::execute_command_auth(&mut shell, "logout");
+    helpers::execute_command_auth(&mut shell, "guest:guest123");
+    let output = helpers::execute_command_auth(&mut shell, "users");
+    assert!(!output.contains("admin"), "{}", output);
+    let output = helpers::execute_command_auth(&mut shell, "?");
+    assert!(!output.contains("users"), "{}", output);
+}
+
 #[test]
 fn test_login_with_wrong_password() {
     // Wrong password should show failure message
@@ -402,3 +568,1274 @@ fn test_password_masking_immediate() {
         output
     );
 }
+
+// ============================================================================
+// User Enumeration Timing Tests
+// ============================================================================
+
+mod decoy_hash {
+    use super::fixtures::{MockAccessLevel, MockHandler, MockIo, TEST_TREE};
+    use core::cell::Cell;
+    use nut_shell::Shell;
+    use nut_shell::auth::{CredentialProvider, PasswordHasher, User, password::Sha256Hasher};
+    use nut_shell::config::{DefaultConfig, ShellConfig};
+    use nut_shell::error::CliError;
+
+    /// Provider with one user that counts hash operations.
+    struct CountingProvider {
+        user: User<MockAccessLevel>,
+        hashes: Cell<usize>,
+    }
+
+    impl CredentialProvider<MockAccessLevel> for CountingProvider {
+        type Error = ();
+
+        fn find_user(&self, username: &str) -> Result<Option<User<MockAccessLevel>>, ()> {
+            Ok((self.user.username.as_str() == username).then(|| self.user.clone()))
+        }
+
+        fn verify_password(&self, user: &User<MockAccessLevel>, password: &str) -> bool {
+            self.hashes.set(self.hashes.get() + 1);
+            Sha256Hasher::new().verify(password, &user.salt, &user.password_hash)
+        }
+
+        fn hash_password(&self, password: &str, salt: &[u8; 16]) -> Result<[u8; 32], CliError> {
+            self.hashes.set(self.hashes.get() + 1);
+            Ok(Sha256Hasher::new().hash(password, salt))
+        }
+    }
+
+    #[test]
+    fn test_unknown_user_costs_one_hash() {
+        let salt = [9u8; 16];
+        let hash = Sha256Hasher::new().hash("secret", &salt);
+        let provider = CountingProvider {
+            user: User::new("admin", MockAccessLevel::Admin, hash, salt).unwrap(),
+            hashes: Cell::new(0),
+        };
+        let mut shell: Shell<_, _, _, DefaultConfig> =
+            Shell::new(&TEST_TREE, MockHandler, &provider, MockIo::new());
+        shell.activate().unwrap();
+
+        for (line, hashes) in [("admin:wrong\n", 1), ("nobody:wrong\n", 2)] {
+            shell.io_mut().clear_output();
+            for c in line.chars() {
+                shell.process_char(c).unwrap();
+            }
+            assert!(
+                shell
+                    .io()
+                    .output()
+                    .contains(DefaultConfig::MSG_LOGIN_FAILED)
+            );
+            assert_eq!(provider.hashes.get(), hashes, "{}", line);
+        }
+    }
+}
+
+// ============================================================================
+// Password Change Tests
+// ============================================================================
+
+mod password_change {
+    use super::fixtures::{MockAccessLevel, MockHandler, MockIo, TEST_TREE};
+    use super::helpers;
+    use core::cell::RefCell;
+    use nut_shell::Shell;
+    use nut_shell::auth::{
+        CredentialProvider, EntropySource, PasswordHasher, User, password::Sha256Hasher,
+    };
+    use nut_shell::config::{DefaultConfig, ShellConfig};
+    use nut_shell::error::CliError;
+
+    /// RAM-backed provider that persists password changes.
+    struct RamProvider {
+        users: RefCell<[User<MockAccessLevel>; 1]>,
+        hasher: Sha256Hasher,
+    }
+
+    impl RamProvider {
+        fn new() -> Self {
+            let hasher = Sha256Hasher::new();
+            let salt = [7u8; 16];
+            let hash = hasher.hash("oldpass", &salt);
+            Self {
+                users: RefCell::new([
+                    User::new("admin", MockAccessLevel::Admin, hash, salt).unwrap()
+                ]),
+                hasher,
+            }
+        }
+    }
+
+    impl CredentialProvider<MockAccessLevel> for RamProvider {
+        type Error = ();
+
+        fn find_user(&self, username: &str) -> Result<Option<User<MockAccessLevel>>, ()> {
+            Ok(self
+                .users
+                .borrow()
+                .iter()
+                .find(|u| u.username.as_str() == username)
+                .cloned())
+        }
+
+        fn verify_password(&self, user: &User<MockAccessLevel>, password: &str) -> bool {
+            self.hasher
+                .verify(password, &user.salt, &user.password_hash)
+        }
+
+        fn hash_password(&self, password: &str, salt: &[u8; 16]) -> Result<[u8; 32], CliError> {
+            Ok(self.hasher.hash(password, salt))
+        }
+
+        fn set_password(
+            &self,
+            username: &str,
+            new_hash: [u8; 32],
+            new_salt: [u8; 16],
+        ) -> Result<(), CliError> {
+            let mut users = self.users.borrow_mut();
+            let user = users
+                .iter_mut()
+                .find(|u| u.username.as_str() == username)
+                .ok_or(CliError::InvalidPath)?;
+            user.password_hash = new_hash;
+            user.salt = new_salt;
+            Ok(())
+        }
+    }
+
+    fn login(
+        shell: &mut Shell<'_, MockAccessLevel, MockIo, MockHandler, DefaultConfig>,
+        line: &str,
+    ) -> bool {
+        shell.io_mut().clear_output();
+        for c in line.chars() {
+            shell.process_char(c).unwrap();
+        }
+        shell.process_char('\n').unwrap();
+        shell
+            .io()
+            .output()
+            .contains(DefaultConfig::MSG_LOGIN_SUCCESS)
+    }
+
+    #[test]
+    fn test_change_password_persists_in_ram_provider() {
+        let provider = RamProvider::new();
+        let mut shell: Shell<_, _, _, DefaultConfig> =
+            Shell::new(&TEST_TREE, MockHandler, &provider, MockIo::new());
+        shell.activate().unwrap();
+
+        assert!(login(&mut shell, "admin:oldpass"));
+        shell
+            .change_password("oldpass", "newpass", [9u8; 16])
+            .unwrap();
+
+        shell.io_mut().clear_output();
+        for c in "logout\n".chars() {
+            shell.process_char(c).unwrap();
+        }
+
+        assert!(!login(&mut shell, "admin:oldpass"));
+        assert!(login(&mut shell, "admin:newpass"));
+    }
+
+    #[test]
+    fn test_change_password_wrong_current_password() {
+        let provider = RamProvider::new();
+        let mut shell: Shell<_, _, _, DefaultConfig> =
+            Shell::new(&TEST_TREE, MockHandler, &provider, MockIo::new());
+        shell.activate().unwrap();
+        assert!(login(&mut shell, "admin:oldpass"));
+
+        assert_eq!(
+            shell.change_password("wrong", "newpass", [9u8; 16]),
+            Err(CliError::AuthenticationFailed)
+        );
+    }
+
+    #[test]
+    fn test_change_password_requires_login() {
+        let provider = RamProvider::new();
+        let mut shell: Shell<_, _, _, DefaultConfig> =
+            Shell::new(&TEST_TREE, MockHandler, &provider, MockIo::new());
+        shell.activate().unwrap();
+
+        assert_eq!(
+            shell.change_password("oldpass", "newpass", [9u8; 16]),
+            Err(CliError::NotAuthenticated)
+        );
+    }
+
+    #[test]
+    fn test_change_password_const_provider_unsupported() {
+        let mut shell = helpers::create_auth_shell();
+        helpers::execute_command_auth(&mut shell, "admin:admin123");
+
+        assert_eq!(
+            shell.change_password("admin123", "newpass", [9u8; 16]),
+            Err(CliError::Unsupported)
+        );
+
+        // Session credentials untouched
+        assert_eq!(
+            shell.change_password("newpass", "x", [9u8; 16]),
+            Err(CliError::AuthenticationFailed)
+        );
+    }
+
+    /// Deterministic stand-in for a hardware RNG.
+    struct CounterEntropy {
+        next: core::cell::Cell<u8>,
+    }
+
+    impl EntropySource for CounterEntropy {
+        fn fill(&self, buf: &mut [u8]) {
+            for b in buf.iter_mut() {
+                *b = self.next.get();
+                self.next.set(self.next.get().wrapping_add(1));
+            }
+        }
+    }
+
+    #[test]
+    fn test_generate_salt_without_entropy_source() {
+        let shell = helpers::create_auth_shell();
+        assert_eq!(shell.generate_salt(), Err(CliError::Unsupported));
+    }
+
+    #[test]
+    fn test_change_password_with_generated_salt() {
+        let provider = RamProvider::new();
+        let entropy = CounterEntropy {
+            next: core::cell::Cell::new(100),
+        };
+        let mut shell: Shell<_, _, _, DefaultConfig> =
+            Shell::new(&TEST_TREE, MockHandler, &provider, MockIo::new());
+        shell.set_entropy_source(&entropy);
+        shell.activate().unwrap();
+        assert!(login(&mut shell, "admin:oldpass"));
+
+        let salt = shell.generate_salt().unwrap();
+        assert_eq!(salt[0], 100);
+        assert_eq!(salt[15], 115);
+        assert_ne!(shell.generate_salt().unwrap(), salt);
+
+        shell.change_password("oldpass", "newpass", salt).unwrap();
+
+        let user = provider.find_user("admin").unwrap().unwrap();
+        assert_eq!(user.salt, salt);
+        assert!(provider.verify_password(&user, "newpass"));
+    }
+}
+
+// ============================================================================
+// Home Directory Tests
+// ============================================================================
+
+mod home_path {
+    use super::helpers::fixtures::{MockAccessLevel, MockHandler, MockIo, TEST_TREE};
+    use nut_shell::Shell;
+    use nut_shell::auth::{ConstCredentialProvider, PasswordHasher, User, password::Sha256Hasher};
+    use nut_shell::config::DefaultConfig;
+
+    fn provider() -> ConstCredentialProvider<MockAccessLevel, Sha256Hasher, 3> {
+        let hasher = Sha256Hasher::new();
+        let salt = [3u8; 16];
+        let hash = hasher.hash("pw", &salt);
+        let user = |name, level| User::new(name, level, hash, salt).unwrap();
+
+        ConstCredentialProvider::new(
+            [
+                user("tech", MockAccessLevel::User).with_home_path("/system"),
+                // Home requires Admin - falls back to root
+                user("guest", MockAccessLevel::Guest).with_home_path("/debug"),
+                user("admin", MockAccessLevel::Admin).with_home_path("/nope"),
+            ],
+            hasher,
+        )
+    }
+
+    fn run(
+        shell: &mut Shell<'_, MockAccessLevel, MockIo, MockHandler, DefaultConfig>,
+        line: &str,
+    ) -> String {
+        shell.io_mut().clear_output();
+        for c in line.chars().chain(['\n']) {
+            shell.process_char(c).unwrap();
+        }
+        shell.io().output().to_string()
+    }
+
+    #[test]
+    fn test_login_enters_home_directory() {
+        let provider = provider();
+        let mut shell: Shell<_, _, _, DefaultConfig> =
+            Shell::new(&TEST_TREE, MockHandler, &provider, MockIo::new());
+        shell.activate().unwrap();
+
+        let output = run(&mut shell, "tech:pw");
+        assert!(output.ends_with("tech@/system> "), "{:?}", output);
+
+        // Logout returns to root; next login starts from its own home
+        run(&mut shell, "logout");
+        let output = run(&mut shell, "guest:pw");
+        assert!(output.ends_with("guest@/> "), "{:?}", output);
+
+        run(&mut shell, "logout");
+        let output = run(&mut shell, "admin:pw");
+        assert!(output.ends_with("admin@/> "), "{:?}", output);
+    }
+}
+
+// ============================================================================
+// Role Tests
+// ============================================================================
+
+#[cfg(feature = "roles")]
+mod roles {
+    use super::fixtures::{CMD_HW_LED, MockAccessLevel, MockHandler, MockIo, cmd};
+    use nut_shell::Shell;
+    use nut_shell::auth::{
+        ConstCredentialProvider, PasswordHasher, RoleSet, User, password::Sha256Hasher,
+    };
+    use nut_shell::config::DefaultConfig;
+    use nut_shell::tree::{CommandKind, CommandMeta, Directory, Node};
+
+    const NET: u8 = 0;
+    const HW: u8 = 1;
+
+    const NET_STATUS: CommandMeta<MockAccessLevel> = CommandMeta::new(
+        "net_status",
+        "netstat",
+        MockAccessLevel::User,
+        CommandKind::Sync,
+    )
+    .with_required_role(NET);
+    const HW_LED: CommandMeta<MockAccessLevel> = CMD_HW_LED.with_required_role(HW);
+    const STATUS: CommandMeta<MockAccessLevel> = cmd("status", MockAccessLevel::User);
+
+    const TREE: Directory<MockAccessLevel> = Directory::new(
+        "/",
+        &[
+            Node::Command(&NET_STATUS),
+            Node::Command(&HW_LED),
+            Node::Command(&STATUS),
+        ],
+        MockAccessLevel::Guest,
+    );
+
+    fn provider() -> ConstCredentialProvider<MockAccessLevel, Sha256Hasher, 4> {
+        let hasher = Sha256Hasher::new();
+        let salt = [7u8; 16];
+        let hash = hasher.hash("pw", &salt);
+        let user = |name, level, roles| {
+            User::new(name, level, hash, salt)
+                .unwrap()
+                .with_roles(roles)
+        };
+
+        ConstCredentialProvider::new(
+            [
+                user("netop", MockAccessLevel::User, RoleSet::empty().with(NET)),
+                user("hwop", MockAccessLevel::User, RoleSet::empty().with(HW)),
+                // Role without the level is not enough
+                user("guest", MockAccessLevel::Guest, RoleSet::empty().with(NET)),
+                // Level without the role is not enough either
+                user("admin", MockAccessLevel::Admin, RoleSet::empty()),
+            ],
+            hasher,
+        )
+    }
+
+    fn run(
+        shell: &mut Shell<'_, MockAccessLevel, MockIo, MockHandler, DefaultConfig>,
+        line: &str,
+    ) -> String {
+        shell.io_mut().clear_output();
+        for c in line.chars().chain(['\n']) {
+            shell.process_char(c).unwrap();
+        }
+        shell.io().output().to_string()
+    }
+
+    #[test]
+    fn test_role_and_level_both_required() {
+        let provider = provider();
+        let mut shell: Shell<_, _, _, DefaultConfig> =
+            Shell::new(&TREE, MockHandler, &provider, MockIo::new());
+        shell.activate().unwrap();
+
+        for (user, net, led) in [
+            ("netop", true, false),
+            ("hwop", false, true),
+            ("guest", false, false),
+            ("admin", false, false),
+        ] {
+            run(&mut shell, &format!("{}:pw", user));
+
+            let output = run(&mut shell, "netstat");
+            assert_eq!(output.contains("Network OK"), net, "{}: {}", user, output);
+            let output = run(&mut shell, "led on");
+            assert_eq!(output.contains("LED: on"), led, "{}: {}", user, output);
+
+            // Listing hides what the user cannot run
+            let output = run(&mut shell, "ls");
+            assert_eq!(output.contains("netstat"), net, "{}: {}", user, output);
+            assert_eq!(output.contains("led"), led, "{}: {}", user, output);
+
+            run(&mut shell, "logout");
+        }
+    }
+}
+
+// ============================================================================
+// Re-authentication Tests
+// ============================================================================
+
+mod reauth {
+    use super::fixtures::{MockAccessLevel, MockHandler, MockIo};
+    use nut_shell::Shell;
+    use nut_shell::auth::{ConstCredentialProvider, PasswordHasher, User, password::Sha256Hasher};
+    use nut_shell::config::{DefaultConfig, ShellConfig};
+    use nut_shell::tree::{CommandKind, CommandMeta, Directory, Node};
+
+    const REBOOT: CommandMeta<MockAccessLevel> = CommandMeta::new(
+        "reboot",
+        "reboot",
+        MockAccessLevel::Guest,
+        CommandKind::Sync,
+    )
+    .with_description("Reboot (privileged)")
+    .requiring_reauth();
+
+    const STATUS: CommandMeta<MockAccessLevel> = CommandMeta::new(
+        "status",
+        "status",
+        MockAccessLevel::Guest,
+        CommandKind::Sync,
+    )
+    .with_description("Status");
+
+    const TREE: Directory<MockAccessLevel> = Directory::new(
+        "/",
+        &[Node::Command(&REBOOT), Node::Command(&STATUS)],
+        MockAccessLevel::Guest,
+    );
+
+    fn provider() -> ConstCredentialProvider<MockAccessLevel, Sha256Hasher, 1> {
+        let hasher = Sha256Hasher::new();
+        let salt = [5u8; 16];
+        let hash = hasher.hash("guest123", &salt);
+        let users = [User::new("guest", MockAccessLevel::Guest, hash, salt).unwrap()];
+        ConstCredentialProvider::new(users, hasher)
+    }
+
+    fn send(
+        shell: &mut Shell<'_, MockAccessLevel, MockIo, MockHandler, DefaultConfig>,
+        line: &str,
+    ) -> heapless::String<1024> {
+        shell.io_mut().clear_output();
+        for c in line.chars() {
+            shell.process_char(c).unwrap();
+        }
+        shell.process_char('\n').unwrap();
+        shell.io().output()
+    }
+
+    #[test]
+    fn test_reauth_prompt_then_dispatch() {
+        let provider = provider();
+        let mut shell = Shell::new(&TREE, MockHandler, &provider, MockIo::new());
+        shell.activate().unwrap();
+        send(&mut shell, "guest:guest123");
+
+        let output = send(&mut shell, "reboot");
+        assert!(
+            output.contains(DefaultConfig::MSG_REAUTH_PROMPT),
+            "{}",
+            output
+        );
+        assert!(!output.contains("Rebooting"), "{}", output);
+
+        // Password is fully masked
+        shell.io_mut().clear_output();
+        for c in "guest123".chars() {
+            shell.process_char(c).unwrap();
+        }
+        assert_eq!(shell.io().output(), "********");
+
+        shell.process_char('\n').unwrap();
+        let output = shell.io().output();
+        assert!(output.contains("Rebooting..."), "{}", output);
+        assert!(output.contains("guest@/> "), "{}", output);
+    }
+
+    #[test]
+    fn test_reauth_wrong_password() {
+        let provider = provider();
+        let mut shell = Shell::new(&TREE, MockHandler, &provider, MockIo::new());
+        shell.activate().unwrap();
+        send(&mut shell, "guest:guest123");
+
+        send(&mut shell, "reboot");
+        let output = send(&mut shell, "nope");
+        assert!(
+            output.contains("Error: Re-authentication failed"),
+            "{}",
+            output
+        );
+        assert!(!output.contains("Rebooting"), "{}", output);
+
+        // Back to normal command processing
+        let output = send(&mut shell, "status");
+        assert!(output.contains("System OK"), "{}", output);
+    }
+
+    #[test]
+    fn test_reauth_failure_audited() {
+        use nut_shell::{AuditEvent, CliError};
+        use std::cell::RefCell;
+
+        std::thread_local! {
+            static LOG: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+        }
+        fn record(event: AuditEvent<'_, MockAccessLevel>) {
+            if let AuditEvent::Command {
+                username,
+                id,
+                arg_count,
+                outcome,
+                ..
+            } = event
+            {
+                let line = format!("{:?} {:?} {} {:?}", username, id, arg_count, outcome);
+                LOG.with(|log| log.borrow_mut().push(line));
+            }
+        }
+
+        let provider = provider();
+        let mut shell = Shell::new(&TREE, MockHandler, &provider, MockIo::new());
+        shell.set_audit_hook(&record);
+        shell.activate().unwrap();
+        send(&mut shell, "guest:guest123");
+
+        // Parking the line is not an attempt yet; the wrong password is
+        send(&mut shell, "reboot");
+        assert!(LOG.with(|log| log.borrow().is_empty()));
+        send(&mut shell, "nope");
+
+        let expected = format!(
+            "{:?} {:?} 0 {:?}",
+            Some("guest"),
+            Some("reboot"),
+            Err::<(), _>(&CliError::ReauthFailed)
+        );
+        assert_eq!(LOG.with(|log| log.borrow().clone()), [expected]);
+    }
+
+    #[test]
+    fn test_reauth_required_every_time() {
+        let provider = provider();
+        let mut shell = Shell::new(&TREE, MockHandler, &provider, MockIo::new());
+        shell.activate().unwrap();
+        send(&mut shell, "guest:guest123");
+
+        send(&mut shell, "reboot");
+        send(&mut shell, "guest123");
+
+        let output = send(&mut shell, "reboot");
+        assert!(
+            output.contains(DefaultConfig::MSG_REAUTH_PROMPT),
+            "{}",
+            output
+        );
+    }
+
+    #[test]
+    fn test_reauth_cancelled_by_double_esc() {
+        let provider = provider();
+        let mut shell = Shell::new(&TREE, MockHandler, &provider, MockIo::new());
+        shell.activate().unwrap();
+        send(&mut shell, "guest:guest123");
+
+        send(&mut shell, "reboot");
+        shell.process_char('\x1b').unwrap();
+        shell.process_char('\x1b').unwrap();
+
+        // Next line is a command again, not a password
+        let output = send(&mut shell, "status");
+        assert!(output.contains("System OK"), "{}", output);
+    }
+
+    #[test]
+    fn test_reauth_dropped_with_privileges() {
+        let provider = provider();
+        let mut shell = Shell::new(&TREE, MockHandler, &provider, MockIo::new());
+        shell.activate().unwrap();
+        send(&mut shell, "guest:guest123");
+
+        send(&mut shell, "reboot");
+        shell.drop_privileges(MockAccessLevel::Guest).unwrap();
+
+        // Password line is an ordinary command now; the parked line never runs
+        let output = send(&mut shell, "guest123");
+        assert!(!output.contains("Rebooting"), "{}", output);
+        assert!(output.contains("Error:"), "{}", output);
+    }
+
+    #[test]
+    fn test_unflagged_command_runs_directly() {
+        let provider = provider();
+        let mut shell = Shell::new(&TREE, MockHandler, &provider, MockIo::new());
+        shell.activate().unwrap();
+        send(&mut shell, "guest:guest123");
+
+        let output = send(&mut shell, "status");
+        assert!(output.contains("System OK"), "{}", output);
+        assert!(!output.contains(DefaultConfig::MSG_REAUTH_PROMPT));
+    }
+}
+
+// ============================================================================
+// Session Idle Timeout Tests
+// ============================================================================
+
+mod session_timeout {
+    use super::fixtures::{MockAccessLevel, MockIo, TEST_TREE, test_config};
+    use super::helpers;
+    use nut_shell::Shell;
+    use nut_shell::auth::{ConstCredentialProvider, PasswordHasher, User, password::Sha256Hasher};
+    use nut_shell::config::{DefaultConfig, ShellConfig};
+    use nut_shell::error::CliError;
+    use nut_shell::response::Response;
+    use nut_shell::shell::CommandHandler;
+
+    test_config!(TimeoutConfig {
+        const SESSION_TIMEOUT_MS: u32 = 1000;
+    });
+
+    struct NoopHandler;
+
+    impl CommandHandler<TimeoutConfig> for NoopHandler {
+        fn execute_sync(
+            &self,
+            _id: &str,
+            _args: &[&str],
+        ) -> Result<Response<TimeoutConfig>, CliError> {
+            Ok(Response::success("OK"))
+        }
+
+        #[cfg(feature = "async")]
+        async fn execute_async(
+            &self,
+            _id: &str,
+            _args: &[&str],
+            _cancel: &nut_shell::CancelToken,
+        ) -> Result<Response<TimeoutConfig>, CliError> {
+            Ok(Response::success("OK"))
+        }
+    }
+
+    fn provider() -> ConstCredentialProvider<MockAccessLevel, Sha256Hasher, 1> {
+        let hasher = Sha256Hasher::new();
+        let salt = [5u8; 16];
+        let hash = hasher.hash("admin123", &salt);
+        let users = [User::new("admin", MockAccessLevel::Admin, hash, salt).unwrap()];
+        ConstCredentialProvider::new(users, hasher)
+    }
+
+    fn login(shell: &mut Shell<'_, MockAccessLevel, MockIo, NoopHandler, TimeoutConfig>) {
+        for c in "admin:admin123\n".chars() {
+            shell.process_char(c).unwrap();
+        }
+        assert!(
+            shell
+                .io()
+                .output()
+                .contains(TimeoutConfig::MSG_LOGIN_SUCCESS)
+        );
+        shell.io_mut().clear_output();
+    }
+
+    #[test]
+    fn test_idle_timeout_logs_out() {
+        let provider = provider();
+        let mut shell = Shell::new(&TEST_TREE, NoopHandler, &provider, MockIo::new());
+        shell.activate().unwrap();
+        login(&mut shell);
+
+        shell.tick(600).unwrap();
+        assert!(shell.io().output().is_empty());
+
+        shell.tick(400).unwrap();
+        let output = shell.io().output();
+        assert!(output.contains(TimeoutConfig::MSG_TIMEOUT), "{}", output);
+        assert!(
+            output.ends_with(TimeoutConfig::MSG_LOGIN_PROMPT),
+            "{}",
+            output
+        );
+
+        // Next line is treated as a login attempt again
+        shell.io_mut().clear_output();
+        for c in "ls\n".chars() {
+            shell.process_char(c).unwrap();
+        }
+        assert!(
+            shell
+                .io()
+                .output()
+                .contains(TimeoutConfig::MSG_INVALID_LOGIN_FORMAT)
+        );
+    }
+
+    #[test]
+    fn test_input_resets_idle_timer() {
+        let provider = provider();
+        let mut shell = Shell::new(&TEST_TREE, NoopHandler, &provider, MockIo::new());
+        shell.activate().unwrap();
+        login(&mut shell);
+
+        shell.tick(900).unwrap();
+        shell.process_char('l').unwrap();
+        shell.io_mut().clear_output();
+        shell.tick(900).unwrap();
+        assert!(shell.io().output().is_empty());
+
+        shell.tick(100).unwrap();
+        assert!(shell.io().output().contains(TimeoutConfig::MSG_TIMEOUT));
+    }
+
+    #[test]
+    fn test_tick_ignored_while_logged_out() {
+        let provider = provider();
+        let mut shell = Shell::new(&TEST_TREE, NoopHandler, &provider, MockIo::new());
+        shell.activate().unwrap();
+        shell.io_mut().clear_output();
+
+        shell.tick(5000).unwrap();
+        assert!(shell.io().output().is_empty());
+    }
+
+    #[test]
+    fn test_timeout_disabled_by_default() {
+        let mut shell = helpers::create_auth_shell();
+        helpers::execute_command_auth(&mut shell, "admin:admin123");
+        shell.io_mut().clear_output();
+
+        shell.tick(u32::MAX).unwrap();
+        shell.tick(u32::MAX).unwrap();
+        assert_eq!(DefaultConfig::SESSION_TIMEOUT_MS, 0);
+        assert!(shell.io().output().is_empty());
+    }
+}
+
+// ============================================================================
+// Mask Character Tests
+// ============================================================================
+
+mod mask_char {
+    use super::fixtures::{MockAccessLevel, MockIo, TEST_TREE, test_config};
+    use nut_shell::Shell;
+    use nut_shell::auth::{ConstCredentialProvider, PasswordHasher, User, password::Sha256Hasher};
+    use nut_shell::config::ShellConfig;
+    use nut_shell::error::CliError;
+    use nut_shell::response::Response;
+    use nut_shell::shell::CommandHandler;
+
+    test_config!(BulletConfig {
+        const MASK_CHAR: Option<char> = Some('•');
+    });
+    test_config!(SilentConfig {
+        const MASK_CHAR: Option<char> = None;
+    });
+    test_config!(RevealConfig {
+        const MASK_CHAR: Option<char> = Some('*');
+        const MASK_REVEAL_LAST: bool = true;
+    });
+
+    struct AnyHandler;
+
+    impl<C: ShellConfig> CommandHandler<C> for AnyHandler {
+        fn execute_sync(&self, _id: &str, _args: &[&str]) -> Result<Response<C>, CliError> {
+            Ok(Response::success("OK"))
+        }
+
+        #[cfg(feature = "async")]
+        async fn execute_async(
+            &self,
+            _id: &str,
+            _args: &[&str],
+            _cancel: &nut_shell::CancelToken,
+        ) -> Result<Response<C>, CliError> {
+            Ok(Response::success("OK"))
+        }
+    }
+
+    fn provider() -> ConstCredentialProvider<MockAccessLevel, Sha256Hasher, 1> {
+        let hasher = Sha256Hasher::new();
+        let salt = [5u8; 16];
+        let hash = hasher.hash("admin123", &salt);
+        let users = [User::new("admin", MockAccessLevel::Admin, hash, salt).unwrap()];
+        ConstCredentialProvider::new(users, hasher)
+    }
+
+    #[test]
+    fn test_custom_mask_char() {
+        let provider = provider();
+        let mut shell: Shell<_, _, _, BulletConfig> =
+            Shell::new(&TEST_TREE, AnyHandler, &provider, MockIo::new());
+        shell.activate().unwrap();
+        shell.io_mut().clear_output();
+
+        for c in "admin:pass".chars() {
+            shell.process_char(c).unwrap();
+        }
+        assert_eq!(shell.io().output(), "admin:••••");
+    }
+
+    #[test]
+    fn test_silent_mask_echoes_nothing() {
+        let provider = provider();
+        let mut shell: Shell<_, _, _, SilentConfig> =
+            Shell::new(&TEST_TREE, AnyHandler, &provider, MockIo::new());
+        shell.activate().unwrap();
+        shell.io_mut().clear_output();
+
+        for c in "admin:pass".chars() {
+            shell.process_char(c).unwrap();
+        }
+        assert_eq!(shell.io().output(), "admin:");
+
+        // Backspace over hidden password chars emits nothing (cursor never moved)
+        shell.io_mut().clear_output();
+        for _ in 0..4 {
+            shell.process_char('\x08').unwrap();
+        }
+        assert_eq!(shell.io().output(), "");
+
+        // Backspace over the visible delimiter still erases it
+        shell.process_char('\x08').unwrap();
+        assert_eq!(shell.io().output(), "\x08 \x08");
+    }
+
+    #[test]
+    fn test_reveal_last_masks_on_next_key() {
+        let provider = provider();
+        let mut shell: Shell<_, _, _, RevealConfig> =
+            Shell::new(&TEST_TREE, AnyHandler, &provider, MockIo::new());
+        shell.activate().unwrap();
+        shell.io_mut().clear_output();
+
+        for c in "admin:ab".chars() {
+            shell.process_char(c).unwrap();
+        }
+        assert_eq!(shell.io().output(), "admin:a\x08*b");
+
+        // Backspace erases the revealed char; the one before stays masked
+        shell.io_mut().clear_output();
+        shell.process_char('\x08').unwrap();
+        shell.process_char('c').unwrap();
+        assert_eq!(shell.io().output(), "\x08 \x08c");
+    }
+
+    #[test]
+    fn test_reveal_last_masked_on_submit() {
+        let provider = provider();
+        let mut shell: Shell<_, _, _, RevealConfig> =
+            Shell::new(&TEST_TREE, AnyHandler, &provider, MockIo::new());
+        shell.activate().unwrap();
+        shell.io_mut().clear_output();
+
+        for c in "admin:admin123\n".chars() {
+            shell.process_char(c).unwrap();
+        }
+        let output = shell.io().output();
+        assert!(output.starts_with("admin:a\x08*d\x08*"), "{}", output);
+        assert!(output.contains("3\x08*\r\n"), "{}", output);
+        assert!(
+            output.contains(RevealConfig::MSG_LOGIN_SUCCESS),
+            "{}",
+            output
+        );
+
+        // Logged in: command input is never masked
+        shell.io_mut().clear_output();
+        for c in "ls".chars() {
+            shell.process_char(c).unwrap();
+        }
+        assert_eq!(shell.io().output(), "ls");
+    }
+
+    #[test]
+    fn test_silent_mask_login_still_works() {
+        let provider = provider();
+        let mut shell: Shell<_, _, _, SilentConfig> =
+            Shell::new(&TEST_TREE, AnyHandler, &provider, MockIo::new());
+        shell.activate().unwrap();
+        shell.io_mut().clear_output();
+
+        for c in "admin:admin123\n".chars() {
+            shell.process_char(c).unwrap();
+        }
+        let output = shell.io().output();
+        assert!(
+            output.contains(SilentConfig::MSG_LOGIN_SUCCESS),
+            "{}",
+            output
+        );
+        assert!(!output.contains("admin123"), "{}", output);
+    }
+}
+
+// ============================================================================
+// Access Denied Reporting Tests
+// ============================================================================
+
+mod access_denied {
+    use super::fixtures::{MockAccessLevel, MockIo, TEST_TREE, test_config};
+    use nut_shell::Shell;
+    use nut_shell::auth::{ConstCredentialProvider, PasswordHasher, User, password::Sha256Hasher};
+    use nut_shell::config::ShellConfig;
+    use nut_shell::error::CliError;
+    use nut_shell::response::Response;
+    use nut_shell::shell::CommandHandler;
+
+    test_config!(RevealConfig {
+        const REVEAL_ACCESS_DENIED: bool = true;
+    });
+    test_config!(HiddenConfig {
+        const REVEAL_ACCESS_DENIED: bool = false;
+    });
+
+    struct AnyHandler;
+
+    impl<C: ShellConfig> CommandHandler<C> for AnyHandler {
+        fn execute_sync(&self, _id: &str, _args: &[&str]) -> Result<Response<C>, CliError> {
+            Ok(Response::success("OK"))
+        }
+
+        #[cfg(feature = "async")]
+        async fn execute_async(
+            &self,
+            _id: &str,
+            _args: &[&str],
+            _cancel: &nut_shell::CancelToken,
+        ) -> Result<Response<C>, CliError> {
+            Ok(Response::success("OK"))
+        }
+    }
+
+    fn provider() -> ConstCredentialProvider<MockAccessLevel, Sha256Hasher, 1> {
+        let hasher = Sha256Hasher::new();
+        let salt = [6u8; 16];
+        let hash = hasher.hash("user123", &salt);
+        let users = [User::new("user", MockAccessLevel::User, hash, salt).unwrap()];
+        ConstCredentialProvider::new(users, hasher)
+    }
+
+    fn run<C: ShellConfig>(
+        shell: &mut Shell<'_, MockAccessLevel, MockIo, AnyHandler, C>,
+        line: &str,
+    ) -> heapless::String<1024> {
+        shell.io_mut().clear_output();
+        for c in line.chars() {
+            shell.process_char(c).unwrap();
+        }
+        shell.process_char('\n').unwrap();
+        shell.io().output()
+    }
+
+    #[test]
+    fn test_reveal_reports_required_level() {
+        let provider = provider();
+        let mut shell: Shell<_, _, _, RevealConfig> =
+            Shell::new(&TEST_TREE, AnyHandler, &provider, MockIo::new());
+        shell.activate().unwrap();
+        run(&mut shell, "user:user123");
+
+        let output = run(&mut shell, "system/reboot");
+        assert!(
+            output.contains("Permission denied (requires Admin)"),
+            "{}",
+            output
+        );
+
+        let output = run(&mut shell, "debug");
+        assert!(
+            output.contains("Permission denied (requires Admin)"),
+            "{}",
+            output
+        );
+    }
+
+    #[test]
+    fn test_default_hides_inaccessible_nodes() {
+        let provider = provider();
+        let mut shell: Shell<_, _, _, HiddenConfig> =
+            Shell::new(&TEST_TREE, AnyHandler, &provider, MockIo::new());
+        shell.activate().unwrap();
+        run(&mut shell, "user:user123");
+
+        let output = run(&mut shell, "system/reboot");
+        assert!(!output.contains("Permission denied"), "{}", output);
+        assert!(!output.contains("Admin"), "{}", output);
+    }
+}
+
+// ============================================================================
+// Disconnect Tests
+// ============================================================================
+
+mod disconnect {
+    use super::helpers::{
+        self,
+        fixtures::{MockHandler, MockIo, TEST_TREE},
+    };
+    use nut_shell::config::{DefaultConfig, ShellConfig};
+    use nut_shell::{CharIo, CliState, ReadState, Shell};
+
+    /// MockIo behind a link that can be dropped.
+    struct LinkIo {
+        inner: MockIo,
+        connected: bool,
+    }
+
+    impl CharIo for LinkIo {
+        type Error = ();
+
+        fn get_char(&mut self) -> Result<Option<char>, Self::Error> {
+            self.inner.get_char()
+        }
+
+        fn read(&mut self) -> Result<ReadState, Self::Error> {
+            if !self.connected {
+                return Ok(ReadState::Disconnected);
+            }
+            self.get_char().map(ReadState::from)
+        }
+
+        fn put_char(&mut self, c: char) -> Result<(), Self::Error> {
+            self.inner.put_char(c)
+        }
+    }
+
+    #[test]
+    fn test_disconnect_logs_out_and_deactivates() {
+        let io = LinkIo {
+            inner: MockIo::with_input("admin:admin123\ncd system\n"),
+            connected: true,
+        };
+        let mut shell: Shell<_, _, _, DefaultConfig> =
+            Shell::new(&TEST_TREE, MockHandler, helpers::auth_provider(), io);
+        shell.activate().unwrap();
+        while !shell.io().inner.input_empty() {
+            shell.poll().unwrap();
+        }
+        assert_eq!(shell.current_username(), Some("admin"));
+
+        // Half-typed line and escape sequence are dropped with the session
+        shell.io_mut().inner.push_input("reb\x1b[");
+        for _ in 0..5 {
+            shell.poll().unwrap();
+        }
+        shell.io_mut().inner.clear_output();
+        shell.io_mut().connected = false;
+        shell.poll().unwrap();
+
+        assert_eq!(shell.state(), CliState::Inactive);
+        assert_eq!(shell.current_username(), None);
+        assert!(shell.io().inner.output().is_empty());
+
+        // Reconnecting peer must log in again, starting at root (no second welcome)
+        shell.io_mut().connected = true;
+        shell.activate_quiet().unwrap();
+        assert_eq!(shell.state(), CliState::LoggedOut);
+        assert_eq!(shell.io().inner.output(), DefaultConfig::MSG_LOGIN_PROMPT);
+        shell.io_mut().inner.push_input("admin:admin123\n");
+        while !shell.io().inner.input_empty() {
+            shell.poll().unwrap();
+        }
+        assert!(shell.io().inner.output().ends_with("admin@/> "));
+    }
+}
+
+// ============================================================================
+// Privilege Drop Tests
+// ============================================================================
+
+mod drop_privileges {
+    use super::helpers::{
+        self,
+        fixtures::{MockAccessLevel, MockHandler, MockIo},
+    };
+    use nut_shell::config::DefaultConfig;
+    use nut_shell::{CliError, Shell};
+
+    fn run(
+        shell: &mut Shell<'static, MockAccessLevel, MockIo, MockHandler, DefaultConfig>,
+        line: &str,
+    ) -> String {
+        shell.io_mut().clear_output();
+        helpers::type_input_auth(shell, line);
+        shell.process_char('\n').unwrap();
+        shell.io().output().to_string()
+    }
+
+    #[test]
+    fn test_drop_privileges_lowers_level_for_session() {
+        let mut shell = helpers::create_auth_shell();
+        run(&mut shell, "admin:admin123");
+        let output = run(&mut shell, "cd debug");
+        assert!(output.ends_with("admin@/debug> "), "{:?}", output);
+
+        shell.drop_privileges(MockAccessLevel::User).unwrap();
+        assert_eq!(shell.current_access_level(), Some(MockAccessLevel::User));
+        assert_eq!(shell.current_username(), Some("admin"));
+
+        // Admin-only directory was left; admin commands are gone
+        let output = run(&mut shell, "");
+        assert!(output.ends_with("admin@/> "), "{:?}", output);
+        let output = run(&mut shell, "system/reboot");
+        assert!(!output.contains("Rebooting"), "{:?}", output);
+
+        // Further drops are fine, regaining privileges is not
+        assert_eq!(
+            shell.drop_privileges(MockAccessLevel::Admin),
+            Err(CliError::PermissionDenied { required: "Admin" })
+        );
+        shell.drop_privileges(MockAccessLevel::Guest).unwrap();
+        assert_eq!(shell.current_access_level(), Some(MockAccessLevel::Guest));
+
+        // Next login starts with the account's own level
+        run(&mut shell, "logout");
+        run(&mut shell, "admin:admin123");
+        assert_eq!(shell.current_access_level(), Some(MockAccessLevel::Admin));
+    }
+
+    #[test]
+    fn test_restore_session_leaves_inaccessible_path() {
+        let mut admin = helpers::create_auth_shell();
+        run(&mut admin, "admin:admin123");
+        run(&mut admin, "cd debug");
+        let state = admin.export_session();
+
+        // Restored under a user who may not enter debug/ - back at root
+        let mut shell = helpers::create_auth_shell();
+        run(&mut shell, "guest:guest123");
+        shell.restore_session(state.clone()).unwrap();
+        let output = run(&mut shell, "");
+        assert!(output.ends_with("guest@/> "), "{:?}", output);
+
+        // The admin keeps the directory
+        run(&mut admin, "logout");
+        run(&mut admin, "admin:admin123");
+        admin.restore_session(state).unwrap();
+        let output = run(&mut admin, "");
+        assert!(output.ends_with("admin@/debug> "), "{:?}", output);
+    }
+
+    #[test]
+    fn test_drop_privileges_requires_login() {
+        let mut shell = helpers::create_auth_shell();
+        assert_eq!(
+            shell.drop_privileges(MockAccessLevel::Guest),
+            Err(CliError::NotAuthenticated)
+        );
+    }
+}
+
+// ============================================================================
+// Paging Tests
+// ============================================================================
+
+#[cfg(feature = "paging")]
+mod paging {
+    use super::fixtures::{MockAccessLevel, MockIo, TEST_TREE, test_config};
+    use nut_shell::Shell;
+    use nut_shell::auth::{ConstCredentialProvider, PasswordHasher, User, password::Sha256Hasher};
+    use nut_shell::config::ShellConfig;
+    use nut_shell::error::CliError;
+    use nut_shell::response::Response;
+    use nut_shell::shell::CommandHandler;
+
+    test_config!(PageConfig {
+        const PAGE_SIZE: usize = 2;
+        const SESSION_TIMEOUT_MS: u32 = 1000;
+    });
+
+    /// Every command replies with two pages, the second one privileged.
+    struct SecretHandler;
+
+    impl<C: ShellConfig> CommandHandler<C> for SecretHandler {
+        fn execute_sync(&self, _id: &str, _args: &[&str]) -> Result<Response<C>, CliError> {
+            Ok(Response::success("P1\r\nP2\r\nSECRET3\r\nSECRET4").indented())
+        }
+
+        #[cfg(feature = "async")]
+        async fn execute_async(
+            &self,
+            id: &str,
+            args: &[&str],
+            _cancel: &nut_shell::CancelToken,
+        ) -> Result<Response<C>, CliError> {
+            self.execute_sync(id, args)
+        }
+    }
+
+    fn provider() -> ConstCredentialProvider<MockAccessLevel, Sha256Hasher, 1> {
+        let hasher = Sha256Hasher::new();
+        let salt = [5u8; 16];
+        let hash = hasher.hash("admin123", &salt);
+        let users = [User::new("admin", MockAccessLevel::Admin, hash, salt).unwrap()];
+        ConstCredentialProvider::new(users, hasher)
+    }
+
+    fn type_line(
+        shell: &mut Shell<'_, MockAccessLevel, MockIo, SecretHandler, PageConfig>,
+        line: &str,
+    ) {
+        for c in line.chars() {
+            shell.process_char(c).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_session_timeout_drops_paused_output() {
+        let provider = provider();
+        let mut shell = Shell::new(&TEST_TREE, SecretHandler, &provider, MockIo::new());
+        shell.activate().unwrap();
+        type_line(&mut shell, "admin:admin123\necho\n");
+        assert!(shell.io().output().ends_with("  P2\r\n-- More --"));
+
+        // Timed out at the pager: the rest of the output never reaches the login prompt
+        shell.tick(1000).unwrap();
+        assert!(shell.io().output().ends_with(PageConfig::MSG_LOGIN_PROMPT));
+        shell.io_mut().clear_output();
+        shell.process_char(' ').unwrap();
+        assert_eq!(shell.io().output(), " ");
+
+        // Keys are login input again, and the next session starts unpaged
+        type_line(&mut shell, "\x08admin:admin123\n");
+        assert!(!shell.io().output().contains("SECRET"));
+        assert!(shell.io().output().ends_with("admin@/> "));
+    }
+
+    #[test]
+    fn test_drop_privileges_drops_paused_output() {
+        let provider = provider();
+        let mut shell = Shell::new(&TEST_TREE, SecretHandler, &provider, MockIo::new());
+        shell.activate().unwrap();
+        type_line(&mut shell, "admin:admin123\necho\n");
+        assert!(shell.io().output().ends_with("-- More --"));
+
+        // Output produced at the old level is not shown at the new one
+        shell.drop_privileges(MockAccessLevel::User).unwrap();
+        shell.io_mut().clear_output();
+        shell.process_char(' ').unwrap();
+        assert_eq!(shell.io().output(), " ");
+    }
+}
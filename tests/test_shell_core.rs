@@ -345,6 +345,208 @@ fn test_global_commands() {
     }
 }
 
+#[test]
+#[cfg(not(feature = "authentication"))]
+fn test_ls_glob_filters_listing() {
+    let mut shell = helpers::create_test_shell();
+
+    let output = helpers::execute_command(&mut shell, "ls sys*");
+    assert!(output.contains("system/"), "{}", output);
+    assert!(!output.contains("echo"), "{}", output);
+
+    let output = helpers::execute_command(&mut shell, "ls ?ch?");
+    assert!(output.contains("echo"), "{}", output);
+    assert!(!output.contains("system"), "{}", output);
+
+    let output = helpers::execute_command(&mut shell, "ls zzz*");
+    assert!(!output.contains(" - "), "{}", output);
+}
+
+#[test]
+#[cfg(not(feature = "authentication"))]
+fn test_ls_path_lists_without_navigating() {
+    let mut shell = helpers::create_test_shell();
+
+    for arg in ["system", "system/", "/system", "./system/"] {
+        let output = helpers::execute_command(&mut shell, &format!("ls {}", arg));
+        helpers::assert_contains_all(&output, &["status", "network/", "hardware/"]);
+        assert!(!output.contains("echo"), "{}", output);
+        assert!(output.ends_with("@/> "), "{}", output);
+    }
+
+    // Path plus pattern in the final segment
+    let output = helpers::execute_command(&mut shell, "ls system/net*");
+    assert!(output.contains("network/"), "{}", output);
+    assert!(!output.contains("hardware"), "{}", output);
+
+    // Relative to the current directory
+    helpers::execute_command(&mut shell, "system");
+    let output = helpers::execute_command(&mut shell, "ls ../");
+    assert!(output.contains("echo"), "{}", output);
+    assert!(output.ends_with("@/system> "), "{}", output);
+
+    let output = helpers::execute_command(&mut shell, "ls status");
+    assert!(
+        output.contains("Error: status is not a directory"),
+        "{}",
+        output
+    );
+    let output = helpers::execute_command(&mut shell, "ls nowhere");
+    assert!(output.contains("Error:"), "{}", output);
+}
+
+#[test]
+#[cfg(not(feature = "authentication"))]
+fn test_cd_enters_directories_only() {
+    let mut shell = helpers::create_test_shell();
+
+    let output = helpers::execute_command(&mut shell, "cd system/network/");
+    assert!(output.ends_with("@/system/network> "), "{}", output);
+
+    let output = helpers::execute_command(&mut shell, "cd ..");
+    assert!(output.ends_with("@/system> "), "{}", output);
+
+    // A command is not entered (nor run)
+    let output = helpers::execute_command(&mut shell, "cd status");
+    assert!(
+        output.contains("Error: status is not a directory"),
+        "{}",
+        output
+    );
+    assert!(!output.contains("System OK"), "{}", output);
+    assert!(output.ends_with("@/system> "), "{}", output);
+
+    let output = helpers::execute_command(&mut shell, "cd");
+    assert!(output.ends_with("@/> "), "{}", output);
+
+    // Commands can't appear mid-path either
+    let output = helpers::execute_command(&mut shell, "system/status/more");
+    assert!(
+        output.contains("Error: status is not a directory"),
+        "{}",
+        output
+    );
+}
+
+#[test]
+#[cfg(feature = "authentication")]
+fn test_ls_glob_respects_access() {
+    let mut shell = helpers::create_auth_shell();
+    helpers::execute_command_auth(&mut shell, "guest:guest123");
+    let output = helpers::execute_command_auth(&mut shell, "ls de*");
+    assert!(!output.contains("debug"), "{}", output);
+
+    helpers::execute_command_auth(&mut shell, "logout");
+    helpers::execute_command_auth(&mut shell, "admin:admin123");
+    let output = helpers::execute_command_auth(&mut shell, "ls de*");
+    assert!(output.contains("debug/"), "{}", output);
+}
+
+#[cfg(not(feature = "authentication"))]
+mod hidden_tree {
+    use super::helpers::fixtures::{CMD_ECHO, CMD_REBOOT, MockAccessLevel};
+    use nut_shell::tree::{CommandKind, CommandMeta, Directory, Node};
+
+    pub const CMD_DIAG: CommandMeta<MockAccessLevel> =
+        CommandMeta::new("status", "diag", MockAccessLevel::Guest, CommandKind::Sync)
+            .with_description("Maintenance diagnostics")
+            .hidden();
+
+    pub const DIR_FACTORY: Directory<MockAccessLevel> = Directory::new(
+        "factory",
+        &[Node::Command(&CMD_REBOOT)],
+        MockAccessLevel::Guest,
+    )
+    .hidden();
+
+    pub const TREE: Directory<MockAccessLevel> = Directory::new(
+        "/",
+        &[
+            Node::Command(&CMD_ECHO),
+            Node::Command(&CMD_DIAG),
+            Node::Directory(&DIR_FACTORY),
+        ],
+        MockAccessLevel::Guest,
+    );
+}
+
+#[test]
+#[cfg(not(feature = "authentication"))]
+fn test_hidden_nodes_omitted_from_ls_but_resolvable() {
+    let mut shell = nut_shell::Shell::new(
+        &hidden_tree::TREE,
+        helpers::fixtures::MockHandler,
+        helpers::fixtures::MockIo::new(),
+    );
+    shell.activate().unwrap();
+
+    let output = helpers::execute_command(&mut shell, "ls");
+    helpers::assert_contains_all(&output, &["echo"]);
+    helpers::assert_contains_none(&output, &["diag", "factory"]);
+
+    // Typed explicitly, hidden nodes still resolve
+    let output = helpers::execute_command(&mut shell, "diag");
+    assert!(output.contains("System OK"), "{}", output);
+
+    let output = helpers::execute_command(&mut shell, "factory/reboot");
+    assert!(output.contains("Rebooting..."), "{}", output);
+}
+
+#[cfg(not(feature = "authentication"))]
+mod menu_tree {
+    use super::helpers::fixtures::{CMD_ECHO, CMD_STATUS, MockAccessLevel};
+    use nut_shell::tree::{Directory, Node};
+
+    pub const DIR_SENSORS: Directory<MockAccessLevel> = Directory::new(
+        "sensors",
+        &[Node::Command(&CMD_STATUS)],
+        MockAccessLevel::Guest,
+    )
+    .with_default_command("status");
+
+    pub const TREE: Directory<MockAccessLevel> = Directory::new(
+        "/",
+        &[Node::Command(&CMD_ECHO), Node::Directory(&DIR_SENSORS)],
+        MockAccessLevel::Guest,
+    );
+
+    nut_shell::validate_tree!(TREE);
+}
+
+#[test]
+#[cfg(not(feature = "authentication"))]
+fn test_default_command_runs_on_directory_entry() {
+    let mut shell = nut_shell::Shell::new(
+        &menu_tree::TREE,
+        helpers::fixtures::MockHandler,
+        helpers::fixtures::MockIo::new(),
+    );
+    shell.activate().unwrap();
+
+    let output = helpers::execute_command(&mut shell, "sensors");
+    assert!(output.contains("System OK"), "{}", output);
+    assert!(output.ends_with("@/sensors> "), "{}", output);
+
+    // Directories without a default just navigate
+    let output = helpers::execute_command(&mut shell, "/");
+    assert!(!output.contains("System OK"), "{}", output);
+    assert!(output.ends_with("@/> "), "{}", output);
+}
+
+#[test]
+#[cfg(not(feature = "authentication"))]
+fn test_session_state_getters() {
+    use nut_shell::CliState;
+
+    let mut shell = helpers::create_test_shell();
+    assert_eq!(shell.state(), CliState::LoggedIn);
+    assert_eq!(shell.current_username(), None);
+    assert_eq!(shell.current_access_level(), None);
+
+    helpers::execute_command(&mut shell, "quit");
+    assert_eq!(shell.state(), CliState::Inactive);
+}
+
 // ============================================================================
 // Command Argument Validation Tests
 // ============================================================================
@@ -370,6 +572,11 @@ fn test_argument_validation() {
             "Expected 0 arguments, got 1",
             "command accepts 0 args but got 1",
         ),
+        (
+            "echo a b c d e f g h i j k l m n o p q",
+            "Too many arguments",
+            "line exceeds argument capacity",
+        ),
     ];
 
     for (cmd, expected, description) in test_cases {
@@ -465,3 +672,1275 @@ fn test_minimal_features() {
         assert!(output.contains("minimal"));
     }
 }
+
+// ============================================================================
+// Audit Hook Tests
+// ============================================================================
+
+mod audit {
+    use super::helpers;
+    use super::helpers::fixtures::MockAccessLevel;
+    use nut_shell::AuditEvent;
+    use std::cell::RefCell;
+
+    std::thread_local! {
+        static LOG: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    }
+
+    /// Record events as compact strings (thread-local, tests run in parallel).
+    fn record(event: AuditEvent<'_, MockAccessLevel>) {
+        let line = match event {
+            AuditEvent::Command {
+                username,
+                id,
+                command_path,
+                arg_count,
+                outcome,
+                ..
+            } => format!(
+                "cmd user={} id={} path={} args={} ok={}",
+                username.unwrap_or("-"),
+                id.unwrap_or("-"),
+                command_path.map_or("-".to_string(), |p| format!("/{}", p.join("/"))),
+                arg_count,
+                outcome.is_ok()
+            ),
+            #[cfg(feature = "authentication")]
+            AuditEvent::Login {
+                username,
+                success,
+                user_found,
+            } => format!(
+                "login user={} ok={} found={}",
+                username, success, user_found
+            ),
+        };
+        LOG.with(|log| log.borrow_mut().push(line));
+    }
+
+    fn take_log() -> Vec<String> {
+        LOG.with(|log| log.borrow_mut().drain(..).collect())
+    }
+
+    #[test]
+    #[cfg(not(feature = "authentication"))]
+    fn test_audit_records_command_attempts() {
+        let mut shell = helpers::create_test_shell();
+        shell.set_audit_hook(&record);
+        take_log();
+
+        helpers::execute_command(&mut shell, "echo a b");
+        helpers::execute_command(&mut shell, "nonexistent 1");
+        helpers::execute_command(&mut shell, "system/reboot now");
+        // Navigation and global commands are not audited
+        helpers::execute_command(&mut shell, "system");
+        helpers::execute_command(&mut shell, "ls");
+
+        assert_eq!(
+            take_log(),
+            [
+                "cmd user=- id=echo path=/echo args=2 ok=true".to_string(),
+                "cmd user=- id=- path=- args=1 ok=false".to_string(),
+                "cmd user=- id=reboot path=/system/reboot args=1 ok=false".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "authentication")]
+    fn test_audit_records_logins_and_commands() {
+        let mut shell = helpers::create_auth_shell();
+        shell.set_audit_hook(&record);
+        take_log();
+
+        helpers::execute_command_auth(&mut shell, "nobody:admin123");
+        helpers::execute_command_auth(&mut shell, "admin:wrong");
+        helpers::execute_command_auth(&mut shell, "admin:admin123");
+        helpers::execute_command_auth(&mut shell, "echo hi");
+
+        assert_eq!(
+            take_log(),
+            [
+                "login user=nobody ok=false found=false".to_string(),
+                "login user=admin ok=false found=true".to_string(),
+                "login user=admin ok=true found=true".to_string(),
+                "cmd user=admin id=echo path=/echo args=1 ok=true".to_string(),
+            ]
+        );
+    }
+}
+
+// ============================================================================
+// Exit Code Tests
+// ============================================================================
+
+#[cfg(not(feature = "authentication"))]
+mod exit_codes {
+    use super::helpers::fixtures::{MockIo, TEST_TREE, test_config};
+    use nut_shell::config::ShellConfig;
+    use nut_shell::{CliError, CommandHandler, Response, Shell};
+
+    test_config!(ExitCodeConfig {
+        const SHOW_EXIT_CODE: bool = true;
+    });
+
+    /// `echo` exits with its first argument as code.
+    struct ExitCodeHandler;
+
+    impl<C: ShellConfig> CommandHandler<C> for ExitCodeHandler {
+        fn execute_sync(&self, _id: &str, args: &[&str]) -> Result<Response<C>, CliError> {
+            let code = args.first().and_then(|a| a.parse().ok()).unwrap_or(0);
+            Ok(Response::failure("done", code))
+        }
+
+        #[cfg(feature = "async")]
+        async fn execute_async(
+            &self,
+            id: &str,
+            args: &[&str],
+            _cancel: &nut_shell::CancelToken,
+        ) -> Result<Response<C>, CliError> {
+            self.execute_sync(id, args)
+        }
+    }
+
+    fn run<C: ShellConfig>(
+        shell: &mut Shell<
+            '_,
+            super::helpers::fixtures::MockAccessLevel,
+            MockIo,
+            ExitCodeHandler,
+            C,
+        >,
+        line: &str,
+    ) -> heapless::String<1024> {
+        shell.io_mut().clear_output();
+        for c in line.chars() {
+            shell.process_char(c).unwrap();
+        }
+        shell.io_mut().output()
+    }
+
+    #[test]
+    fn test_last_exit_code_tracks_commands() {
+        let mut shell: Shell<_, _, _, nut_shell::DefaultConfig> =
+            Shell::new(&TEST_TREE, ExitCodeHandler, MockIo::new());
+        shell.activate().unwrap();
+        assert_eq!(shell.last_exit_code(), 0);
+
+        let output = run(&mut shell, "echo 3\n");
+        assert_eq!(shell.last_exit_code(), 3);
+        // SHOW_EXIT_CODE disabled by default
+        assert!(!output.contains("[3]"), "{}", output);
+
+        run(&mut shell, "nonexistent\n");
+        assert_eq!(shell.last_exit_code(), 1);
+
+        run(&mut shell, "echo\n");
+        assert_eq!(shell.last_exit_code(), 0);
+    }
+
+    #[test]
+    fn test_prompt_shows_nonzero_exit_code() {
+        let mut shell: Shell<_, _, _, ExitCodeConfig> =
+            Shell::new(&TEST_TREE, ExitCodeHandler, MockIo::new());
+        shell.activate().unwrap();
+
+        let output = run(&mut shell, "echo 42\n");
+        assert!(output.ends_with("[42] @/> "), "{:?}", output);
+
+        let output = run(&mut shell, "echo 0\n");
+        assert!(output.ends_with("\r\n@/> "), "{:?}", output);
+    }
+}
+
+// ============================================================================
+// Prompt Emission Tests
+// ============================================================================
+
+#[cfg(not(feature = "authentication"))]
+mod emit_prompt {
+    use super::helpers::fixtures::{MockIo, TEST_TREE, test_config};
+    use nut_shell::config::ShellConfig;
+    use nut_shell::{CliError, CliState, CommandHandler, Response, Shell};
+
+    test_config!(SilentPromptConfig {
+        const EMIT_PROMPT: bool = false;
+    });
+
+    /// Responds with the first argument.
+    struct EchoHandler;
+
+    impl<C: ShellConfig> CommandHandler<C> for EchoHandler {
+        fn execute_sync(&self, _id: &str, args: &[&str]) -> Result<Response<C>, CliError> {
+            Ok(Response::success(args.first().copied().unwrap_or("")))
+        }
+
+        #[cfg(feature = "async")]
+        async fn execute_async(
+            &self,
+            id: &str,
+            args: &[&str],
+            _cancel: &nut_shell::CancelToken,
+        ) -> Result<Response<C>, CliError> {
+            self.execute_sync(id, args)
+        }
+    }
+
+    #[test]
+    fn test_prompt_left_to_caller() {
+        let mut shell: Shell<_, _, _, SilentPromptConfig> =
+            Shell::new(&TEST_TREE, EchoHandler, MockIo::new());
+        assert_eq!(shell.current_prompt(), "");
+
+        shell.activate().unwrap();
+        assert_eq!(shell.state(), CliState::LoggedIn);
+        assert_eq!(shell.io().output(), "Welcome\r\n");
+        assert_eq!(shell.current_prompt(), "@/> ");
+
+        shell.io_mut().clear_output();
+        for c in "echo hi\ncd system\n".chars() {
+            shell.process_char(c).unwrap();
+        }
+        let output = shell.io().output();
+        assert!(output.contains("hi"), "{:?}", output);
+        assert!(!output.contains("> "), "{:?}", output);
+        assert_eq!(shell.current_prompt(), "@/system> ");
+    }
+}
+
+// ============================================================================
+// Empty Enter Hook Tests
+// ============================================================================
+
+#[cfg(not(feature = "authentication"))]
+mod empty_enter {
+    use super::helpers::fixtures::{MockAccessLevel, MockIo, TEST_TREE};
+    use nut_shell::config::{DefaultConfig, ShellConfig};
+    use nut_shell::{CliError, CommandHandler, Response, Shell};
+
+    /// Renders a status line on empty Enter.
+    struct RefreshHandler;
+
+    impl<C: ShellConfig> CommandHandler<C> for RefreshHandler {
+        fn execute_sync(&self, _id: &str, _args: &[&str]) -> Result<Response<C>, CliError> {
+            Ok(Response::success("ran"))
+        }
+
+        #[cfg(feature = "async")]
+        async fn execute_async(
+            &self,
+            id: &str,
+            args: &[&str],
+            _cancel: &nut_shell::CancelToken,
+        ) -> Result<Response<C>, CliError> {
+            self.execute_sync(id, args)
+        }
+
+        fn on_empty_enter(&self) -> Option<Response<C>> {
+            Some(Response::success("temp=21C"))
+        }
+    }
+
+    #[test]
+    fn test_on_empty_enter_renders_response() {
+        let mut shell: Shell<MockAccessLevel, _, _, DefaultConfig> =
+            Shell::new(&TEST_TREE, RefreshHandler, MockIo::new());
+        shell.activate().unwrap();
+
+        for line in ["\n", "   \n"] {
+            shell.io_mut().clear_output();
+            for c in line.chars() {
+                shell.process_char(c).unwrap();
+            }
+            let output = shell.io_mut().output();
+            assert!(output.contains("temp=21C"), "{:?}", output);
+            assert!(output.ends_with("@/> "), "{:?}", output);
+        }
+    }
+
+    #[test]
+    fn test_default_empty_enter_only_redraws_prompt() {
+        let mut shell = super::helpers::create_test_shell();
+        let output = super::helpers::execute_command(&mut shell, "");
+        assert_eq!(output.as_str(), "\r\n@/> ");
+    }
+}
+
+// ============================================================================
+// Programmatic Execution Tests
+// ============================================================================
+
+#[cfg(not(feature = "authentication"))]
+mod execute_line {
+    use super::helpers;
+    use nut_shell::CliError;
+
+    #[test]
+    fn test_execute_line_returns_response_without_io() {
+        let mut shell = helpers::create_test_shell();
+
+        let response = shell.execute_line("echo \"hello world\"").unwrap();
+        assert_eq!(response.message.as_str(), "hello world");
+        assert_eq!(shell.io_mut().output(), "");
+    }
+
+    #[test]
+    fn test_execute_line_navigates_and_reports_errors() {
+        let mut shell = helpers::create_test_shell();
+
+        shell.execute_line("system").unwrap();
+        assert!(shell.execute_line("status").is_ok());
+
+        assert_eq!(
+            shell.execute_line("nonexistent").unwrap_err(),
+            CliError::CommandNotFound
+        );
+        assert!(shell.execute_line("/echo").unwrap().message.is_empty());
+        assert_eq!(shell.io_mut().output(), "");
+    }
+
+    #[test]
+    fn test_would_resolve_is_read_only() {
+        use nut_shell::ResolveKind;
+
+        let mut shell = helpers::create_test_shell();
+        shell.io_mut().clear_output();
+
+        assert_eq!(shell.would_resolve("system"), Ok(ResolveKind::Directory));
+        assert_eq!(
+            shell.would_resolve("system/network/ping host1"),
+            Ok(ResolveKind::Command)
+        );
+        assert_eq!(shell.would_resolve(" ls system"), Ok(ResolveKind::Global));
+        assert_eq!(shell.would_resolve("?"), Ok(ResolveKind::Global));
+        assert_eq!(
+            shell.would_resolve("nonexistent"),
+            Err(CliError::CommandNotFound)
+        );
+        assert_eq!(shell.would_resolve(""), Err(CliError::CommandNotFound));
+
+        // Nothing ran: no output, no navigation
+        assert_eq!(
+            shell.would_resolve("network"),
+            Err(CliError::CommandNotFound)
+        );
+        assert_eq!(shell.io_mut().output(), "");
+    }
+}
+
+// ============================================================================
+// Session Export/Restore Tests
+// ============================================================================
+
+#[test]
+#[cfg(not(feature = "authentication"))]
+fn test_session_survives_shell_rebuild() {
+    let mut old = helpers::create_test_shell();
+    helpers::execute_command(&mut old, "system/network");
+    helpers::execute_command(&mut old, "status");
+    helpers::execute_command(&mut old, "ping host1");
+    let state = old.export_session();
+    drop(old);
+
+    let mut shell = nut_shell::Shell::new(
+        &fixtures::TEST_TREE,
+        fixtures::MockHandler,
+        fixtures::MockIo::new(),
+    );
+    shell.restore_session(state.clone()).unwrap();
+    shell.activate().unwrap();
+    assert!(shell.io().output().ends_with("@/system/network> "));
+
+    #[cfg(feature = "history")]
+    {
+        let entries: Vec<&str> = state.history.iter().collect();
+        assert_eq!(entries, ["status", "ping host1"]);
+
+        shell.io_mut().clear_output();
+        shell.process_char('\x1b').unwrap();
+        shell.process_char('[').unwrap();
+        shell.process_char('A').unwrap();
+        assert!(shell.io().output().ends_with("ping host1"));
+    }
+}
+
+#[test]
+#[cfg(not(feature = "authentication"))]
+fn test_activate_quiet_skips_welcome() {
+    let mut shell = helpers::create_test_shell();
+    helpers::execute_command(&mut shell, "system");
+    let state = shell.export_session();
+    shell.deactivate();
+
+    shell.io_mut().clear_output();
+    shell.restore_session(state).unwrap();
+    shell.activate_quiet().unwrap();
+    assert_eq!(shell.io().output(), "@/system> ");
+    assert_eq!(shell.state(), nut_shell::CliState::LoggedIn);
+}
+
+#[test]
+#[cfg(not(feature = "authentication"))]
+fn test_restore_rejects_stale_path() {
+    let mut shell = helpers::create_test_shell();
+    helpers::execute_command(&mut shell, "system");
+
+    let mut state = nut_shell::SessionState::<nut_shell::DefaultConfig>::default();
+    state.path.push(99).unwrap();
+    assert_eq!(
+        shell.restore_session(state),
+        Err(nut_shell::CliError::InvalidPath)
+    );
+    // Nothing applied
+    assert_eq!(shell.export_session().path.len(), 1);
+}
+
+// ============================================================================
+// Command Context Tests
+// ============================================================================
+
+mod command_context {
+    use super::fixtures::{MockAccessLevel, MockIo, TEST_TREE};
+    use nut_shell::config::DefaultConfig;
+    use nut_shell::shell::{CommandContext, CommandHandler};
+    use nut_shell::{AccessLevel, CliError, Response, Shell};
+
+    /// Reports the context it was dispatched with.
+    struct WhoAmI;
+
+    impl CommandHandler<DefaultConfig> for WhoAmI {
+        fn execute_sync(
+            &self,
+            _id: &str,
+            _args: &[&str],
+        ) -> Result<Response<DefaultConfig>, CliError> {
+            Ok(Response::success("no context"))
+        }
+
+        fn execute_sync_ctx<L: AccessLevel>(
+            &self,
+            ctx: &CommandContext<'_, L>,
+            id: &str,
+            args: &[&str],
+        ) -> Result<Response<DefaultConfig>, CliError> {
+            if id == "echo" {
+                return self.execute_sync(id, args);
+            }
+            let msg = format!(
+                "user={} level={} path=/{} cmd=/{}",
+                ctx.username.unwrap_or("-"),
+                ctx.access_level.map_or("-", |l| l.as_str()),
+                ctx.path.join("/"),
+                ctx.command_path.join("/")
+            );
+            Ok(Response::success(&msg))
+        }
+
+        /// Hardware commands need the (absent) subsystem, and never run from `/system`.
+        fn before_execute<L: AccessLevel>(
+            &self,
+            ctx: &CommandContext<'_, L>,
+            id: &str,
+        ) -> Result<(), CliError> {
+            if id.starts_with("hw_") {
+                return Err(CliError::Unsupported);
+            }
+            if ctx.path == ["system"] && id == "echo" {
+                return Err(CliError::InvalidPath);
+            }
+            Ok(())
+        }
+
+        #[cfg(feature = "async")]
+        async fn execute_async(
+            &self,
+            id: &str,
+            args: &[&str],
+            _cancel: &nut_shell::CancelToken,
+        ) -> Result<Response<DefaultConfig>, CliError> {
+            self.execute_sync(id, args)
+        }
+    }
+
+    fn run(
+        shell: &mut Shell<'_, MockAccessLevel, MockIo, WhoAmI, DefaultConfig>,
+        line: &str,
+    ) -> String {
+        shell.io_mut().clear_output();
+        for c in line.chars().chain(['\n']) {
+            shell.process_char(c).unwrap();
+        }
+        shell.io().output().to_string()
+    }
+
+    #[test]
+    #[cfg(not(feature = "authentication"))]
+    fn test_context_reports_current_and_command_path() {
+        let mut shell: Shell<_, _, _, DefaultConfig> =
+            Shell::new(&TEST_TREE, WhoAmI, MockIo::new());
+        shell.activate().unwrap();
+
+        let output = run(&mut shell, "system/status");
+        assert!(
+            output.contains("user=- level=- path=/ cmd=/system/status\r\n"),
+            "{}",
+            output
+        );
+
+        // Default method forwards to execute_sync()
+        let output = run(&mut shell, "echo");
+        assert!(output.contains("no context"), "{}", output);
+
+        run(&mut shell, "system/network");
+        let output = run(&mut shell, "status");
+        assert!(
+            output.contains("path=/system/network cmd=/system/network/status\r\n"),
+            "{}",
+            output
+        );
+    }
+
+    #[test]
+    #[cfg(not(feature = "authentication"))]
+    fn test_before_execute_can_abort() {
+        let mut shell: Shell<_, _, _, DefaultConfig> =
+            Shell::new(&TEST_TREE, WhoAmI, MockIo::new());
+        shell.activate().unwrap();
+
+        assert_eq!(
+            shell.execute_line("system/hardware/led on").unwrap_err(),
+            CliError::Unsupported
+        );
+        assert!(shell.execute_line("echo").is_ok());
+
+        run(&mut shell, "system");
+        let output = run(&mut shell, "/echo");
+        assert!(output.contains("Invalid path"), "{}", output);
+    }
+
+    #[test]
+    #[cfg(feature = "authentication")]
+    fn test_context_reports_user_and_path() {
+        use nut_shell::auth::{
+            ConstCredentialProvider, PasswordHasher, User, password::Sha256Hasher,
+        };
+
+        let hasher = Sha256Hasher::new();
+        let salt = [9u8; 16];
+        let hash = hasher.hash("pw", &salt);
+        let provider = ConstCredentialProvider::<_, _, 1>::new(
+            [User::new("ops", MockAccessLevel::User, hash, salt).unwrap()],
+            hasher,
+        );
+        let mut shell: Shell<_, _, _, DefaultConfig> =
+            Shell::new(&TEST_TREE, WhoAmI, &provider, MockIo::new());
+        shell.activate().unwrap();
+        run(&mut shell, "ops:pw");
+
+        run(&mut shell, "system");
+        let output = run(&mut shell, "network/status");
+        assert!(
+            output.contains("user=ops level=User path=/system cmd=/system/network/status\r\n"),
+            "{}",
+            output
+        );
+    }
+}
+
+// ============================================================================
+// Output Flush Tests
+// ============================================================================
+
+#[cfg(not(feature = "authentication"))]
+mod flush {
+    use super::helpers::fixtures::{MockAccessLevel, MockHandler, TEST_TREE};
+    use nut_shell::Shell;
+    use nut_shell::config::DefaultConfig;
+    use nut_shell::io::CharIo;
+
+    /// Bounded output buffer that reports overflow and counts flushes.
+    struct BoundedIo {
+        out: heapless::String<64>,
+        flushes: usize,
+    }
+
+    impl CharIo for BoundedIo {
+        type Error = ();
+
+        fn get_char(&mut self) -> Result<Option<char>, ()> {
+            Ok(None)
+        }
+
+        fn put_char(&mut self, c: char) -> Result<(), ()> {
+            self.out.push(c).map_err(|_| ())
+        }
+
+        fn flush(&mut self) -> Result<(), ()> {
+            self.flushes += 1;
+            self.out.clear();
+            Ok(())
+        }
+    }
+
+    fn type_line(
+        shell: &mut Shell<'_, MockAccessLevel, BoundedIo, MockHandler, DefaultConfig>,
+        line: &str,
+    ) -> Result<(), ()> {
+        line.chars().try_for_each(|c| shell.process_char(c))
+    }
+
+    #[test]
+    fn test_response_is_flushed() {
+        let io = BoundedIo {
+            out: heapless::String::new(),
+            flushes: 0,
+        };
+        let mut shell: Shell<_, _, _, DefaultConfig> = Shell::new(&TEST_TREE, MockHandler, io);
+        shell.activate().unwrap();
+        shell.io_mut().out.clear();
+
+        type_line(&mut shell, "echo hi\n").unwrap();
+        assert_eq!(shell.io().flushes, 1);
+        // Only the prompt follows the flushed response
+        assert_eq!(shell.io().out.as_str(), "@/> ");
+    }
+
+    #[test]
+    fn test_write_overflow_is_surfaced() {
+        let io = BoundedIo {
+            out: heapless::String::new(),
+            flushes: 0,
+        };
+        let mut shell: Shell<_, _, _, DefaultConfig> = Shell::new(&TEST_TREE, MockHandler, io);
+        shell.activate().unwrap();
+        shell.io_mut().out.clear();
+
+        let long = "x".repeat(80);
+        assert_eq!(type_line(&mut shell, &long), Err(()));
+    }
+}
+
+// ============================================================================
+// Message of the Day Tests
+// ============================================================================
+
+#[test]
+#[cfg(not(feature = "authentication"))]
+fn test_motd_shown_once_at_activate() {
+    use nut_shell::config::{DefaultConfig, ShellConfig};
+
+    let mut shell = helpers::create_test_shell();
+    shell
+        .set_motd("Firmware 2.1 - calibrate before use")
+        .unwrap();
+    assert_eq!(
+        shell.set_motd(&"x".repeat(nut_shell::config::capacity::MESSAGE + 1)),
+        Err(nut_shell::CliError::BufferFull)
+    );
+
+    helpers::execute_command(&mut shell, "exit");
+    shell.io_mut().clear_output();
+    shell.activate().unwrap();
+    let output = shell.io_mut().output();
+    let welcome = output.find(DefaultConfig::MSG_WELCOME).unwrap();
+    let motd = output.find("Firmware 2.1 - calibrate before use").unwrap();
+    assert!(welcome < motd, "{}", output);
+    assert!(output.ends_with("@/> "), "{}", output);
+
+    // Not repeated after commands
+    let output = helpers::execute_command(&mut shell, "echo hi");
+    assert!(!output.contains("Firmware"), "{}", output);
+
+    // Empty clears it
+    shell.set_motd("").unwrap();
+    helpers::execute_command(&mut shell, "exit");
+    shell.io_mut().clear_output();
+    shell.activate().unwrap();
+    assert!(!shell.io_mut().output().contains("Firmware"));
+}
+
+#[cfg(not(feature = "authentication"))]
+mod arg_spec {
+    use super::fixtures::{MockAccessLevel, MockIo};
+    use nut_shell::config::DefaultConfig;
+    use nut_shell::shell::CommandHandler;
+    use nut_shell::{
+        ArgType, CliError, CommandKind, CommandMeta, Directory, Node, Response, Shell,
+    };
+
+    const POKE: CommandMeta<MockAccessLevel> =
+        CommandMeta::new("poke", "poke", MockAccessLevel::Guest, CommandKind::Sync)
+            .with_description("Write a register")
+            .with_args(2, 3)
+            .with_arg_spec(&[ArgType::Hex, ArgType::UInt]);
+
+    const ROOT: Directory<MockAccessLevel> =
+        Directory::new("/", &[Node::Command(&POKE)], MockAccessLevel::Guest);
+
+    /// Echoes the arguments it was dispatched with.
+    struct Echo;
+
+    impl CommandHandler<DefaultConfig> for Echo {
+        fn execute_sync(
+            &self,
+            _id: &str,
+            args: &[&str],
+        ) -> Result<Response<DefaultConfig>, CliError> {
+            Ok(Response::success(&format!("args={}", args.join(","))))
+        }
+
+        #[cfg(feature = "async")]
+        async fn execute_async(
+            &self,
+            id: &str,
+            args: &[&str],
+            _cancel: &nut_shell::CancelToken,
+        ) -> Result<Response<DefaultConfig>, CliError> {
+            self.execute_sync(id, args)
+        }
+    }
+
+    #[test]
+    fn test_args_checked_before_dispatch() {
+        let mut shell: Shell<_, _, _, DefaultConfig> = Shell::new(&ROOT, Echo, MockIo::new());
+        shell.activate().unwrap();
+
+        let mut run = |line: &str| {
+            shell.io_mut().clear_output();
+            for c in line.chars().chain(['\n']) {
+                shell.process_char(c).unwrap();
+            }
+            shell.io().output().to_string()
+        };
+
+        let output = run("poke 0x40 12 extra");
+        assert!(output.contains("args=0x40,12,extra"), "{}", output);
+
+        let output = run("poke 0x40 -3");
+        assert!(
+            output.contains("Argument 2: expected unsigned integer"),
+            "{}",
+            output
+        );
+        assert!(!output.contains("args="), "{}", output);
+
+        let output = run("poke zz 1");
+        assert!(
+            output.contains("Argument 1: expected hex number"),
+            "{}",
+            output
+        );
+    }
+}
+
+#[test]
+#[cfg(not(feature = "authentication"))]
+fn test_suspended_prompt_redrawn_once_on_resume() {
+    let mut shell = helpers::create_test_shell();
+
+    shell.suspend_prompt();
+    let output = helpers::execute_command(&mut shell, "echo one");
+    assert!(output.contains("one"), "{}", output);
+    assert!(!output.contains("@/> "), "{}", output);
+    helpers::execute_command(&mut shell, "echo two");
+
+    shell.io_mut().clear_output();
+    helpers::type_input(&mut shell, "ec");
+    shell.resume_prompt().unwrap();
+    assert_eq!(shell.io_mut().output(), "ec@/> ec");
+
+    // Resuming again draws nothing; normal redraws are back
+    shell.io_mut().clear_output();
+    shell.resume_prompt().unwrap();
+    assert_eq!(shell.io_mut().output(), "");
+    let output = helpers::execute_command(&mut shell, "ho three");
+    assert!(output.ends_with("@/> "), "{}", output);
+}
+
+// ============================================================================
+// Shell Action Tests
+// ============================================================================
+
+#[cfg(not(feature = "authentication"))]
+mod shell_actions {
+    use super::helpers::fixtures::{MockIo, TEST_TREE};
+    use nut_shell::config::DefaultConfig;
+    use nut_shell::shell::CliState;
+    use nut_shell::{CliError, CommandHandler, Response, Shell, ShellAction};
+
+    /// `echo` requests the action named by its first argument.
+    struct ActionHandler;
+
+    impl CommandHandler<DefaultConfig> for ActionHandler {
+        fn execute_sync(
+            &self,
+            _id: &str,
+            args: &[&str],
+        ) -> Result<Response<DefaultConfig>, CliError> {
+            let action = match args.first().copied() {
+                Some("logout") => ShellAction::Logout,
+                Some("deactivate") => ShellAction::Deactivate,
+                Some("clear") => ShellAction::ClearScreen,
+                _ => ShellAction::None,
+            };
+            Ok(Response::success("done").with_action(action))
+        }
+
+        #[cfg(feature = "async")]
+        async fn execute_async(
+            &self,
+            id: &str,
+            args: &[&str],
+            _cancel: &nut_shell::CancelToken,
+        ) -> Result<Response<DefaultConfig>, CliError> {
+            self.execute_sync(id, args)
+        }
+    }
+
+    fn run(
+        shell: &mut Shell<
+            '_,
+            super::helpers::fixtures::MockAccessLevel,
+            MockIo,
+            ActionHandler,
+            DefaultConfig,
+        >,
+        line: &str,
+    ) -> heapless::String<1024> {
+        shell.io_mut().clear_output();
+        for c in line.chars() {
+            shell.process_char(c).unwrap();
+        }
+        shell.io_mut().output()
+    }
+
+    #[test]
+    fn test_response_actions() {
+        let mut shell = Shell::new(&TEST_TREE, ActionHandler, MockIo::new());
+        shell.activate().unwrap();
+
+        // Clear happens before the message, prompt follows as usual
+        let output = run(&mut shell, "echo clear\n");
+        assert!(output.contains("\x1b[2J\x1b[Hdone"), "{:?}", output);
+        assert!(output.ends_with("@/> "), "{:?}", output);
+
+        // Logout needs authentication; without it the prompt is shown as usual
+        let output = run(&mut shell, "echo logout\n");
+        assert!(output.ends_with("@/> "), "{:?}", output);
+        assert_eq!(shell.state(), CliState::LoggedIn);
+
+        // Deactivate ends the session after the message, without a prompt
+        let output = run(&mut shell, "echo deactivate\n");
+        assert!(output.contains("done"), "{:?}", output);
+        assert!(output.contains("Goodbye."), "{:?}", output);
+        assert!(!output.ends_with("@/> "), "{:?}", output);
+        assert_eq!(shell.state(), CliState::Inactive);
+    }
+}
+
+// ============================================================================
+// Rate Limit Tests
+// ============================================================================
+
+#[cfg(not(feature = "authentication"))]
+mod rate_limit {
+    use super::fixtures::{MockAccessLevel, MockIo, cmd};
+    use core::cell::Cell;
+    use nut_shell::config::DefaultConfig;
+    use nut_shell::shell::CommandHandler;
+    use nut_shell::{CliError, CommandMeta, Directory, Node, Response, Shell};
+
+    const SELFTEST: CommandMeta<MockAccessLevel> =
+        cmd("selftest", MockAccessLevel::Guest).with_min_interval_ms(1000);
+    const STATUS: CommandMeta<MockAccessLevel> = cmd("status", MockAccessLevel::Guest);
+
+    const ROOT: Directory<MockAccessLevel> = Directory::new(
+        "/",
+        &[Node::Command(&SELFTEST), Node::Command(&STATUS)],
+        MockAccessLevel::Guest,
+    );
+
+    /// Responds with the command id.
+    struct Named;
+
+    impl CommandHandler<DefaultConfig> for Named {
+        fn execute_sync(
+            &self,
+            id: &str,
+            _args: &[&str],
+        ) -> Result<Response<DefaultConfig>, CliError> {
+            Ok(Response::success(id))
+        }
+
+        #[cfg(feature = "async")]
+        async fn execute_async(
+            &self,
+            id: &str,
+            args: &[&str],
+            _cancel: &nut_shell::CancelToken,
+        ) -> Result<Response<DefaultConfig>, CliError> {
+            self.execute_sync(id, args)
+        }
+    }
+
+    #[test]
+    fn test_rerun_within_interval_rejected() {
+        let now = Cell::new(5_000u32);
+        let clock = || now.get();
+        let mut shell: Shell<_, _, _, DefaultConfig> = Shell::new(&ROOT, Named, MockIo::new());
+        shell.activate().unwrap();
+
+        // Without a clock nothing is limited
+        assert!(shell.execute_line("selftest").is_ok());
+        assert!(shell.execute_line("selftest").is_ok());
+
+        shell.set_clock(&clock);
+        assert!(shell.execute_line("selftest").is_ok());
+        now.set(5_400);
+        assert_eq!(
+            shell.execute_line("selftest").unwrap_err(),
+            CliError::RateLimited { retry_in_ms: 600 }
+        );
+
+        // Unlimited commands are unaffected
+        assert!(shell.execute_line("status").is_ok());
+        assert!(shell.execute_line("status").is_ok());
+
+        now.set(6_000);
+        assert!(shell.execute_line("selftest").is_ok());
+    }
+}
+
+// ============================================================================
+// Anonymous Refusal Tests
+// ============================================================================
+
+#[cfg(not(feature = "authentication"))]
+mod requires_auth {
+    use super::helpers::fixtures::{MockAccessLevel, MockHandler, MockIo, cmd};
+    use nut_shell::config::DefaultConfig;
+    use nut_shell::{CliError, CommandMeta, Directory, Node, Shell};
+
+    const FACTORY_RESET: CommandMeta<MockAccessLevel> =
+        cmd("factory-reset", MockAccessLevel::Guest).requiring_auth();
+    const HELP: CommandMeta<MockAccessLevel> = cmd("help", MockAccessLevel::Guest);
+
+    const ROOT: Directory<MockAccessLevel> = Directory::new(
+        "/",
+        &[Node::Command(&FACTORY_RESET), Node::Command(&HELP)],
+        MockAccessLevel::Guest,
+    );
+
+    #[test]
+    fn test_anonymous_build_refuses_command() {
+        let mut shell: Shell<_, _, _, DefaultConfig> =
+            Shell::new(&ROOT, MockHandler, MockIo::new());
+        shell.activate().unwrap();
+
+        assert_eq!(
+            shell.execute_line("factory-reset").unwrap_err(),
+            CliError::AuthRequired
+        );
+        assert!(shell.execute_line("help").is_ok());
+
+        shell.io_mut().clear_output();
+        for c in "factory-reset\n".chars() {
+            shell.process_char(c).unwrap();
+        }
+        let output = shell.io_mut().output();
+        assert!(output.contains("Authentication required"), "{}", output);
+    }
+}
+
+// ============================================================================
+// Command Chaining Tests
+// ============================================================================
+
+#[cfg(not(feature = "authentication"))]
+mod chain {
+    use super::helpers::fixtures::{MockAccessLevel, MockIo, TEST_TREE, test_config};
+    use nut_shell::shell::CommandHandler;
+    use nut_shell::{CliError, Response, Shell, ShellConfig};
+
+    test_config!(ChainConfig {
+        const CHAIN_SEPARATOR: Option<char> = Some(';');
+    });
+    test_config!(ContinueConfig {
+        const CHAIN_SEPARATOR: Option<char> = Some(';');
+        const CHAIN_CONTINUE_ON_ERROR: bool = true;
+    });
+
+    /// Responds with the command id and its arguments.
+    struct Reply;
+
+    impl<C: ShellConfig> CommandHandler<C> for Reply {
+        fn execute_sync(&self, id: &str, args: &[&str]) -> Result<Response<C>, CliError> {
+            Ok(Response::success(&format!("{}:{}", id, args.join(","))))
+        }
+
+        #[cfg(feature = "async")]
+        async fn execute_async(
+            &self,
+            id: &str,
+            args: &[&str],
+            _cancel: &nut_shell::CancelToken,
+        ) -> Result<Response<C>, CliError> {
+            self.execute_sync(id, args)
+        }
+    }
+
+    fn run<C: ShellConfig>(
+        shell: &mut Shell<'_, MockAccessLevel, MockIo, Reply, C>,
+        line: &str,
+    ) -> String {
+        shell.io_mut().clear_output();
+        for c in line.chars().chain(['\n']) {
+            shell.process_char(c).unwrap();
+        }
+        shell.io().output().to_string()
+    }
+
+    #[test]
+    fn test_chain_runs_segments_in_order() {
+        let mut shell: Shell<_, _, _, ChainConfig> = Shell::new(&TEST_TREE, Reply, MockIo::new());
+        shell.activate().unwrap();
+
+        let output = run(&mut shell, r#"echo a; system; status;/echo "b;c""#);
+        assert_eq!(
+            output,
+            "echo a; system; status;/echo \"b;c\"\r\necho:a\r\nstatus:\r\necho:b;c\r\n@/system> ",
+            "{:?}",
+            output
+        );
+
+        #[cfg(feature = "history")]
+        {
+            let state = shell.export_session();
+            let entries: Vec<(&str, bool)> = state.history.entries().collect();
+            assert_eq!(entries, [(r#"echo a; system; status;/echo "b;c""#, true)]);
+        }
+    }
+
+    #[test]
+    fn test_chain_stops_at_first_error() {
+        let mut shell: Shell<_, _, _, ChainConfig> = Shell::new(&TEST_TREE, Reply, MockIo::new());
+        shell.activate().unwrap();
+
+        let output = run(&mut shell, "echo 1; nope; echo 2");
+        assert!(output.contains("echo:1"), "{:?}", output);
+        assert!(output.contains("Error: Command not found"), "{:?}", output);
+        assert!(!output.contains("echo:2"), "{:?}", output);
+        assert_eq!(shell.last_exit_code(), 1);
+
+        #[cfg(feature = "history")]
+        {
+            let state = shell.export_session();
+            let entries: Vec<(&str, bool)> = state.history.entries().collect();
+            assert_eq!(entries, [("echo 1; nope; echo 2", false)]);
+        }
+
+        let mut shell: Shell<_, _, _, ContinueConfig> =
+            Shell::new(&TEST_TREE, Reply, MockIo::new());
+        shell.activate().unwrap();
+
+        let output = run(&mut shell, "echo 1; nope; echo 2");
+        assert!(output.contains("Error: Command not found"), "{:?}", output);
+        assert!(output.ends_with("echo:2\r\n@/> "), "{:?}", output);
+        assert_eq!(shell.last_exit_code(), 0);
+    }
+}
+
+// ============================================================================
+// Error Code Tests
+// ============================================================================
+
+#[cfg(not(feature = "authentication"))]
+mod error_codes {
+    use super::helpers::fixtures::{MockHandler, MockIo, TEST_TREE, test_config};
+    use nut_shell::config::{DefaultConfig, ShellConfig};
+    use nut_shell::{CliError, CommandHandler, Response, Shell};
+
+    test_config!(CodeConfig {
+        const SHOW_ERROR_CODES: bool = true;
+    });
+
+    /// Never reached: only resolution errors are exercised.
+    struct NoopHandler;
+
+    impl<C: ShellConfig> CommandHandler<C> for NoopHandler {
+        fn execute_sync(&self, _id: &str, _args: &[&str]) -> Result<Response<C>, CliError> {
+            Ok(Response::success(""))
+        }
+
+        #[cfg(feature = "async")]
+        async fn execute_async(
+            &self,
+            id: &str,
+            args: &[&str],
+            _cancel: &nut_shell::CancelToken,
+        ) -> Result<Response<C>, CliError> {
+            self.execute_sync(id, args)
+        }
+    }
+
+    #[test]
+    fn test_error_code_prefix() {
+        let mut shell: Shell<_, _, _, CodeConfig> =
+            Shell::new(&TEST_TREE, NoopHandler, MockIo::new());
+        shell.activate().unwrap();
+        shell.io_mut().clear_output();
+        for c in "nonexistent\n".chars() {
+            shell.process_char(c).unwrap();
+        }
+        let output = shell.io().output();
+        assert!(
+            output.contains("Error[0x0001]: Command not found\r\n"),
+            "{:?}",
+            output
+        );
+        assert_eq!(CliError::CommandNotFound.code(), 0x0001);
+
+        // Off by default
+        let mut shell: Shell<_, _, _, DefaultConfig> =
+            Shell::new(&TEST_TREE, MockHandler, MockIo::new());
+        shell.activate().unwrap();
+        for c in "nonexistent\n".chars() {
+            shell.process_char(c).unwrap();
+        }
+        assert!(shell.io().output().contains("  Error: Command not found"));
+    }
+}
+
+// ============================================================================
+// Output Formatting Config Tests
+// ============================================================================
+
+#[cfg(not(feature = "authentication"))]
+mod output_config {
+    use super::helpers::fixtures::{MockAccessLevel, MockIo, TEST_TREE, test_config};
+    use nut_shell::shell::CommandHandler;
+    use nut_shell::{CliError, Response, Shell, ShellConfig};
+
+    test_config!(StampConfig {
+        const TIMESTAMP_OUTPUT: bool = true;
+    });
+    test_config!(SortConfig {
+        const SORT_LISTINGS: bool = true;
+    });
+    test_config!(TabConfig {
+        const INDENT: &'static str = "\t";
+    });
+    test_config!(LfConfig {
+        const LINE_ENDING: &'static str = "\n";
+    });
+
+    /// `echo` replies with its arguments, one indented line each.
+    struct LinesHandler;
+
+    impl<C: ShellConfig> CommandHandler<C> for LinesHandler {
+        fn execute_sync(&self, _id: &str, args: &[&str]) -> Result<Response<C>, CliError> {
+            let response = Response::success(&args.join("\r\n"));
+            Ok(if args.len() > 1 {
+                response.indented()
+            } else {
+                response
+            })
+        }
+
+        #[cfg(feature = "async")]
+        async fn execute_async(
+            &self,
+            id: &str,
+            args: &[&str],
+            _cancel: &nut_shell::CancelToken,
+        ) -> Result<Response<C>, CliError> {
+            self.execute_sync(id, args)
+        }
+    }
+
+    fn run<C: ShellConfig>(
+        shell: &mut Shell<'_, MockAccessLevel, MockIo, LinesHandler, C>,
+        line: &str,
+    ) -> String {
+        for c in line.chars() {
+            shell.process_char(c).unwrap();
+        }
+        shell.io_mut().clear_output();
+        shell.process_char('\n').unwrap();
+        shell.io().output().to_string()
+    }
+
+    fn shell<C: ShellConfig>() -> Shell<'static, MockAccessLevel, MockIo, LinesHandler, C> {
+        let mut shell = Shell::new(&TEST_TREE, LinesHandler, MockIo::new());
+        shell.activate().unwrap();
+        shell
+    }
+
+    #[test]
+    fn test_table_rows_use_line_ending() {
+        let mut table: nut_shell::response::Table<'_, 2, 2> = nut_shell::response::Table::new();
+        table.push_row(["a", "1"]).unwrap();
+        table.push_row(["bb", "2"]).unwrap();
+        let out = table.render::<LfConfig, 32>().unwrap();
+        assert_eq!(out.as_str(), "a   1\nbb  2");
+    }
+
+    #[test]
+    fn test_timestamp_prefix() {
+        let clock = || 1234;
+        let mut shell = shell::<StampConfig>();
+
+        // No clock set - no prefix
+        assert_eq!(run(&mut shell, "echo ok"), "\r\nok\r\n@/> ");
+
+        shell.set_clock(&clock);
+        assert_eq!(run(&mut shell, "echo ok"), "\r\n[1234] ok\r\n@/> ");
+
+        // Empty responses stay unstamped
+        assert_eq!(run(&mut shell, "echo"), "\r\n\r\n@/> ");
+    }
+
+    #[test]
+    fn test_ls_sorted() {
+        let mut shell = shell::<SortConfig>();
+
+        let output = run(&mut shell, "ls");
+        let names: Vec<&str> = output
+            .split("\r\n")
+            .filter_map(|line| line.strip_prefix("  "))
+            .map(|line| line.split("  - ").next().unwrap())
+            .collect();
+        assert!(names.len() > 2, "{:?}", output);
+        assert!(names.is_sorted(), "{:?}", names);
+
+        // Glob filtering still applies
+        let output = run(&mut shell, "ls test-*");
+        assert!(!output.contains("echo"), "{:?}", output);
+        assert!(output.contains("  test-indented  - "), "{:?}", output);
+    }
+
+    #[test]
+    fn test_custom_indent() {
+        let mut shell = shell::<TabConfig>();
+
+        assert_eq!(
+            run(&mut shell, "echo L1 L2 L3"),
+            "\r\n\tL1\r\n\tL2\r\n\tL3\r\n@/> "
+        );
+
+        // Listings and help use the same indent
+        let mut output = run(&mut shell, "ls");
+        output.push_str(&run(&mut shell, "?"));
+        for line in output
+            .split("\r\n")
+            .filter(|l| !l.is_empty() && !l.starts_with('@'))
+        {
+            assert!(
+                line.starts_with('\t') && !line.starts_with("\t "),
+                "{:?}",
+                line
+            );
+        }
+    }
+}
@@ -46,6 +46,23 @@ fn test_backspace_sequence() {
     helpers::assert_contains_ansi(&output, "\x08"); // Backspace again (VT100 sequence)
 }
 
+#[test]
+#[cfg(not(feature = "authentication"))]
+fn test_backspace_erases_wide_character() {
+    let mut shell = helpers::create_test_shell();
+
+    helpers::type_input(&mut shell, "a中");
+    shell.io_mut().clear_output();
+
+    // Double-width glyph needs two erase groups
+    helpers::press_backspace(&mut shell);
+    assert_eq!(shell.io_mut().output(), "\x08 \x08\x08 \x08");
+
+    shell.io_mut().clear_output();
+    helpers::press_backspace(&mut shell);
+    assert_eq!(shell.io_mut().output(), "\x08 \x08");
+}
+
 #[test]
 #[cfg(not(feature = "authentication"))]
 fn test_backspace_until_empty() {
@@ -167,6 +184,218 @@ fn test_double_esc_clears_and_shows_prompt() {
     helpers::assert_prompt(&output, "@/>");
 }
 
+#[test]
+#[cfg(not(feature = "authentication"))]
+fn test_lone_esc_clears_after_timeout() {
+    let mut shell = helpers::create_test_shell();
+
+    // No pending ESC - nothing happens
+    helpers::type_input(&mut shell, "echo test");
+    shell.io_mut().clear_output();
+    shell.escape_timeout().unwrap();
+    assert_eq!(shell.io_mut().output(), "");
+
+    shell.process_char('\x1b').unwrap();
+    shell.escape_timeout().unwrap();
+    let output = shell.io_mut().output();
+    helpers::assert_contains_ansi(&output, "\x1b[K");
+    helpers::assert_prompt(&output, "@/>");
+
+    // Buffer is empty and the decoder is back to normal input
+    shell.io_mut().clear_output();
+    helpers::press_enter(&mut shell);
+    helpers::assert_contains_none(&shell.io_mut().output(), &["test"]);
+}
+
+// ============================================================================
+// Echo Control Tests
+// ============================================================================
+
+#[test]
+#[cfg(not(feature = "authentication"))]
+fn test_echo_off_suppresses_input_but_not_output() {
+    let mut shell = helpers::create_test_shell();
+    shell.set_echo(false);
+
+    helpers::type_input(&mut shell, "echo hix");
+    helpers::press_backspace(&mut shell);
+    assert_eq!(shell.io_mut().output(), "");
+
+    helpers::press_enter(&mut shell);
+    let output = shell.io_mut().output();
+    assert!(!output.contains("echo"), "{:?}", output);
+    assert!(output.contains("hi"), "{:?}", output);
+    assert!(output.ends_with("@/> "), "{:?}", output);
+
+    // Back on: keystrokes echo again
+    shell.set_echo(true);
+    shell.io_mut().clear_output();
+    helpers::type_input(&mut shell, "ls");
+    assert_eq!(shell.io_mut().output(), "ls");
+}
+
+#[cfg(not(feature = "authentication"))]
+mod echo_input {
+    use super::helpers::fixtures::{MockIo, TEST_TREE, test_config};
+    use nut_shell::config::ShellConfig;
+    use nut_shell::{CliError, CommandHandler, Response, Shell};
+
+    test_config!(PaintedConfig {
+        const ECHO_INPUT: bool = false;
+    });
+
+    struct OkHandler;
+
+    impl<C: ShellConfig> CommandHandler<C> for OkHandler {
+        fn execute_sync(&self, _id: &str, _args: &[&str]) -> Result<Response<C>, CliError> {
+            Ok(Response::success("OK"))
+        }
+
+        #[cfg(feature = "async")]
+        async fn execute_async(
+            &self,
+            id: &str,
+            args: &[&str],
+            _cancel: &nut_shell::CancelToken,
+        ) -> Result<Response<C>, CliError> {
+            self.execute_sync(id, args)
+        }
+    }
+
+    #[test]
+    fn test_front_end_reads_line_instead_of_echo() {
+        let mut shell: Shell<_, _, _, PaintedConfig> =
+            Shell::new(&TEST_TREE, OkHandler, MockIo::new());
+        shell.activate().unwrap();
+        shell.io_mut().clear_output();
+
+        for c in "echp\u{7f}o hä".chars() {
+            shell.process_char(c).unwrap();
+        }
+        assert_eq!(shell.io_mut().output(), "");
+        assert_eq!(shell.input_line(), "echo hä");
+        assert_eq!(shell.cursor_position(), 7);
+
+        shell.process_char('\n').unwrap();
+        assert_eq!(shell.input_line(), "");
+        assert_eq!(shell.cursor_position(), 0);
+
+        // Recalled history is not redrawn either, only the prompt
+        #[cfg(feature = "history")]
+        {
+            shell.io_mut().clear_output();
+            for c in "\x1b[A".chars() {
+                shell.process_char(c).unwrap();
+            }
+            assert_eq!(shell.input_line(), "echo hä");
+            assert!(shell.io_mut().output().ends_with("@/> "));
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "authentication")]
+fn test_echo_off_skips_password_mask() {
+    let mut shell = helpers::create_auth_shell();
+    shell.set_echo(false);
+
+    helpers::type_input_auth(&mut shell, "admin:admin123");
+    assert_eq!(shell.io_mut().output(), "");
+}
+
+// ============================================================================
+// Event Filter Tests
+// ============================================================================
+
+#[cfg(not(feature = "authentication"))]
+mod event_filter {
+    use super::helpers;
+    use nut_shell::shell::InputEvent;
+    use std::cell::RefCell;
+
+    std::thread_local! {
+        static SEEN: RefCell<Vec<InputEvent>> = const { RefCell::new(Vec::new()) };
+    }
+
+    /// Claims Tab and double-ESC for the surrounding UI, passes everything else.
+    fn claim_tab_and_esc(event: InputEvent) -> bool {
+        SEEN.with(|seen| seen.borrow_mut().push(event));
+        !matches!(event, InputEvent::Tab | InputEvent::DoubleEsc)
+    }
+
+    #[test]
+    fn test_filter_consumes_claimed_events() {
+        let mut shell = helpers::create_test_shell();
+        shell.set_event_filter(&claim_tab_and_esc);
+
+        helpers::type_input(&mut shell, "ec");
+        shell.process_char('\t').unwrap();
+        helpers::press_double_esc(&mut shell);
+        // Neither completion nor line clear happened
+        assert_eq!(shell.io_mut().output(), "ec");
+
+        helpers::type_input(&mut shell, "ho hi");
+        helpers::press_enter(&mut shell);
+        assert!(shell.io_mut().output().contains("hi\r\n"));
+
+        let seen = SEEN.with(|seen| seen.borrow_mut().drain(..).collect::<Vec<_>>());
+        assert_eq!(
+            seen[..3],
+            [
+                InputEvent::Char('e'),
+                InputEvent::Char('c'),
+                InputEvent::Tab
+            ]
+        );
+        // Partial escape sequences are not reported, only the decoded event
+        assert_eq!(seen[3], InputEvent::DoubleEsc);
+        assert_eq!(seen.last(), Some(&InputEvent::Enter));
+    }
+}
+
+// ============================================================================
+// Ctrl+C Line Discard Tests
+// ============================================================================
+
+#[test]
+#[cfg(not(feature = "authentication"))]
+fn test_ctrl_c_discards_line() {
+    let mut shell = helpers::create_test_shell();
+
+    helpers::type_input(&mut shell, "echo test");
+    shell.io_mut().clear_output();
+
+    shell.process_char('\x03').unwrap();
+    let output = shell.io_mut().output();
+    assert_eq!(output.as_str(), "^C\r\n@/> ");
+
+    // Buffer was discarded - Enter runs nothing
+    shell.io_mut().clear_output();
+    shell.process_char('\n').unwrap();
+    helpers::assert_contains_none(&shell.io_mut().output(), &["test"]);
+}
+
+#[test]
+#[cfg(feature = "authentication")]
+fn test_ctrl_c_keeps_login_state() {
+    use nut_shell::CliState;
+
+    let mut shell = helpers::create_auth_shell();
+    helpers::type_input_auth(&mut shell, "admin:adm");
+    shell.io_mut().clear_output();
+    shell.process_char('\x03').unwrap();
+    assert!(shell.io_mut().output().ends_with("^C\r\nLogin> "));
+    assert_eq!(shell.state(), CliState::LoggedOut);
+
+    helpers::execute_command_auth(&mut shell, "admin:admin123");
+    helpers::type_input_auth(&mut shell, "system/reb");
+    shell.io_mut().clear_output();
+    shell.process_char('\x03').unwrap();
+    assert!(shell.io_mut().output().ends_with("^C\r\nadmin@/> "));
+    assert_eq!(shell.state(), CliState::LoggedIn);
+    assert_eq!(shell.current_username(), Some("admin"));
+}
+
 // ============================================================================
 // Buffer Overflow Handling Tests
 // ============================================================================
@@ -215,6 +444,163 @@ fn test_bell_on_buffer_overflow() {
     );
 }
 
+#[test]
+#[cfg(not(feature = "authentication"))]
+fn test_input_too_long_message_shown_once() {
+    use nut_shell::config::{DefaultConfig, ShellConfig};
+
+    let mut shell = helpers::create_test_shell();
+    let line = "a".repeat(128);
+    helpers::type_input(&mut shell, &line);
+    shell.io_mut().clear_output();
+
+    helpers::type_input(&mut shell, "xyz");
+    let output = shell.io_mut().output();
+    assert_eq!(output.matches(DefaultConfig::MSG_INPUT_TOO_LONG).count(), 1);
+    assert_eq!(helpers::count_char(&output, '\x07'), 3);
+
+    // Line is redrawn below the message for further editing
+    assert!(
+        output.ends_with(&format!("@/> {}\x07\x07", line)),
+        "{:?}",
+        output
+    );
+}
+
+/// Handler usable with any config (fixture handler is tied to `DefaultConfig`).
+#[cfg(not(feature = "authentication"))]
+struct ConfigHandler;
+
+#[cfg(not(feature = "authentication"))]
+impl<C: nut_shell::ShellConfig> nut_shell::shell::CommandHandler<C> for ConfigHandler {
+    fn execute_sync(
+        &self,
+        _id: &str,
+        args: &[&str],
+    ) -> Result<nut_shell::Response<C>, nut_shell::CliError> {
+        let mut msg = heapless::String::<32>::new();
+        core::fmt::write(&mut msg, format_args!("{} args", args.len())).unwrap();
+        Ok(nut_shell::Response::success(&msg))
+    }
+
+    #[cfg(feature = "async")]
+    async fn execute_async(
+        &self,
+        id: &str,
+        args: &[&str],
+        _cancel: &nut_shell::CancelToken,
+    ) -> Result<nut_shell::Response<C>, nut_shell::CliError> {
+        self.execute_sync(id, args)
+    }
+}
+
+#[test]
+#[cfg(all(feature = "history", not(feature = "authentication")))]
+fn test_minimal_config_limits_history_size() {
+    use nut_shell::Shell;
+    use nut_shell::config::MinimalConfig;
+
+    let mut shell: Shell<_, _, _, MinimalConfig> =
+        Shell::new(&fixtures::TEST_TREE, ConfigHandler, fixtures::MockIo::new());
+    shell.activate().unwrap();
+
+    for i in 1..=5 {
+        for c in format!("echo {}\n", i).chars() {
+            shell.process_char(c).unwrap();
+        }
+    }
+
+    // MinimalConfig::HISTORY_SIZE (4): "echo 1" was evicted, oldest is "echo 2"
+    for _ in 0..4 {
+        for c in "\x1b[A".chars() {
+            shell.process_char(c).unwrap();
+        }
+    }
+    shell.io_mut().clear_output();
+    for c in "\x1b[A".chars() {
+        shell.process_char(c).unwrap();
+    }
+    let output = shell.io().output();
+    assert!(output.contains("echo 2"), "{:?}", output);
+}
+
+#[test]
+#[cfg(not(feature = "authentication"))]
+fn test_minimal_config_limits_input_length() {
+    use nut_shell::Shell;
+    use nut_shell::config::{MinimalConfig, ShellConfig};
+
+    let mut shell: Shell<_, _, _, MinimalConfig> =
+        Shell::new(&fixtures::TEST_TREE, ConfigHandler, fixtures::MockIo::new());
+    shell.activate().unwrap();
+    shell.io_mut().clear_output();
+
+    // MinimalConfig::MAX_INPUT (64) is enforced
+    for c in "a".repeat(MinimalConfig::MAX_INPUT).chars() {
+        shell.process_char(c).unwrap();
+    }
+    assert_eq!(helpers::count_char(&shell.io().output(), '\x07'), 0);
+
+    shell.process_char('x').unwrap();
+    assert_eq!(helpers::count_char(&shell.io().output(), '\x07'), 1);
+}
+
+#[test]
+#[cfg(not(feature = "authentication"))]
+fn test_minimal_config_caps_argument_count() {
+    use nut_shell::Shell;
+    use nut_shell::config::MinimalConfig;
+
+    let mut shell: Shell<_, _, _, MinimalConfig> =
+        Shell::new(&fixtures::TEST_TREE, ConfigHandler, fixtures::MockIo::new());
+    shell.activate().unwrap();
+
+    // echo accepts up to 16 args, but MinimalConfig::MAX_ARGS is 8
+    shell.io_mut().clear_output();
+    for c in "echo 1 2 3 4 5 6 7 8\n".chars() {
+        shell.process_char(c).unwrap();
+    }
+    assert!(shell.io().output().contains("8 args"));
+
+    shell.io_mut().clear_output();
+    for c in "echo 1 2 3 4 5 6 7 8 9\n".chars() {
+        shell.process_char(c).unwrap();
+    }
+    let output = shell.io().output();
+    assert!(
+        output.contains("Expected 0-8 arguments, got 9"),
+        "{}",
+        output
+    );
+}
+
+#[test]
+#[cfg(not(feature = "authentication"))]
+fn test_quoted_argument_counts_as_one() {
+    use nut_shell::Shell;
+    use nut_shell::config::DefaultConfig;
+
+    let mut shell: Shell<_, _, _, DefaultConfig> =
+        Shell::new(&fixtures::TEST_TREE, ConfigHandler, fixtures::MockIo::new());
+    shell.activate().unwrap();
+
+    shell.io_mut().clear_output();
+    for c in "echo \"hello world\" x\\ y\n".chars() {
+        shell.process_char(c).unwrap();
+    }
+    assert!(shell.io().output().contains("2 args"));
+
+    shell.io_mut().clear_output();
+    for c in "echo \"oops\n".chars() {
+        shell.process_char(c).unwrap();
+    }
+    assert!(
+        shell.io().output().contains("Error: Unterminated quote"),
+        "{}",
+        shell.io().output()
+    );
+}
+
 // ============================================================================
 // Terminal Behavior Documentation Tests
 // ============================================================================
@@ -232,6 +618,16 @@ fn test_prompt_format() {
     helpers::assert_prompt(&output, "@/>");
 }
 
+#[test]
+#[cfg(not(feature = "authentication"))]
+fn test_crlf_enter_runs_once() {
+    let mut shell = helpers::create_test_shell();
+
+    helpers::type_input(&mut shell, "echo hi\r\n");
+    let output = shell.io_mut().output();
+    assert_eq!(output.matches("@/> ").count(), 1, "{:?}", output);
+}
+
 #[test]
 #[cfg(not(feature = "authentication"))]
 fn test_prompt_updates_with_navigation() {
@@ -246,3 +642,354 @@ fn test_prompt_updates_with_navigation() {
     let output = shell.io_mut().output();
     helpers::assert_prompt(&output, "@/system>");
 }
+
+// ============================================================================
+// Line Ending Tests
+// ============================================================================
+
+// Config emitting bare `\n` line endings (raw pipes).
+#[cfg(not(feature = "authentication"))]
+fixtures::test_config!(LfConfig {
+    const LINE_ENDING: &'static str = "\n";
+});
+
+#[test]
+#[cfg(not(feature = "authentication"))]
+fn test_configured_line_ending_used_everywhere() {
+    use nut_shell::Shell;
+
+    let mut shell: Shell<_, _, _, LfConfig> =
+        Shell::new(&fixtures::TEST_TREE, ConfigHandler, fixtures::MockIo::new());
+    shell.activate().unwrap();
+
+    for line in ["?\n", "ls\n", "echo a b\n", "nonexistent\n"] {
+        shell.io_mut().clear_output();
+        for c in line.chars() {
+            shell.process_char(c).unwrap();
+        }
+        let output = shell.io().output();
+        assert!(output.contains('\n'), "{:?}", output);
+        assert!(!output.contains('\r'), "No CR expected: {:?}", output);
+    }
+}
+
+// ============================================================================
+// Bell Mode Tests
+// ============================================================================
+
+// Config with visual bell (reverse-video flash).
+#[cfg(not(feature = "authentication"))]
+fixtures::test_config!(VisualBellConfig {
+    const BELL: nut_shell::BellMode = nut_shell::BellMode::Visual;
+    const MSG_INPUT_TOO_LONG: &'static str = ""; // Bell only
+});
+
+#[test]
+#[cfg(not(feature = "authentication"))]
+fn test_visual_bell_replaces_bel() {
+    use nut_shell::Shell;
+
+    let mut shell: Shell<_, _, _, VisualBellConfig> =
+        Shell::new(&fixtures::TEST_TREE, ConfigHandler, fixtures::MockIo::new());
+    shell.activate().unwrap();
+
+    for _ in 0..<VisualBellConfig as nut_shell::ShellConfig>::MAX_INPUT {
+        shell.process_char('a').unwrap();
+    }
+    shell.io_mut().clear_output();
+
+    // Buffer full
+    shell.process_char('e').unwrap();
+    assert_eq!(shell.io().output().as_str(), "\x1b[?5h\x1b[?5l");
+}
+
+// ============================================================================
+// Prompt Path Truncation Tests
+// ============================================================================
+
+// Config eliding prompt paths longer than 14 characters.
+#[cfg(not(feature = "authentication"))]
+fixtures::test_config!(ShortPromptConfig {
+    const MAX_PROMPT_PATH: usize = 14;
+});
+
+#[cfg(not(feature = "authentication"))]
+mod deep_tree {
+    use super::fixtures::MockAccessLevel;
+    use nut_shell::{Directory, Node};
+
+    const fn dir(
+        name: &'static str,
+        children: &'static [Node<MockAccessLevel>],
+    ) -> Directory<MockAccessLevel> {
+        Directory::new(name, children, MockAccessLevel::Guest)
+    }
+
+    const LEDS: Directory<MockAccessLevel> = dir("leds", &[]);
+    const GPIO: Directory<MockAccessLevel> = dir("gpio", &[Node::Directory(&LEDS)]);
+    const HARDWARE: Directory<MockAccessLevel> = dir("hardware", &[Node::Directory(&GPIO)]);
+    const SYS: Directory<MockAccessLevel> = dir("sys", &[Node::Directory(&HARDWARE)]);
+    const LONG: Directory<MockAccessLevel> = dir("bbbbbbbbbbbbb", &[]);
+    const A: Directory<MockAccessLevel> = dir("a", &[Node::Directory(&LONG)]);
+    pub const ROOT: Directory<MockAccessLevel> =
+        dir("/", &[Node::Directory(&SYS), Node::Directory(&A)]);
+}
+
+#[test]
+#[cfg(not(feature = "authentication"))]
+fn test_long_prompt_path_is_elided() {
+    use nut_shell::Shell;
+
+    let mut shell: Shell<_, _, _, ShortPromptConfig> =
+        Shell::new(&deep_tree::ROOT, ConfigHandler, fixtures::MockIo::new());
+    shell.activate().unwrap();
+
+    // Short paths are untouched
+    for c in "sys/hardware\n".chars() {
+        shell.process_char(c).unwrap();
+    }
+    assert!(shell.io().output().ends_with("@/sys/hardware> "));
+
+    // Middle segments give way, the final segment stays
+    shell.io_mut().clear_output();
+    for c in "gpio/leds\n".chars() {
+        shell.process_char(c).unwrap();
+    }
+    assert!(
+        shell.io().output().ends_with("@/sys/.../leds> "),
+        "{:?}",
+        shell.io().output()
+    );
+
+    // A segment shorter than `...` is kept, even over the limit
+    shell.io_mut().clear_output();
+    for c in "/a/bbbbbbbbbbbbb\n".chars() {
+        shell.process_char(c).unwrap();
+    }
+    assert!(
+        shell.io().output().ends_with("@/a/bbbbbbbbbbbbb> "),
+        "{:?}",
+        shell.io().output()
+    );
+}
+
+// ============================================================================
+// Byte Input (UTF-8) Tests
+// ============================================================================
+
+#[test]
+#[cfg(not(feature = "authentication"))]
+fn test_process_bytes_handles_split_sequences() {
+    let mut shell = helpers::create_test_shell();
+
+    // `é` and an up-arrow sequence split across packets
+    shell.process_bytes(b"echo \xc3").unwrap();
+    shell.process_bytes(b"\xa9 ok\n\x1b[").unwrap();
+    let output = shell.io().output();
+    assert!(output.contains("é ok"), "{}", output);
+
+    // Completed up-arrow recalls the line (no stray `A` typed)
+    shell.io_mut().clear_output();
+    shell.process_bytes(b"A").unwrap();
+    assert!(!shell.io().output().ends_with('A'));
+    #[cfg(feature = "history")]
+    assert!(shell.io().output().ends_with("echo é ok"));
+}
+
+#[test]
+#[cfg(not(feature = "authentication"))]
+fn test_process_byte_decodes_utf8() {
+    let mut shell = helpers::create_test_shell();
+
+    // "echo é" as raw bytes - `é` is two bytes (0xC3 0xA9)
+    for &b in "echo é".as_bytes() {
+        shell.process_byte(b).unwrap();
+    }
+    assert_eq!(
+        shell.io().output(),
+        "echo é",
+        "Multibyte char echoed intact"
+    );
+
+    shell.io_mut().clear_output();
+    shell.process_byte(b'\n').unwrap();
+    let output = shell.io().output();
+    assert!(output.contains("é"), "{}", output);
+}
+
+#[test]
+#[cfg(not(feature = "authentication"))]
+fn test_process_byte_invalid_utf8_does_not_corrupt_buffer() {
+    let mut shell = helpers::create_test_shell();
+
+    // Stray continuation byte becomes U+FFFD, backspace removes it as one char
+    for &b in b"ab\x80" {
+        shell.process_byte(b).unwrap();
+    }
+    assert_eq!(shell.io().output(), "ab\u{FFFD}");
+
+    helpers::press_backspace(&mut shell);
+    shell.io_mut().clear_output();
+    for &b in b" x\n" {
+        shell.process_byte(b).unwrap();
+    }
+    let output = shell.io().output();
+    helpers::assert_contains_none(&output, &["\u{FFFD}"]);
+}
+
+// ============================================================================
+// Bracketed Paste Tests
+// ============================================================================
+
+#[test]
+#[cfg(not(feature = "authentication"))]
+fn test_bracketed_paste_runs_each_line() {
+    let mut shell = helpers::create_test_shell();
+
+    helpers::type_input(&mut shell, "\x1b[200~echo one\r\n\r\necho two\r\n\x1b[201~");
+    let output = shell.io().output();
+
+    // Each line echoed as a unit, then executed; blank lines and CR LF pairs skipped
+    assert_eq!(
+        output, "echo one\r\none\r\n@/> echo two\r\ntwo\r\n@/> ",
+        "{:?}",
+        output
+    );
+}
+
+#[test]
+#[cfg(not(feature = "authentication"))]
+fn test_bracketed_paste_partial_line_left_for_editing() {
+    let mut shell = helpers::create_test_shell();
+
+    helpers::type_input(&mut shell, "\x1b[200~echo\tpasted\x1b[201~");
+    // Tab inside a paste is whitespace, not completion; nothing runs without Enter
+    assert_eq!(shell.io().output(), "echo pasted");
+
+    shell.io_mut().clear_output();
+    helpers::type_input(&mut shell, "!\n");
+    let output = shell.io().output();
+    assert!(output.contains("pasted!"), "{:?}", output);
+}
+
+#[test]
+#[cfg(not(feature = "authentication"))]
+fn test_bracketed_paste_backspace() {
+    let mut shell = helpers::create_test_shell();
+
+    // Backspacing into typed text erases it on screen
+    helpers::type_input(&mut shell, "echo abc");
+    shell.io_mut().clear_output();
+    helpers::type_input(&mut shell, "\x1b[200~\x7f\x7fx\n");
+    let output = shell.io().output();
+    assert_eq!(output, "\x08 \x08\x08 \x08x\r\nax\r\n@/> ", "{:?}", output);
+
+    // Pasted text not yet echoed is removed silently
+    shell.io_mut().clear_output();
+    helpers::type_input(&mut shell, "yz\x7f\x1b[201~");
+    assert_eq!(shell.io().output(), "y");
+}
+
+// ============================================================================
+// Quote Escape Tests
+// ============================================================================
+
+#[cfg(not(feature = "authentication"))]
+mod quote_escapes {
+    use super::fixtures::{MockIo, TEST_TREE, test_config};
+    use nut_shell::config::DefaultConfig;
+    use nut_shell::shell::CommandHandler;
+    use nut_shell::{CliError, Response, Shell, ShellConfig};
+
+    test_config!(EscapeConfig {
+        const QUOTE_ESCAPES: bool = true;
+    });
+
+    /// Reports the arguments it received, debug-formatted.
+    struct ArgsHandler;
+
+    impl<C: ShellConfig> CommandHandler<C> for ArgsHandler {
+        fn execute_sync(&self, _id: &str, args: &[&str]) -> Result<Response<C>, CliError> {
+            Ok(Response::success(&format!("{:?}", args)))
+        }
+
+        #[cfg(feature = "async")]
+        async fn execute_async(
+            &self,
+            id: &str,
+            args: &[&str],
+            _cancel: &nut_shell::CancelToken,
+        ) -> Result<Response<C>, CliError> {
+            self.execute_sync(id, args)
+        }
+    }
+
+    const LINE: &str = r#"echo "a\tb\x21" c\n"#;
+
+    #[test]
+    fn test_quote_escapes_opt_in() {
+        let mut shell: Shell<_, _, _, EscapeConfig> =
+            Shell::new(&TEST_TREE, ArgsHandler, MockIo::new());
+        let response = shell.execute_line(LINE).unwrap();
+        assert_eq!(response.message.as_str(), r#"["a\tb!", "cn"]"#);
+
+        let mut shell: Shell<_, _, _, DefaultConfig> =
+            Shell::new(&TEST_TREE, ArgsHandler, MockIo::new());
+        let response = shell.execute_line(LINE).unwrap();
+        assert_eq!(response.message.as_str(), r#"["atbx21", "cn"]"#);
+    }
+}
+
+// ============================================================================
+// Argument Delimiter Tests
+// ============================================================================
+
+#[cfg(not(feature = "authentication"))]
+mod arg_delimiter {
+    use super::fixtures::{MockIo, TEST_TREE, test_config};
+    use nut_shell::config::DefaultConfig;
+    use nut_shell::shell::CommandHandler;
+    use nut_shell::{CliError, Response, Shell, ShellConfig};
+
+    test_config!(CommaConfig {
+        const ARG_DELIMITER: char = ',';
+    });
+
+    /// Reports the arguments it received, debug-formatted.
+    struct ArgsHandler;
+
+    impl<C: ShellConfig> CommandHandler<C> for ArgsHandler {
+        fn execute_sync(&self, _id: &str, args: &[&str]) -> Result<Response<C>, CliError> {
+            Ok(Response::success(&format!("{:?}", args)))
+        }
+
+        #[cfg(feature = "async")]
+        async fn execute_async(
+            &self,
+            id: &str,
+            args: &[&str],
+            _cancel: &nut_shell::CancelToken,
+        ) -> Result<Response<C>, CliError> {
+            self.execute_sync(id, args)
+        }
+    }
+
+    const LINE: &str = r#"echo,on,"a,b""#;
+
+    #[test]
+    fn test_arg_delimiter_splits_path_and_args() {
+        let mut shell: Shell<_, _, _, CommaConfig> =
+            Shell::new(&TEST_TREE, ArgsHandler, MockIo::new());
+        let response = shell.execute_line(LINE).unwrap();
+        assert_eq!(response.message.as_str(), r#"["on", "a,b"]"#);
+
+        // Spaces still separate
+        let response = shell.execute_line("echo on off").unwrap();
+        assert_eq!(response.message.as_str(), r#"["on", "off"]"#);
+
+        // Default config treats the comma as part of the path
+        let mut shell: Shell<_, _, _, DefaultConfig> =
+            Shell::new(&TEST_TREE, ArgsHandler, MockIo::new());
+        assert_eq!(shell.execute_line(LINE), Err(CliError::CommandNotFound));
+    }
+}
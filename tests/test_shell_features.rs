@@ -75,6 +75,28 @@ fn test_tab_with_no_matches() {
     );
 }
 
+#[test]
+#[cfg(all(feature = "completion", not(feature = "authentication")))]
+fn test_tab_silent_once_command_has_all_args() {
+    let mut shell = helpers::create_test_shell();
+    helpers::execute_command(&mut shell, "system/network");
+
+    // Still room for arguments: falls back to name completion (no match, bell)
+    helpers::type_input(&mut shell, "ping host1 ");
+    shell.io_mut().clear_output();
+    helpers::press_tab(&mut shell);
+    assert!(shell.io_mut().output().contains('\x07'));
+
+    // max_args reached: Tab does nothing
+    helpers::type_input(&mut shell, "host2");
+    shell.io_mut().clear_output();
+    helpers::press_tab(&mut shell);
+    assert_eq!(shell.io_mut().output(), "");
+
+    let output = helpers::execute_command(&mut shell, "");
+    assert!(!output.contains("Error"), "{}", output);
+}
+
 #[test]
 #[cfg(all(feature = "completion", not(feature = "authentication")))]
 fn test_tab_completion_in_subdirectory() {
@@ -116,6 +138,29 @@ fn test_tab_completes_directory_with_slash() {
     );
 }
 
+#[test]
+#[cfg(all(feature = "completion", not(feature = "authentication")))]
+fn test_tab_completes_global_command() {
+    let mut shell = helpers::create_test_shell();
+    helpers::execute_command(&mut shell, "system");
+
+    helpers::type_input(&mut shell, "cl");
+    helpers::press_tab(&mut shell);
+    assert_eq!(shell.input_line(), "clear");
+
+    // Globals mix with the directory's children
+    let mut shell = helpers::create_test_shell();
+    helpers::type_input(&mut shell, "e");
+    shell.io_mut().clear_output();
+    helpers::press_tab(&mut shell);
+    let output = shell.io_mut().output();
+    assert!(
+        output.contains("echo") && output.contains("exit"),
+        "{}",
+        output
+    );
+}
+
 // ============================================================================
 // History Navigation Tests (requires history feature)
 // ============================================================================
@@ -309,6 +354,19 @@ fn test_history_after_failed_command() {
     );
 }
 
+#[test]
+#[cfg(all(feature = "history", not(feature = "authentication")))]
+fn test_history_records_command_outcome() {
+    let mut shell = helpers::create_test_shell();
+
+    helpers::execute_command(&mut shell, "echo ok");
+    helpers::execute_command(&mut shell, "nonexistent");
+
+    let state = shell.export_session();
+    let entries: Vec<(&str, bool)> = state.history.entries().collect();
+    assert_eq!(entries, [("echo ok", true), ("nonexistent", false)]);
+}
+
 #[test]
 #[cfg(all(feature = "history", not(feature = "authentication")))]
 fn test_history_edit_recalled_command() {
@@ -339,6 +397,63 @@ fn test_history_edit_recalled_command() {
     );
 }
 
+#[cfg(all(feature = "history", not(feature = "authentication")))]
+mod history_wrap {
+    use super::helpers::fixtures::{MockAccessLevel, MockIo, TEST_TREE, test_config};
+    use nut_shell::shell::CommandHandler;
+    use nut_shell::{CliError, Response, Shell, ShellConfig};
+
+    test_config!(WrapConfig {
+        const HISTORY_WRAP: bool = true;
+    });
+
+    struct OkHandler;
+
+    impl<C: ShellConfig> CommandHandler<C> for OkHandler {
+        fn execute_sync(&self, _id: &str, _args: &[&str]) -> Result<Response<C>, CliError> {
+            Ok(Response::success("ok"))
+        }
+
+        #[cfg(feature = "async")]
+        async fn execute_async(
+            &self,
+            id: &str,
+            args: &[&str],
+            _cancel: &nut_shell::CancelToken,
+        ) -> Result<Response<C>, CliError> {
+            self.execute_sync(id, args)
+        }
+    }
+
+    fn press(shell: &mut Shell<'_, MockAccessLevel, MockIo, OkHandler, WrapConfig>, key: char) {
+        for c in ['\x1b', '[', key] {
+            shell.process_char(c).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_history_wrap() {
+        let mut shell: Shell<_, _, _, WrapConfig> =
+            Shell::new(&TEST_TREE, OkHandler, MockIo::new());
+        shell.activate().unwrap();
+        for c in "echo first\necho second\n".chars() {
+            shell.process_char(c).unwrap();
+        }
+
+        // Up: second, first, empty line, then around to second again
+        for expected in ["echo second", "echo first", "", "echo second"] {
+            press(&mut shell, 'A');
+            assert_eq!(shell.input_line(), expected);
+        }
+
+        // Down: empty line, then around to first
+        for expected in ["", "echo first"] {
+            press(&mut shell, 'B');
+            assert_eq!(shell.input_line(), expected);
+        }
+    }
+}
+
 // ============================================================================
 // Async Command Execution Tests (requires async feature)
 // ============================================================================
@@ -392,3 +507,546 @@ async fn test_async_command_with_arguments() {
     let output = shell.io_mut().output();
     assert!(output.contains("Waited 250ms"));
 }
+
+// ============================================================================
+// Async Cancellation Tests (requires async feature)
+// ============================================================================
+
+/// Async handler whose command yields until cancelled (bounded).
+#[cfg(all(feature = "async", not(feature = "authentication")))]
+struct CancellableHandler;
+
+#[cfg(all(feature = "async", not(feature = "authentication")))]
+impl nut_shell::CommandHandler<nut_shell::config::DefaultConfig> for CancellableHandler {
+    fn execute_sync(
+        &self,
+        _id: &str,
+        _args: &[&str],
+    ) -> Result<nut_shell::Response<nut_shell::config::DefaultConfig>, nut_shell::CliError> {
+        Err(nut_shell::CliError::CommandNotFound)
+    }
+
+    async fn execute_async(
+        &self,
+        _id: &str,
+        _args: &[&str],
+        cancel: &nut_shell::CancelToken,
+    ) -> Result<nut_shell::Response<nut_shell::config::DefaultConfig>, nut_shell::CliError> {
+        for _ in 0..10 {
+            cancel.check()?;
+            tokio::task::yield_now().await;
+        }
+        Ok(nut_shell::Response::success("Completed"))
+    }
+}
+
+#[tokio::test]
+#[cfg(all(feature = "async", not(feature = "authentication")))]
+async fn test_async_command_cancelled_via_shared_token() {
+    let token = nut_shell::CancelToken::new();
+    let mut shell = Shell::new(&TEST_TREE, CancellableHandler, MockIo::new());
+    shell.set_cancel_token(&token);
+    shell.activate().unwrap();
+
+    for c in "system\n".chars() {
+        shell.process_char_async(c).await.unwrap();
+    }
+    for c in "async-wait".chars() {
+        shell.process_char_async(c).await.unwrap();
+    }
+    shell.io_mut().clear_output();
+
+    // Cancel from a concurrent task while the command is awaiting
+    let (result, ()) = tokio::join!(shell.process_char_async('\n'), async {
+        tokio::task::yield_now().await;
+        token.cancel();
+    });
+    result.unwrap();
+
+    let output = shell.io_mut().output();
+    assert!(output.contains("Cancelled"), "Output: {}", output);
+    assert!(!output.contains("Completed"));
+}
+
+#[tokio::test]
+#[cfg(all(feature = "async", not(feature = "authentication")))]
+async fn test_stale_cancel_request_cleared_before_dispatch() {
+    let mut shell = Shell::new(&TEST_TREE, CancellableHandler, MockIo::new());
+    shell.activate().unwrap();
+
+    for c in "system\n".chars() {
+        shell.process_char_async(c).await.unwrap();
+    }
+    shell.request_cancel();
+    assert!(shell.cancel_token().is_cancelled());
+    shell.io_mut().clear_output();
+
+    for c in "async-wait\n".chars() {
+        shell.process_char_async(c).await.unwrap();
+    }
+
+    let output = shell.io_mut().output();
+    assert!(output.contains("Completed"), "Output: {}", output);
+}
+
+// ============================================================================
+// Async Timeout Tests (requires async feature)
+// ============================================================================
+
+#[cfg(all(feature = "async", not(feature = "authentication")))]
+mod async_timeout {
+    use super::*;
+    use core::cell::Cell;
+    use core::task::{Context, Poll};
+    use helpers::fixtures::test_config;
+    use nut_shell::config::{DefaultConfig, ShellConfig};
+    use nut_shell::{CancelToken, CliError, CommandHandler, Response, Timer};
+
+    test_config!(TimeoutConfig {
+        const ASYNC_TIMEOUT_MS: u32 = 500;
+    });
+
+    /// `async-wait hang` never completes; anything else completes immediately.
+    struct HangHandler;
+
+    impl<C: ShellConfig> CommandHandler<C> for HangHandler {
+        fn execute_sync(&self, _id: &str, _args: &[&str]) -> Result<Response<C>, CliError> {
+            Err(CliError::CommandNotFound)
+        }
+
+        async fn execute_async(
+            &self,
+            _id: &str,
+            args: &[&str],
+            _cancel: &CancelToken,
+        ) -> Result<Response<C>, CliError> {
+            if args.first() == Some(&"hang") {
+                core::future::pending::<()>().await;
+            }
+            Ok(Response::success("Completed"))
+        }
+    }
+
+    /// Timer that either expires on first poll or never.
+    struct TestTimer {
+        expires: bool,
+        armed_ms: Cell<u32>,
+    }
+
+    impl TestTimer {
+        fn new(expires: bool) -> Self {
+            Self {
+                expires,
+                armed_ms: Cell::new(0),
+            }
+        }
+    }
+
+    impl Timer for TestTimer {
+        fn start(&self, ms: u32) {
+            self.armed_ms.set(ms);
+        }
+
+        fn poll_expired(&self, _cx: &mut Context<'_>) -> Poll<()> {
+            if self.expires {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        }
+    }
+
+    async fn run<C: ShellConfig>(
+        shell: &mut Shell<'_, MockAccessLevel, MockIo, HangHandler, C>,
+        line: &str,
+    ) {
+        for c in line.chars() {
+            shell.process_char_async(c).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hung_async_command_times_out() {
+        let timer = TestTimer::new(true);
+        let mut shell: Shell<_, _, _, TimeoutConfig> =
+            Shell::new(&TEST_TREE, HangHandler, MockIo::new());
+        shell.set_timer(&timer);
+        shell.activate().unwrap();
+
+        run(&mut shell, "system\n").await;
+        shell.io_mut().clear_output();
+        run(&mut shell, "async-wait hang\n").await;
+
+        assert_eq!(timer.armed_ms.get(), 500);
+        let output = shell.io_mut().output();
+        assert!(output.contains("Timeout"), "Output: {}", output);
+
+        // Shell remains usable after the command future is dropped
+        shell.io_mut().clear_output();
+        run(&mut shell, "async-wait\n").await;
+        assert!(shell.io_mut().output().contains("Completed"));
+    }
+
+    #[tokio::test]
+    async fn test_async_command_completes_before_timeout() {
+        let timer = TestTimer::new(false);
+        let mut shell: Shell<_, _, _, TimeoutConfig> =
+            Shell::new(&TEST_TREE, HangHandler, MockIo::new());
+        shell.set_timer(&timer);
+        shell.activate().unwrap();
+
+        run(&mut shell, "system\n").await;
+        shell.io_mut().clear_output();
+        run(&mut shell, "async-wait\n").await;
+
+        let output = shell.io_mut().output();
+        assert!(output.contains("Completed"), "Output: {}", output);
+    }
+
+    #[tokio::test]
+    async fn test_command_future_dropped_leaves_shell_usable() {
+        let mut shell: Shell<_, _, _, DefaultConfig> =
+            Shell::new(&TEST_TREE, HangHandler, MockIo::new());
+        shell.activate().unwrap();
+
+        run(&mut shell, "system\n").await;
+        run(&mut shell, "async-wait hang").await;
+
+        // Abandon the hung command (e.g. an outer select) - must not wedge the shell
+        tokio::select! {
+            biased;
+            _ = shell.process_char_async('\n') => panic!("hung command completed"),
+            _ = tokio::task::yield_now() => {}
+        }
+
+        shell.io_mut().clear_output();
+        run(&mut shell, "async-wait\n").await;
+        assert!(shell.io_mut().output().contains("Completed"));
+    }
+
+    #[tokio::test]
+    async fn test_timer_ignored_when_timeout_disabled() {
+        let timer = TestTimer::new(true);
+        let mut shell: Shell<_, _, _, DefaultConfig> =
+            Shell::new(&TEST_TREE, HangHandler, MockIo::new());
+        shell.set_timer(&timer);
+        shell.activate().unwrap();
+
+        run(&mut shell, "system\n").await;
+        run(&mut shell, "async-wait\n").await;
+
+        // DefaultConfig::ASYNC_TIMEOUT_MS is 0, so the timer is never armed
+        assert_eq!(timer.armed_ms.get(), 0);
+        assert!(shell.io_mut().output().contains("Completed"));
+    }
+}
+
+// ============================================================================
+// Streamed Response Tests
+// ============================================================================
+
+#[cfg(not(feature = "authentication"))]
+struct StreamHandler;
+
+#[cfg(not(feature = "authentication"))]
+fn emit_lines(emit: &mut dyn FnMut(&str) -> Result<(), ()>) -> Result<(), ()> {
+    for i in 0..20 {
+        let mut line = heapless::String::<32>::new();
+        core::fmt::write(&mut line, format_args!("line {:02} ----------\r\n", i)).unwrap();
+        emit(&line)?;
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "authentication"))]
+impl nut_shell::shell::CommandHandler<nut_shell::config::DefaultConfig> for StreamHandler {
+    fn execute_sync(
+        &self,
+        _id: &str,
+        args: &[&str],
+    ) -> Result<nut_shell::Response<nut_shell::config::DefaultConfig>, nut_shell::CliError> {
+        let response = nut_shell::Response::streamed(emit_lines);
+        Ok(if args.is_empty() {
+            response
+        } else {
+            response.indented()
+        })
+    }
+
+    #[cfg(feature = "async")]
+    async fn execute_async(
+        &self,
+        id: &str,
+        args: &[&str],
+        _cancel: &nut_shell::CancelToken,
+    ) -> Result<nut_shell::Response<nut_shell::config::DefaultConfig>, nut_shell::CliError> {
+        self.execute_sync(id, args)
+    }
+}
+
+#[test]
+#[cfg(not(feature = "authentication"))]
+fn test_streamed_response_exceeds_max_response() {
+    let mut shell = Shell::new(&TEST_TREE, StreamHandler, MockIo::new());
+    shell.activate().unwrap();
+    shell.io_mut().clear_output();
+
+    for c in "echo\n".chars() {
+        shell.process_char(c).unwrap();
+    }
+
+    let output = shell.io_mut().output();
+    assert!(output.len() > 256, "Output: {}", output);
+    assert!(output.contains("line 00 ----------\r\nline 01"));
+    assert!(output.contains("line 19 ----------\r\n"));
+}
+
+#[test]
+#[cfg(not(feature = "authentication"))]
+fn test_streamed_response_indented() {
+    let mut shell = Shell::new(&TEST_TREE, StreamHandler, MockIo::new());
+    shell.activate().unwrap();
+    shell.io_mut().clear_output();
+
+    for c in "echo x\n".chars() {
+        shell.process_char(c).unwrap();
+    }
+
+    let output = shell.io_mut().output();
+    assert!(output.contains("  line 00 ----------\r\n  line 01"));
+    // Trailing line ending is dropped like buffered indented responses
+    assert!(!output.contains("  \r\n"), "Output: {}", output);
+    assert!(
+        output.contains("line 19 ----------\r\n@/>"),
+        "Output: {}",
+        output
+    );
+}
+
+// ============================================================================
+// Paging Tests
+// ============================================================================
+
+#[cfg(all(feature = "paging", not(feature = "authentication")))]
+mod paging {
+    use super::helpers::fixtures::{MockAccessLevel, MockIo, TEST_TREE, test_config};
+    use nut_shell::config::DefaultConfig;
+    use nut_shell::shell::CommandHandler;
+    use nut_shell::{CliError, Response, Shell, ShellConfig};
+
+    test_config!(PageConfig {
+        const PAGE_SIZE: usize = 2;
+    });
+
+    /// `echo long` replies with five indented lines, `echo stream` streams them, and
+    /// `echo page` returns exactly one page.
+    struct PageHandler;
+
+    fn five_lines(emit: &mut dyn FnMut(&str) -> Result<(), ()>) -> Result<(), ()> {
+        for line in ["S1\r\n", "S2\r\n", "S3\r\n", "S4\r\n", "S5\r\n"] {
+            emit(line)?;
+        }
+        Ok(())
+    }
+
+    impl<C: ShellConfig> CommandHandler<C> for PageHandler {
+        fn execute_sync(&self, _id: &str, args: &[&str]) -> Result<Response<C>, CliError> {
+            match args.first() {
+                Some(&"long") => Ok(Response::success("L1\r\nL2\r\nL3\r\nL4\r\nL5").indented()),
+                Some(&"stream") => Ok(Response::streamed(five_lines).indented()),
+                _ => Ok(Response::success("a\r\nb\r\n")),
+            }
+        }
+
+        #[cfg(feature = "async")]
+        async fn execute_async(
+            &self,
+            id: &str,
+            args: &[&str],
+            _cancel: &nut_shell::CancelToken,
+        ) -> Result<Response<C>, CliError> {
+            self.execute_sync(id, args)
+        }
+    }
+
+    type PageShell = Shell<'static, MockAccessLevel, MockIo, PageHandler, PageConfig>;
+
+    fn page_shell() -> PageShell {
+        let mut shell = Shell::new(&TEST_TREE, PageHandler, MockIo::new());
+        shell.activate().unwrap();
+        shell
+    }
+
+    /// Type `line`, then press Enter with a cleared output.
+    fn run(shell: &mut PageShell, line: &str) -> String {
+        for c in line.chars() {
+            shell.process_char(c).unwrap();
+        }
+        press(shell, '\n')
+    }
+
+    fn press(shell: &mut PageShell, key: char) -> String {
+        shell.io_mut().clear_output();
+        shell.process_char(key).unwrap();
+        shell.io().output().to_string()
+    }
+
+    #[test]
+    fn test_long_response_paged() {
+        let mut shell = page_shell();
+        assert_eq!(
+            run(&mut shell, "echo long"),
+            "\r\n  L1\r\n  L2\r\n-- More --"
+        );
+
+        // Other keys are swallowed; space shows the next page, the last one ends with the prompt
+        assert_eq!(press(&mut shell, 'x'), "");
+        assert_eq!(press(&mut shell, ' '), "\r\x1b[K  L3\r\n  L4\r\n-- More --");
+        assert_eq!(press(&mut shell, ' '), "\r\x1b[K  L5\r\n@/> ");
+
+        // q drops the rest
+        run(&mut shell, "echo long");
+        assert_eq!(press(&mut shell, 'q'), "\r\x1b[K@/> ");
+
+        // Keys reach the line editor again
+        assert_eq!(press(&mut shell, 'x'), "x");
+        shell.process_char('\x08').unwrap();
+
+        // Exactly a page (trailing line ending included) never pauses
+        assert_eq!(run(&mut shell, "echo page"), "\r\na\r\nb\r\n\r\n@/> ");
+    }
+
+    #[test]
+    fn test_streamed_response_paged() {
+        let mut shell = page_shell();
+        assert_eq!(
+            run(&mut shell, "echo stream"),
+            "\r\n  S1\r\n  S2\r\n-- More --"
+        );
+        assert_eq!(press(&mut shell, ' '), "\r\x1b[K  S3\r\n  S4\r\n-- More --");
+        assert_eq!(press(&mut shell, ' '), "\r\x1b[K  S5\r\n@/> ");
+    }
+
+    #[test]
+    fn test_ls_paged() {
+        let mut shell = page_shell();
+        let output = run(&mut shell, "ls");
+        assert!(output.ends_with("-- More --"), "{output:?}");
+        assert_eq!(output.matches("  - ").count(), 2);
+
+        // Pages continue where the last stopped, and the last one ends with the prompt
+        let mut listing = output.replace("-- More --", "");
+        loop {
+            let page = press(&mut shell, ' ');
+            let page = page.strip_prefix("\r\x1b[K").unwrap();
+            match page.strip_suffix("-- More --") {
+                Some(rest) => listing.push_str(rest),
+                None => {
+                    listing.push_str(page.strip_suffix("@/> ").unwrap());
+                    break;
+                }
+            }
+        }
+
+        let mut unpaged: Shell<'static, MockAccessLevel, MockIo, PageHandler, DefaultConfig> =
+            Shell::new(&TEST_TREE, PageHandler, MockIo::new());
+        unpaged.activate().unwrap();
+        for c in "ls\n".chars() {
+            unpaged.process_char(c).unwrap();
+        }
+        assert!(unpaged.io().output().ends_with(&format!("ls{listing}@/> ")));
+
+        // q ends the listing early
+        run(&mut shell, "ls");
+        assert_eq!(press(&mut shell, 'q'), "\r\x1b[K@/> ");
+    }
+}
+
+// ============================================================================
+// Dynamic Command Registration Tests (requires dynamic feature)
+// ============================================================================
+
+#[cfg(all(feature = "dynamic", not(feature = "authentication")))]
+static CMD_PROBE: nut_shell::CommandMeta<MockAccessLevel> = nut_shell::CommandMeta::new(
+    "status", // Reuses MockHandler's "status" implementation
+    "probe",
+    MockAccessLevel::Guest,
+    nut_shell::CommandKind::Sync,
+)
+.with_description("Runtime-registered probe");
+
+#[test]
+#[cfg(all(feature = "dynamic", not(feature = "authentication")))]
+fn test_registered_command_executes_and_lists() {
+    let mut shell = Shell::new(&TEST_TREE, MockHandler, MockIo::new());
+    shell.register_command(&CMD_PROBE, "/system").unwrap();
+    shell.activate().unwrap();
+
+    // Resolves by absolute path from anywhere
+    shell.io_mut().clear_output();
+    for c in "system/probe\n".chars() {
+        shell.process_char(c).unwrap();
+    }
+    assert!(shell.io_mut().output().contains("System OK"));
+
+    // Listed after the const children of its directory
+    for c in "system\n".chars() {
+        shell.process_char(c).unwrap();
+    }
+    shell.io_mut().clear_output();
+    for c in "ls\n".chars() {
+        shell.process_char(c).unwrap();
+    }
+    let output = shell.io_mut().output();
+    let probe = output.find("probe  - Runtime-registered probe").unwrap();
+    assert!(output.find("reboot").unwrap() < probe);
+
+    // Relative resolution inside the directory
+    shell.io_mut().clear_output();
+    for c in "probe\n".chars() {
+        shell.process_char(c).unwrap();
+    }
+    assert!(shell.io_mut().output().contains("System OK"));
+}
+
+#[test]
+#[cfg(all(feature = "dynamic", not(feature = "authentication")))]
+fn test_registered_command_scoped_to_parent() {
+    let mut shell = Shell::new(&TEST_TREE, MockHandler, MockIo::new());
+    shell.register_command(&CMD_PROBE, "system").unwrap();
+    shell.activate().unwrap();
+    shell.io_mut().clear_output();
+
+    // Not visible at root
+    for c in "probe\n".chars() {
+        shell.process_char(c).unwrap();
+    }
+    assert!(shell.io_mut().output().contains("Command not found"));
+}
+
+#[test]
+#[cfg(all(feature = "dynamic", not(feature = "authentication")))]
+fn test_register_command_errors() {
+    use nut_shell::CliError;
+
+    let mut shell = Shell::new(&TEST_TREE, MockHandler, MockIo::new());
+
+    // Parent must be an existing directory
+    assert_eq!(
+        shell.register_command(&CMD_PROBE, "/missing"),
+        Err(CliError::CommandNotFound)
+    );
+    assert_eq!(
+        shell.register_command(&CMD_PROBE, "/system/reboot"),
+        Err(CliError::NotADirectory { name: "reboot" })
+    );
+
+    // Bounded by capacity::DYNAMIC_COMMANDS
+    for _ in 0..nut_shell::config::capacity::DYNAMIC_COMMANDS {
+        shell.register_command(&CMD_PROBE, "/").unwrap();
+    }
+    assert_eq!(
+        shell.register_command(&CMD_PROBE, "/"),
+        Err(CliError::BufferFull)
+    );
+}
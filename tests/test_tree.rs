@@ -10,6 +10,8 @@ use fixtures::{MockAccessLevel, MockHandler, TEST_TREE};
 use nut_shell::shell::handler::CommandHandler;
 use nut_shell::tree::{CommandKind, Node};
 
+#[cfg(feature = "async")]
+use nut_shell::CancelToken;
 #[cfg(feature = "async")]
 use nut_shell::error::CliError;
 
@@ -80,21 +82,22 @@ fn test_async_command_in_tree() {
 async fn test_async_command_execution() {
     // Validates that async commands execute correctly through handler
     let handler = MockHandler;
+    let cancel = CancelToken::new();
 
     // Test async-wait with no args
-    let result = handler.execute_async("async-wait", &[]).await;
+    let result = handler.execute_async("async-wait", &[], &cancel).await;
     assert!(result.is_ok());
     let response = result.unwrap();
     assert!(response.message.as_str().contains("Waited 100ms"));
 
     // Test async-wait with custom duration
-    let result = handler.execute_async("async-wait", &["250"]).await;
+    let result = handler.execute_async("async-wait", &["250"], &cancel).await;
     assert!(result.is_ok());
     let response = result.unwrap();
     assert!(response.message.as_str().contains("Waited 250ms"));
 
     // Test unknown async command
-    let result = handler.execute_async("unknown-async", &[]).await;
+    let result = handler.execute_async("unknown-async", &[], &cancel).await;
     assert_eq!(result, Err(CliError::CommandNotFound));
 }
 
@@ -188,3 +191,39 @@ fn test_varied_argument_counts() {
     assert_eq!(CMD_HW_LED.min_args, 1);
     assert_eq!(CMD_HW_LED.max_args, 1);
 }
+
+// ============================================================================
+// Compile-Time Validation Tests
+// ============================================================================
+
+// Fails the build if TEST_TREE ever gains a duplicate id or bad arg range
+nut_shell::validate_tree!(TEST_TREE);
+
+#[test]
+fn test_fixture_tree_is_valid() {
+    assert_eq!(nut_shell::tree::validate_tree(&TEST_TREE), Ok(()));
+}
+
+// ============================================================================
+// Tree Traversal Tests
+// ============================================================================
+
+#[test]
+fn test_walk_fixture_tree() {
+    let mut walk = TEST_TREE.walk::<{ nut_shell::config::capacity::PATH_DEPTH }>();
+
+    let (depth, _, path) = walk
+        .by_ref()
+        .find(|(_, node, _)| node.name() == "led")
+        .expect("led command in tree");
+    assert_eq!(depth, 2);
+    assert_eq!(path.as_slice(), &["system", "hardware", "led"]);
+
+    // Every command in the tree is reachable by walking
+    let commands = TEST_TREE
+        .walk::<8>()
+        .filter(|(_, node, _)| node.is_command())
+        .count();
+    assert!(commands > 10, "found {} commands", commands);
+    assert!(!walk.is_truncated());
+}